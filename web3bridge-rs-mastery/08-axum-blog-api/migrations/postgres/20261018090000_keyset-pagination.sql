@@ -0,0 +1,4 @@
+-- Composite indexes backing keyset pagination on (created_at, id)
+CREATE INDEX IF NOT EXISTS idx_posts_created_at_id ON posts(created_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_posts_author_id_created_at_id ON posts(author_id, created_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_authors_created_at_id ON authors(created_at DESC, id DESC);
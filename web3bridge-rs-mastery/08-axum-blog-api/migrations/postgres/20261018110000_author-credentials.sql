@@ -0,0 +1,2 @@
+-- Argon2 PHC-format password hash; NULL for authors created before login existed
+ALTER TABLE authors ADD COLUMN IF NOT EXISTS password_hash TEXT;
@@ -0,0 +1,22 @@
+-- Create comments table (threaded via parent_id, moderated via status)
+CREATE TABLE IF NOT EXISTS comments (
+    id UUID PRIMARY KEY,
+    post_id UUID NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    parent_id UUID REFERENCES comments(id) ON DELETE CASCADE,
+    author_id UUID REFERENCES authors(id) ON DELETE CASCADE,
+    guest_name VARCHAR(255),
+    depth INTEGER NOT NULL DEFAULT 0 CHECK (depth >= 0),
+    body TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'approved', 'rejected')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (author_id IS NOT NULL OR guest_name IS NOT NULL)
+);
+
+-- Index for loading a post's comment thread
+CREATE INDEX IF NOT EXISTS idx_comments_post_id_created_at ON comments(post_id, created_at);
+
+-- Indexes so cascades from a parent comment or an author stay cheap
+CREATE INDEX IF NOT EXISTS idx_comments_parent_id ON comments(parent_id);
+CREATE INDEX IF NOT EXISTS idx_comments_author_id ON comments(author_id);
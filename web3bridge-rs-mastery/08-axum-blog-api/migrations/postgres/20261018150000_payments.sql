@@ -0,0 +1,31 @@
+-- Raw payment-provider webhook events, stored once per (provider, event_id)
+CREATE TABLE IF NOT EXISTS payment_events (
+    id UUID PRIMARY KEY,
+    provider TEXT NOT NULL,
+    event_id TEXT NOT NULL,
+    event_type TEXT NOT NULL,
+    payload JSONB NOT NULL,
+    received_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    processed_at TIMESTAMPTZ,
+    UNIQUE (provider, event_id)
+);
+
+-- Subscriptions mirrored from the provider; customer_email links them to authors
+CREATE TABLE IF NOT EXISTS subscriptions (
+    id UUID PRIMARY KEY,
+    provider TEXT NOT NULL,
+    subscription_code TEXT NOT NULL UNIQUE,
+    customer_email VARCHAR(255) NOT NULL,
+    plan_code TEXT,
+    status TEXT NOT NULL
+        CHECK (status IN ('active', 'non-renewing', 'attention', 'cancelled')),
+    next_payment_date TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Index for entitlement checks by email
+CREATE INDEX IF NOT EXISTS idx_subscriptions_customer_email ON subscriptions(customer_email);
+
+-- Premium posts are only readable by subscribers
+ALTER TABLE posts ADD COLUMN IF NOT EXISTS is_premium BOOLEAN NOT NULL DEFAULT FALSE;
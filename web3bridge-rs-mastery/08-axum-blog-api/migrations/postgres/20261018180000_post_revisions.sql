@@ -0,0 +1,17 @@
+-- Snapshot of a post's title and content after every create, update and restore
+CREATE TABLE IF NOT EXISTS post_revisions (
+    id UUID PRIMARY KEY,
+    post_id UUID NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    revision INTEGER NOT NULL CHECK (revision >= 1),
+    title VARCHAR(255) NOT NULL,
+    content TEXT NOT NULL,
+    -- Set when this revision was produced by restoring an older one
+    restored_from INTEGER,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (post_id, revision)
+);
+
+-- Existing posts start their history at revision 1 with their current text
+INSERT INTO post_revisions (id, post_id, revision, title, content, created_at)
+SELECT gen_random_uuid(), id, 1, title, content, updated_at FROM posts
+ON CONFLICT (post_id, revision) DO NOTHING;
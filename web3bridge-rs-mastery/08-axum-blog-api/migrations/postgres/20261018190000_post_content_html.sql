@@ -0,0 +1,3 @@
+-- Sanitized HTML rendered from the Markdown in content whenever a post is written.
+-- Rows written before this column existed stay NULL until their next edit and are rendered on read.
+ALTER TABLE posts ADD COLUMN IF NOT EXISTS content_html TEXT;
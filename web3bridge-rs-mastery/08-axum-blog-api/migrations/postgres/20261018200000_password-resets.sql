@@ -0,0 +1,10 @@
+-- One-time tokens an operator issues so an author can set a password without signing in;
+-- only a SHA-256 of each token is kept
+CREATE TABLE IF NOT EXISTS password_resets (
+    token_hash TEXT PRIMARY KEY,
+    author_id UUID NOT NULL REFERENCES authors(id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_password_resets_author ON password_resets(author_id);
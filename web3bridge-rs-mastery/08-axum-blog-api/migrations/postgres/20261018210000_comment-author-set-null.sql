@@ -0,0 +1,9 @@
+-- Deleting an author keeps their comments, shown as "[deleted author]", so other people's
+-- replies under them survive instead of cascading away with the thread
+ALTER TABLE comments DROP CONSTRAINT IF EXISTS comments_author_id_fkey;
+ALTER TABLE comments
+    ADD CONSTRAINT comments_author_id_fkey
+    FOREIGN KEY (author_id) REFERENCES authors(id) ON DELETE SET NULL;
+
+-- Such comments have neither an author nor a guest name
+ALTER TABLE comments DROP CONSTRAINT IF EXISTS comments_check;
@@ -0,0 +1,18 @@
+-- The author each provider customer pays for, taken from the author_id a checkout puts in
+-- charge metadata; entitlement follows this link rather than the unverified author email
+CREATE TABLE IF NOT EXISTS payment_customers (
+    provider TEXT NOT NULL,
+    customer_code TEXT NOT NULL,
+    author_id UUID NOT NULL REFERENCES authors(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (provider, customer_code)
+);
+
+CREATE INDEX IF NOT EXISTS idx_payment_customers_author_id ON payment_customers(author_id);
+
+-- Subscriptions are matched to customers by code; rows stored before this stay unlinked
+-- until their next event
+ALTER TABLE subscriptions ADD COLUMN IF NOT EXISTS customer_code TEXT;
+
+DROP INDEX IF EXISTS idx_subscriptions_customer_email;
+CREATE INDEX IF NOT EXISTS idx_subscriptions_customer_code ON subscriptions(provider, customer_code);
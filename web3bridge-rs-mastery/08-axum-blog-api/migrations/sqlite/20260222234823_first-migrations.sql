@@ -0,0 +1,24 @@
+-- SQLite twin of migrations/postgres: UUIDs are 16-byte BLOBs and timestamps are
+-- RFC 3339 TEXT in UTC, the formats sqlx binds, so they compare correctly as text.
+
+-- Create authors table
+CREATE TABLE IF NOT EXISTS authors (
+    id BLOB PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    email VARCHAR(255) NOT NULL UNIQUE,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+-- Create posts table
+CREATE TABLE IF NOT EXISTS posts (
+    id BLOB PRIMARY KEY,
+    title VARCHAR(255) NOT NULL,
+    content TEXT NOT NULL,
+    author_id BLOB NOT NULL REFERENCES authors(id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+-- Index for faster post lookups by author
+CREATE INDEX IF NOT EXISTS idx_posts_author_id ON posts(author_id);
@@ -0,0 +1,22 @@
+-- Full-text index over post title and content; SQLite has no tsvector, so an FTS5
+-- table kept in step by triggers stands in for the generated search_vector column.
+CREATE VIRTUAL TABLE IF NOT EXISTS posts_fts USING fts5(
+    post_id UNINDEXED,
+    title,
+    content,
+    tokenize = 'porter unicode61'
+);
+
+CREATE TRIGGER IF NOT EXISTS posts_fts_insert AFTER INSERT ON posts BEGIN
+    INSERT INTO posts_fts (post_id, title, content) VALUES (NEW.id, NEW.title, NEW.content);
+END;
+
+CREATE TRIGGER IF NOT EXISTS posts_fts_update AFTER UPDATE OF title, content ON posts BEGIN
+    UPDATE posts_fts SET title = NEW.title, content = NEW.content WHERE post_id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS posts_fts_delete AFTER DELETE ON posts BEGIN
+    DELETE FROM posts_fts WHERE post_id = OLD.id;
+END;
+
+INSERT INTO posts_fts (post_id, title, content) SELECT id, title, content FROM posts;
@@ -0,0 +1,16 @@
+-- Post lifecycle: draft -> scheduled -> published -> archived, plus soft delete
+ALTER TABLE posts ADD COLUMN status TEXT NOT NULL DEFAULT 'published'
+    CHECK (status IN ('draft', 'scheduled', 'published', 'archived'));
+ALTER TABLE posts ADD COLUMN published_at TEXT;
+ALTER TABLE posts ADD COLUMN deleted_at TEXT;
+
+-- Every post written before this migration was live from the moment it was created
+UPDATE posts SET published_at = created_at WHERE published_at IS NULL AND status = 'published';
+
+-- Public listings only ever read live, published rows
+CREATE INDEX IF NOT EXISTS idx_posts_published_created_at_id ON posts(created_at DESC, id DESC)
+    WHERE status = 'published' AND deleted_at IS NULL;
+
+-- The scheduler polls for scheduled posts whose time has come
+CREATE INDEX IF NOT EXISTS idx_posts_scheduled_published_at ON posts(published_at)
+    WHERE status = 'scheduled';
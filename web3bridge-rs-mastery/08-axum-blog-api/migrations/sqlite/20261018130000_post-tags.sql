@@ -0,0 +1,16 @@
+-- Create tags table (names are stored trimmed + lowercased)
+CREATE TABLE IF NOT EXISTS tags (
+    id BLOB PRIMARY KEY,
+    name VARCHAR(64) NOT NULL UNIQUE,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+-- Many-to-many link between posts and tags
+CREATE TABLE IF NOT EXISTS post_tags (
+    post_id BLOB NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    tag_id BLOB NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    PRIMARY KEY (post_id, tag_id)
+);
+
+-- Index for "posts with tag X" lookups (post_id is covered by the primary key)
+CREATE INDEX IF NOT EXISTS idx_post_tags_tag_id ON post_tags(tag_id);
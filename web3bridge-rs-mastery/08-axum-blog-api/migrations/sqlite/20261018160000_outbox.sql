@@ -0,0 +1,17 @@
+-- Side-effect events written in the same transaction as the change that caused them
+CREATE TABLE IF NOT EXISTS outbox (
+    id BLOB PRIMARY KEY,
+    event_type TEXT NOT NULL,
+    aggregate_id BLOB NOT NULL,
+    payload TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'done', 'dead')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    last_error TEXT,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    processed_at TEXT
+);
+
+-- The worker only ever scans pending rows that are due
+CREATE INDEX IF NOT EXISTS idx_outbox_pending ON outbox(next_attempt_at) WHERE status = 'pending';
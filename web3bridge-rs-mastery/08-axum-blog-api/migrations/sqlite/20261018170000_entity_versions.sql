@@ -0,0 +1,3 @@
+-- Bumped on every write; together with updated_at it forms the ETag used for optimistic concurrency
+ALTER TABLE authors ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE posts ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
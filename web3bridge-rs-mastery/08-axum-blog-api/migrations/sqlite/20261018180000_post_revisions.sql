@@ -0,0 +1,16 @@
+-- Snapshot of a post's title and content after every create, update and restore
+CREATE TABLE IF NOT EXISTS post_revisions (
+    id BLOB PRIMARY KEY,
+    post_id BLOB NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    revision INTEGER NOT NULL CHECK (revision >= 1),
+    title VARCHAR(255) NOT NULL,
+    content TEXT NOT NULL,
+    -- Set when this revision was produced by restoring an older one
+    restored_from INTEGER,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    UNIQUE (post_id, revision)
+);
+
+-- Existing posts start their history at revision 1 with their current text
+INSERT OR IGNORE INTO post_revisions (id, post_id, revision, title, content, created_at)
+SELECT randomblob(16), id, 1, title, content, updated_at FROM posts;
@@ -0,0 +1,10 @@
+-- One-time tokens an operator issues so an author can set a password without signing in;
+-- only a SHA-256 of each token is kept
+CREATE TABLE IF NOT EXISTS password_resets (
+    token_hash TEXT PRIMARY KEY,
+    author_id BLOB NOT NULL REFERENCES authors(id) ON DELETE CASCADE,
+    expires_at TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_password_resets_author ON password_resets(author_id);
@@ -0,0 +1,29 @@
+-- Deleting an author keeps their comments, shown as "[deleted author]", so other people's
+-- replies under them survive instead of cascading away with the thread. SQLite cannot alter
+-- a foreign key, so the table is rebuilt; such comments have neither an author nor a guest
+-- name, so that check goes too. Nothing else references comments, and the new table points
+-- at itself so dropping the old one cascades nowhere.
+CREATE TABLE comments_new (
+    id BLOB PRIMARY KEY,
+    post_id BLOB NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    parent_id BLOB REFERENCES comments_new(id) ON DELETE CASCADE,
+    author_id BLOB REFERENCES authors(id) ON DELETE SET NULL,
+    guest_name VARCHAR(255),
+    depth INTEGER NOT NULL DEFAULT 0 CHECK (depth >= 0),
+    body TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'approved', 'rejected')),
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+INSERT INTO comments_new (id, post_id, parent_id, author_id, guest_name, depth, body, status, created_at, updated_at)
+SELECT id, post_id, parent_id, author_id, guest_name, depth, body, status, created_at, updated_at
+FROM comments ORDER BY depth;
+
+DROP TABLE comments;
+ALTER TABLE comments_new RENAME TO comments;
+
+CREATE INDEX IF NOT EXISTS idx_comments_post_id_created_at ON comments(post_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_comments_parent_id ON comments(parent_id);
+CREATE INDEX IF NOT EXISTS idx_comments_author_id ON comments(author_id);
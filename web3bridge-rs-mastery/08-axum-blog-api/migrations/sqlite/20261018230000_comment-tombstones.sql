@@ -0,0 +1,4 @@
+-- A commenter deleting their own comment leaves a "[deleted]" tombstone while it has
+-- replies, so other people's replies under it survive; only the post's author removes a
+-- whole subtree
+ALTER TABLE comments ADD COLUMN deleted_at TEXT;
@@ -1,24 +1,135 @@
 use crate::app::router::create_router;
 use crate::app::state::{AppState, SharedState};
+use crate::auth::JwtKeys;
 use crate::config::{AppConfig, connect_db};
+use crate::outbox::spawn_outbox_worker;
+use crate::post::spawn_publisher;
+use crate::rate_limit::{RATELIMIT_LIMIT, RATELIMIT_REMAINING, RATELIMIT_RESET};
+use axum::http::{
+    HeaderName, HeaderValue,
+    header::{ETAG, RETRY_AFTER},
+};
+use std::net::SocketAddr;
 use tokio::net::TcpListener;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
 
+pub mod openapi;
 pub mod router;
 pub mod state;
+#[cfg(test)]
+pub mod test_support;
 
-pub async fn create_app() {
-    let app_config = AppConfig::from_env().expect("Failed to load configuration from environment");
-
-    let db_pool = connect_db(&app_config.database_url)
+pub async fn create_app(app_config: AppConfig) {
+    let db_pool = connect_db(&app_config.database)
         .await
         .expect("Failed to connect to the database");
 
-    let app_state = SharedState::new(AppState::new(db_pool));
-    let app = create_router(app_state);
+    let jwt = JwtKeys::new(&app_config.auth.jwt_secret, app_config.auth.jwt_ttl_seconds);
+    let app_state = SharedState::new(AppState {
+        site: app_config.site,
+        ..AppState::new(
+            db_pool,
+            jwt,
+            app_config.comment_max_depth,
+            app_config.paystack_secret,
+            app_config.admin_token,
+            app_config.features,
+            app_config.rate_limit,
+        )
+        .with_cache(&app_config.cache)
+    });
+    let background_tasks = [
+        spawn_publisher(app_state.clone()),
+        spawn_outbox_worker(app_state.clone()),
+    ];
+    let mut app = create_router(app_state.clone());
+    if let Some(cors) = cors_layer(&app_config.cors_origins) {
+        app = app.layer(cors);
+    }
+
+    let server_address = app_config.server.bind_address;
+    let listener = TcpListener::bind(server_address).await.unwrap();
+
+    tracing::info!(address = %server_address, features = ?app_config.features, "server started");
+    // Peer addresses key the rate limiter for anonymous clients.
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown({
+        let post_events = app_state.post_events.clone();
+        async move {
+            shutdown_signal().await;
+            // Event streams never finish on their own; end them so draining can complete.
+            post_events.close();
+        }
+    })
+    .await
+    .unwrap();
+
+    // In-flight requests have finished. Work the background tasks drop here is picked
+    // up again on the next start: scheduled posts are still due and claimed outbox
+    // events become visible once their lease expires.
+    for task in background_tasks {
+        task.abort();
+    }
+    app_state.db_pool.close().await;
+    tracing::info!("shutdown complete");
+}
+
+/// Browser access for the configured origins; `None` leaves CORS headers off entirely.
+fn cors_layer(origins: &[String]) -> Option<CorsLayer> {
+    if origins.is_empty() {
+        return None;
+    }
+
+    let allow_origin = if origins.iter().any(|origin| origin == "*") {
+        AllowOrigin::any()
+    } else {
+        // Validated when the configuration was loaded.
+        AllowOrigin::list(
+            origins
+                .iter()
+                .filter_map(|origin| origin.parse::<HeaderValue>().ok()),
+        )
+    };
+    Some(
+        CorsLayer::new()
+            .allow_origin(allow_origin)
+            .allow_methods(Any)
+            .allow_headers(Any)
+            .expose_headers([
+                HeaderName::from_static("x-request-id"),
+                ETAG,
+                RETRY_AFTER,
+                RATELIMIT_LIMIT,
+                RATELIMIT_REMAINING,
+                RATELIMIT_RESET,
+            ]),
+    )
+}
+
+/// Resolves on ctrl-c or SIGTERM, which stops the server accepting new connections.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to listen for ctrl-c");
+    };
 
-    let server_address = format!("127.0.0.1:{}", app_config.server_port);
-    let listener = TcpListener::bind(&server_address).await.unwrap();
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to listen for SIGTERM")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
 
-    println!("Server started successfully on {}", server_address);
-    axum::serve(listener, app).await.unwrap();
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+    tracing::info!("shutdown signal received, draining in-flight requests");
 }
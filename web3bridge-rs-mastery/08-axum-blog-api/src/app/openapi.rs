@@ -0,0 +1,260 @@
+use crate::app::router::MetaApi;
+use crate::auth::{ADMIN_TOKEN_HEADER, AuthApi};
+use crate::author::AuthorApi;
+use crate::bulk::BulkApi;
+use crate::comment::CommentApi;
+use crate::error::{ErrorResponse, PROBLEM_JSON};
+use crate::feed::FeedApi;
+use crate::health::HealthApi;
+use crate::payments::PaymentApi;
+use crate::post::PostApi;
+use crate::response::MessageResponse;
+use crate::revision::RevisionApi;
+use crate::tag::TagApi;
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::openapi::{Content, RefOr};
+use utoipa::{Modify, OpenApi};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Axum Blog API",
+        description = "Authors, posts, comments and tags. Errors are `application/problem+json` bodies with a stable `code`."
+    ),
+    components(schemas(ErrorResponse, MessageResponse)),
+    modifiers(&BearerAuth),
+    tags(
+        (name = "auth", description = "Login and tokens"),
+        (name = "authors", description = "Author accounts"),
+        (name = "posts", description = "Blog posts"),
+        (name = "comments", description = "Threaded comments on posts"),
+        (name = "revisions", description = "Post edit history and rollback"),
+        (name = "tags", description = "Post tags"),
+        (name = "feeds", description = "Atom/RSS feeds and the sitemap"),
+        (name = "bulk", description = "NDJSON export and import for operators"),
+        (name = "webhooks", description = "Payment provider callbacks"),
+        (name = "meta", description = "Service status"),
+    )
+)]
+struct ApiDoc;
+
+/// The full OpenAPI document, assembled from each domain's paths.
+pub fn api_doc() -> utoipa::openapi::OpenApi {
+    let mut doc = ApiDoc::openapi()
+        .merge_from(MetaApi::openapi())
+        .merge_from(HealthApi::openapi())
+        .merge_from(AuthApi::openapi())
+        .merge_from(AuthorApi::openapi())
+        .merge_from(PostApi::openapi())
+        .merge_from(CommentApi::openapi())
+        .merge_from(RevisionApi::openapi())
+        .merge_from(TagApi::openapi())
+        .merge_from(FeedApi::openapi())
+        .merge_from(BulkApi::openapi())
+        .merge_from(PaymentApi::openapi());
+    ProblemJson.modify(&mut doc);
+    doc
+}
+
+// Registers the `bearer` and `admin_token` schemes referenced by `security(...)` on protected paths.
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "admin_token",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new(ADMIN_TOKEN_HEADER))),
+        );
+    }
+}
+
+// `body = ErrorResponse` documents `application/json`; `AppError` actually sends problem details.
+// Runs after the merge, since modifiers listed in `#[openapi]` only see `ApiDoc`'s own paths.
+struct ProblemJson;
+
+impl Modify for ProblemJson {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let operations = openapi.paths.paths.values_mut().flat_map(|item| {
+            [
+                &mut item.get,
+                &mut item.put,
+                &mut item.post,
+                &mut item.delete,
+                &mut item.patch,
+            ]
+            .into_iter()
+            .flatten()
+        });
+        for operation in operations {
+            for response in operation.responses.responses.values_mut() {
+                let RefOr::T(response) = response else {
+                    continue;
+                };
+                let is_error = response
+                    .content
+                    .get("application/json")
+                    .is_some_and(is_error_body);
+                if is_error && let Some(content) = response.content.shift_remove("application/json")
+                {
+                    response.content.insert(PROBLEM_JSON.to_string(), content);
+                }
+            }
+        }
+    }
+}
+
+fn is_error_body(content: &Content) -> bool {
+    let Some(RefOr::Ref(schema)) = &content.schema else {
+        return false;
+    };
+    schema.ref_location == "#/components/schemas/ErrorResponse"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::router::create_router;
+    use crate::app::state::{AppState, SharedState};
+    use crate::config::RateLimitConfig;
+    use crate::rate_limit::RateLimiter;
+    use axum::{
+        Router,
+        body::Body,
+        http::{Method, Request, StatusCode},
+    };
+    use serde_json::Value;
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    // Served by Swagger UI itself rather than by the API.
+    const DOC_PATHS: [&str; 2] = ["/docs", "/openapi.json"];
+
+    // axum has no public route listing, but its Debug output names every registered path.
+    fn router_paths(router: &Router) -> Vec<String> {
+        let debug = format!("{router:?}");
+        let mut paths: Vec<String> = debug
+            .split('"')
+            .filter(|part| part.starts_with('/') && !part.contains("__private__axum"))
+            .map(str::to_string)
+            .collect();
+        paths.sort();
+        paths.dedup();
+        paths
+    }
+
+    fn with_sample_params(path: &str) -> String {
+        path.split('/')
+            .map(|segment| {
+                if segment.starts_with('{') {
+                    "00000000-0000-0000-0000-000000000000"
+                } else {
+                    segment
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    #[tokio::test]
+    async fn every_route_is_in_the_spec() {
+        // Probing every method of every route would otherwise run out of rate limit budget.
+        let mut state = Arc::into_inner(AppState::for_tests()).expect("state is not shared yet");
+        state.rate_limiter = RateLimiter::in_memory(RateLimitConfig {
+            enabled: false,
+            ..RateLimitConfig::default()
+        });
+        let router = create_router(SharedState::new(state));
+        let spec = serde_json::to_value(api_doc()).unwrap();
+        let paths = router_paths(&router);
+        assert!(
+            paths.iter().any(|path| path == "/posts/{id}"),
+            "could not read routes from the router: {paths:?}"
+        );
+
+        let mut missing = Vec::new();
+        for path in paths
+            .iter()
+            .filter(|path| !DOC_PATHS.iter().any(|doc| path.starts_with(doc)))
+        {
+            let uri = with_sample_params(path);
+            for method in [
+                Method::GET,
+                Method::POST,
+                Method::PUT,
+                Method::PATCH,
+                Method::DELETE,
+            ] {
+                let request = Request::builder()
+                    .method(method.clone())
+                    .uri(&uri)
+                    .body(Body::empty())
+                    .unwrap();
+                let response = router.clone().oneshot(request).await.unwrap();
+                if response.status() == StatusCode::METHOD_NOT_ALLOWED {
+                    continue;
+                }
+
+                let operation = &spec["paths"][path.as_str()][method.as_str().to_lowercase()];
+                if !operation.is_object() {
+                    missing.push(format!("{method} {path}"));
+                }
+            }
+        }
+
+        assert!(
+            missing.is_empty(),
+            "routes missing from /openapi.json: {missing:?}"
+        );
+    }
+
+    #[test]
+    fn error_responses_are_problem_details() {
+        let spec = serde_json::to_value(api_doc()).unwrap();
+        let content = &spec["paths"]["/posts/{id}"]["get"]["responses"]["404"]["content"];
+
+        assert!(content.get("application/json").is_none());
+        assert_eq!(
+            content[PROBLEM_JSON]["schema"]["$ref"],
+            "#/components/schemas/ErrorResponse"
+        );
+    }
+
+    #[test]
+    fn every_schema_reference_resolves() {
+        fn collect_refs(value: &Value, refs: &mut Vec<String>) {
+            match value {
+                Value::Object(map) => {
+                    if let Some(Value::String(target)) = map.get("$ref") {
+                        refs.push(target.clone());
+                    }
+                    map.values().for_each(|value| collect_refs(value, refs));
+                }
+                Value::Array(items) => items.iter().for_each(|value| collect_refs(value, refs)),
+                _ => {}
+            }
+        }
+
+        let spec = serde_json::to_value(api_doc()).unwrap();
+        let mut refs = Vec::new();
+        collect_refs(&spec, &mut refs);
+
+        let dangling: Vec<_> = refs
+            .iter()
+            .filter(|target| {
+                let name = target.trim_start_matches("#/components/schemas/");
+                spec["components"]["schemas"][name].is_null()
+            })
+            .collect();
+        assert!(dangling.is_empty(), "unresolved schema refs: {dangling:?}");
+    }
+}
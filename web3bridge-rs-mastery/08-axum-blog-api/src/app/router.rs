@@ -1,32 +1,113 @@
+use crate::app::openapi::api_doc;
 use crate::app::state::SharedState;
+use crate::auth::auth_routes;
 use crate::author::author_routes;
+use crate::bulk::bulk_routes;
+use crate::comment::comment_routes;
+use crate::error::{AppError, ErrorCode};
+use crate::feed::feed_routes;
+use crate::health::health_routes;
+use crate::payments::payment_routes;
 use crate::post::post_routes;
+use crate::rate_limit::rate_limit;
+use crate::revision::revision_routes;
+use crate::tag::tag_routes;
+use crate::telemetry::{get_metrics, request_context, track_metrics};
 use axum::{
-    Json, Router,
-    http::StatusCode,
+    Router,
+    http::{Method, Uri},
+    middleware,
     routing::get,
 };
-use serde_json::json;
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
+
+/// Spec for the routes defined directly on the root router.
+#[derive(OpenApi)]
+#[openapi(paths(hello, crate::telemetry::metrics::get_metrics))]
+pub struct MetaApi;
 
 pub fn create_router(state: SharedState) -> Router {
-    Router::new()
+    let features = state.features;
+    let mut router = Router::new()
+        .nest("/auth", auth_routes())
         .nest("/authors", author_routes())
         .nest("/posts", post_routes())
+        .nest("/posts/{post_id}/revisions", revision_routes())
+        .nest("/tags", tag_routes())
+        .merge(feed_routes())
+        .merge(bulk_routes());
+
+    if features.comments {
+        router = router.nest("/posts/{post_id}/comments", comment_routes());
+    }
+    // Only the API routes above are limited (which also slows admin token guessing); probes, docs, metrics and webhooks are not.
+    router = router
+        .layer(middleware::from_fn_with_state(state.clone(), rate_limit))
         .route("/", get(hello))
-        .route("/health", get(health_handler))
+        .nest("/health", health_routes());
+    // Without a secret no delivery could be verified, so Paystack gets a 404 rather than a
+    // 500 it would retry forever.
+    if features.webhooks && state.paystack_secret.is_some() {
+        router = router.nest("/webhooks", payment_routes());
+    }
+    if features.docs {
+        router = router.merge(SwaggerUi::new("/docs").url("/openapi.json", api_doc()));
+    }
+    if features.metrics {
+        router = router
+            .route("/metrics", get(get_metrics))
+            .layer(middleware::from_fn_with_state(state.clone(), track_metrics));
+    }
+
+    // Registered last so the 405 handler covers every route above.
+    router
+        .fallback(route_not_found)
+        .method_not_allowed_fallback(method_not_allowed)
+        .layer(middleware::from_fn(request_context))
         .with_state(state)
 }
 
+async fn route_not_found(uri: Uri) -> AppError {
+    AppError::NotFound(
+        ErrorCode::RequestRouteNotFound,
+        format!("No route for {}", uri.path()),
+    )
+}
+
+async fn method_not_allowed(method: Method, uri: Uri) -> AppError {
+    AppError::MethodNotAllowed(format!("{method} is not allowed on {}", uri.path()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/",
+    tag = "meta",
+    responses((status = 200, description = "Greeting", body = String, content_type = "text/plain"))
+)]
 async fn hello() -> &'static str {
     "Hello, World!"
 }
 
-async fn health_handler() -> (StatusCode, Json<serde_json::Value>) {
-    (
-        StatusCode::OK,
-        Json(json!({
-            "status": "ok",
-            "message": "Server is running"
-        })),
-    )
+#[cfg(test)]
+mod tests {
+    use crate::app::state::AppState;
+    use crate::app::test_support::{send, send_with_headers};
+    use axum::http::{Method, StatusCode, header::ALLOW};
+
+    #[tokio::test]
+    async fn unknown_routes_and_methods_are_problem_details() {
+        let state = AppState::for_tests_in_memory();
+
+        let (status, error) = send(&state, Method::GET, "/nope", None, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(error["code"], "request.route_not_found");
+
+        let (status, headers, error) =
+            send_with_headers(&state, Method::PUT, "/posts", None, &[], None).await;
+        assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(error["code"], "request.method_not_allowed");
+        let allow = headers[ALLOW].to_str().unwrap();
+        assert!(allow.contains("GET") && allow.contains("POST"), "{allow}");
+    }
 }
@@ -1,14 +1,154 @@
-use sqlx::PgPool;
+use crate::auth::JwtKeys;
+use crate::author::{AuthorCache, AuthorRepository, SqlAuthorRepository};
+use crate::config::{CacheConfig, FeatureToggles, RateLimitConfig, SiteConfig};
+use crate::db::DbPool;
+use crate::payments::{SqlSubscriptionRepository, SubscriptionRepository};
+use crate::post::{PostCache, PostEvents, PostRepository, SqlPostRepository};
+use crate::rate_limit::RateLimiter;
+use crate::telemetry::Metrics;
+use secrecy::SecretString;
 use std::sync::Arc;
 
 #[derive(Debug, Clone)]
 pub struct AppState {
-    pub db_pool: PgPool,
+    pub db_pool: DbPool,
+    pub authors: Arc<dyn AuthorRepository>,
+    pub posts: Arc<dyn PostRepository>,
+    pub subscriptions: Arc<dyn SubscriptionRepository>,
+    /// Shared with the SQL repositories, for writes that bypass them.
+    pub caches: Caches,
+    /// Committed post writes, streamed by `GET /posts/stream`.
+    pub post_events: PostEvents,
+    pub jwt: JwtKeys,
+    pub site: SiteConfig,
+    pub comment_max_depth: i32,
+    pub paystack_secret: Option<SecretString>,
+    pub admin_token: Option<SecretString>,
+    pub features: FeatureToggles,
+    pub rate_limiter: RateLimiter,
+    pub metrics: Metrics,
 }
 
 impl AppState {
-    pub fn new(db_pool: PgPool) -> Self {
-        Self { db_pool }
+    pub fn new(
+        db_pool: DbPool,
+        jwt: JwtKeys,
+        comment_max_depth: i32,
+        paystack_secret: Option<SecretString>,
+        admin_token: Option<SecretString>,
+        features: FeatureToggles,
+        rate_limit: RateLimitConfig,
+    ) -> Self {
+        let metrics = Metrics::new();
+        let caches = Caches::new(&CacheConfig::default(), &metrics);
+        let post_events = PostEvents::default();
+        Self {
+            authors: caches.author_repository(&db_pool, &post_events),
+            posts: caches.post_repository(&db_pool, &post_events),
+            subscriptions: Arc::new(SqlSubscriptionRepository::new(db_pool.clone())),
+            caches,
+            post_events,
+            db_pool,
+            jwt,
+            site: SiteConfig::default(),
+            comment_max_depth,
+            paystack_secret,
+            admin_token,
+            features,
+            rate_limiter: RateLimiter::in_memory(rate_limit),
+            metrics,
+        }
+    }
+
+    /// Replaces the default caches with ones built from `config`.
+    pub fn with_cache(self, config: &CacheConfig) -> Self {
+        let caches = Caches::new(config, &self.metrics);
+        Self {
+            authors: caches.author_repository(&self.db_pool, &self.post_events),
+            posts: caches.post_repository(&self.db_pool, &self.post_events),
+            caches,
+            ..self
+        }
+    }
+}
+
+/// The read caches in front of `author::queries` and `post::queries`.
+///
+/// The repositories invalidate exactly what each write changes. Anything else that writes
+/// `authors`, `posts` or `post_tags` has to invalidate here once it commits: `bulk` clears
+/// every cache after an import. `auth::queries` only ever sets `password_hash`, which no
+/// cache holds.
+#[derive(Debug, Clone)]
+pub struct Caches {
+    pub authors: AuthorCache,
+    pub posts: PostCache,
+}
+
+impl Caches {
+    pub fn new(config: &CacheConfig, metrics: &Metrics) -> Self {
+        Self {
+            authors: AuthorCache::new(config, metrics),
+            posts: PostCache::new(config, metrics),
+        }
+    }
+
+    pub fn clear(&self) {
+        self.authors.clear();
+        self.posts.clear();
+    }
+
+    fn author_repository(
+        &self,
+        db_pool: &DbPool,
+        events: &PostEvents,
+    ) -> Arc<dyn AuthorRepository> {
+        Arc::new(SqlAuthorRepository::new(
+            db_pool.clone(),
+            self.authors.clone(),
+            self.posts.clone(),
+            events.clone(),
+        ))
+    }
+
+    fn post_repository(&self, db_pool: &DbPool, events: &PostEvents) -> Arc<dyn PostRepository> {
+        Arc::new(SqlPostRepository::new(
+            db_pool.clone(),
+            self.posts.clone(),
+            events.clone(),
+        ))
+    }
+}
+
+#[cfg(test)]
+impl AppState {
+    /// State for router tests that never reach the database; any query fails fast.
+    pub fn for_tests() -> SharedState {
+        Self::for_tests_with_pool(crate::db::unreachable_pool())
+    }
+
+    /// Like `for_tests`, but authors, posts and subscriptions live in memory so their
+    /// handlers work.
+    pub fn for_tests_in_memory() -> SharedState {
+        let mut state = Arc::into_inner(Self::for_tests()).expect("state is not shared yet");
+        state.authors = Arc::new(crate::author::InMemoryAuthorRepository::default());
+        state.posts = Arc::new(crate::post::InMemoryPostRepository::new(
+            state.post_events.clone(),
+        ));
+        state.subscriptions = Arc::new(crate::payments::InMemorySubscriptionRepository::default());
+        SharedState::new(state)
+    }
+
+    pub fn for_tests_with_pool(db_pool: DbPool) -> SharedState {
+        let jwt = JwtKeys::new(&SecretString::from("test-secret"), 60);
+        SharedState::new(Self::new(
+            db_pool,
+            jwt,
+            5,
+            None,
+            None,
+            FeatureToggles::default(),
+            RateLimitConfig::default(),
+        ))
     }
 }
 
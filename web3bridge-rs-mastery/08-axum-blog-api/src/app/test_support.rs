@@ -0,0 +1,79 @@
+//! Request helpers shared by the handler test suites.
+
+use crate::app::router::create_router;
+use crate::app::state::SharedState;
+use axum::{
+    body::Body,
+    http::{HeaderMap, HeaderName, Method, Request, StatusCode, header::ETAG},
+};
+use serde_json::Value;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Sends one request through the full router, with an optional bearer token and JSON body.
+pub async fn send(
+    state: &SharedState,
+    method: Method,
+    uri: &str,
+    token: Option<&str>,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let (status, _, body) = send_with_headers(state, method, uri, token, &[], body).await;
+    (status, body)
+}
+
+/// Like `send`, with extra request headers; also returns the response headers.
+pub async fn send_with_headers(
+    state: &SharedState,
+    method: Method,
+    uri: &str,
+    token: Option<&str>,
+    headers: &[(HeaderName, &str)],
+    body: Option<Value>,
+) -> (StatusCode, HeaderMap, Value) {
+    let mut request = Request::builder().method(method).uri(uri);
+    if let Some(token) = token {
+        request = request.header("authorization", format!("Bearer {token}"));
+    }
+    for (name, value) in headers {
+        request = request.header(name, *value);
+    }
+    let request = match body {
+        Some(body) => request
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string())),
+        None => request.body(Body::empty()),
+    }
+    .unwrap();
+
+    let response = create_router(state.clone()).oneshot(request).await.unwrap();
+    let status = response.status();
+    let headers = response.headers().clone();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body = if bytes.is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_slice(&bytes).unwrap()
+    };
+    (status, headers, body)
+}
+
+/// The `ETag` a GET of `uri` currently returns, for use in `If-Match`.
+pub async fn current_etag(state: &SharedState, uri: &str, token: Option<&str>) -> String {
+    let (status, headers, _) = send_with_headers(state, Method::GET, uri, token, &[], None).await;
+    assert_eq!(status, StatusCode::OK, "GET {uri}");
+    headers[ETAG].to_str().unwrap().to_string()
+}
+
+/// Stores an author directly in the repository and returns its id plus a valid token.
+pub async fn signed_in_author(state: &SharedState, email: &str) -> (Uuid, String) {
+    let author = state
+        .authors
+        .insert("Test Author", email, "not-a-real-hash")
+        .await
+        .unwrap();
+    let token = state.jwt.issue(author.id).unwrap();
+    (author.id, token)
+}
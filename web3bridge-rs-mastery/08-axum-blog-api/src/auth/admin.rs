@@ -0,0 +1,45 @@
+use crate::app::state::SharedState;
+use crate::error::{AppError, ErrorCode};
+use axum::{extract::FromRequestParts, http::request::Parts};
+use secrecy::ExposeSecret;
+use subtle::ConstantTimeEq;
+
+pub const ADMIN_TOKEN_HEADER: &str = "x-admin-token";
+
+/// A request carrying the operator token from `admin.token` in `X-Admin-Token`.
+///
+/// Guards deployment-wide endpoints that no single author may use. Without a
+/// configured token those endpoints answer `403` for everyone.
+#[derive(Debug, Clone, Copy)]
+pub struct AdminAccess;
+
+impl FromRequestParts<SharedState> for AdminAccess {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &SharedState,
+    ) -> Result<Self, Self::Rejection> {
+        let Some(expected) = &state.admin_token else {
+            return Err(AppError::Forbidden(
+                ErrorCode::AuthAdminDisabled,
+                "Admin endpoints are disabled; set ADMIN_TOKEN to enable them".to_string(),
+            ));
+        };
+
+        let given = parts
+            .headers
+            .get(ADMIN_TOKEN_HEADER)
+            .map(|value| value.as_bytes())
+            .unwrap_or_default();
+        // Constant time, so response timing does not reveal how much of a guess was right.
+        if !bool::from(given.ct_eq(expected.expose_secret().as_bytes())) {
+            return Err(AppError::Unauthorized(
+                ErrorCode::AuthInvalidAdminToken,
+                "Invalid admin token".to_string(),
+            ));
+        }
+
+        Ok(Self)
+    }
+}
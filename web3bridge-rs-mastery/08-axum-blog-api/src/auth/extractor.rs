@@ -0,0 +1,79 @@
+use crate::app::state::SharedState;
+use crate::error::{AppError, ErrorCode};
+use axum::{
+    extract::{FromRequestParts, OptionalFromRequestParts},
+    http::{header::AUTHORIZATION, request::Parts},
+};
+use uuid::Uuid;
+
+/// The author identified by a valid `Authorization: Bearer <jwt>` header.
+#[derive(Debug, Clone, Copy)]
+pub struct AuthAuthor {
+    pub author_id: Uuid,
+}
+
+impl AuthAuthor {
+    /// Fails with `403` unless the authenticated author is `owner_id`.
+    pub fn ensure_owner(&self, owner_id: Uuid) -> Result<(), AppError> {
+        if self.author_id != owner_id {
+            return Err(AppError::Forbidden(
+                ErrorCode::AuthNotOwner,
+                "You do not have permission to modify this resource".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl FromRequestParts<SharedState> for AuthAuthor {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &SharedState,
+    ) -> Result<Self, Self::Rejection> {
+        let header = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .ok_or_else(|| {
+                AppError::Unauthorized(
+                    ErrorCode::AuthMissingToken,
+                    "Missing bearer token".to_string(),
+                )
+            })?;
+
+        let token = header
+            .strip_prefix("Bearer ")
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .ok_or_else(|| {
+                AppError::Unauthorized(
+                    ErrorCode::AuthMissingToken,
+                    "Missing bearer token".to_string(),
+                )
+            })?;
+
+        let author_id = state.jwt.verify(token)?;
+        Ok(Self { author_id })
+    }
+}
+
+// Anonymous requests are allowed through as `None`, but a malformed or expired token is still a 401.
+impl OptionalFromRequestParts<SharedState> for AuthAuthor {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &SharedState,
+    ) -> Result<Option<Self>, Self::Rejection> {
+        if !parts.headers.contains_key(AUTHORIZATION) {
+            return Ok(None);
+        }
+
+        <Self as FromRequestParts<SharedState>>::from_request_parts(parts, state)
+            .await
+            .map(Some)
+    }
+}
@@ -0,0 +1,234 @@
+use crate::app::state::SharedState;
+use crate::auth::models::{
+    LoginRequest, PasswordResetRequest, PasswordResetResponse, SetPasswordRequest, TokenResponse,
+};
+use crate::auth::reset::{RESET_TOKEN_TTL_SECONDS, new_reset_token, reset_token_hash};
+use crate::auth::{AdminAccess, password, queries};
+use crate::error::{AppError, ErrorCode, ErrorResponse};
+use crate::validation::ValidJson;
+use axum::{Json, extract::State, http::StatusCode};
+use chrono::{TimeDelta, Utc};
+use uuid::Uuid;
+
+#[utoipa::path(
+    post,
+    path = "/auth/login",
+    tag = "auth",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "Bearer token", body = TokenResponse),
+        (status = 401, description = "Invalid email or password", body = ErrorResponse),
+        (status = 422, description = "Invalid input", body = ErrorResponse),
+    )
+)]
+pub async fn login(
+    State(state): State<SharedState>,
+    ValidJson(payload): ValidJson<LoginRequest>,
+) -> Result<Json<TokenResponse>, AppError> {
+    let invalid = || {
+        AppError::Unauthorized(
+            ErrorCode::AuthInvalidCredentials,
+            "Invalid email or password".to_string(),
+        )
+    };
+
+    let email = payload.email.trim().to_lowercase();
+    let (author_id, password_hash) =
+        match queries::find_credentials_by_email(&state, &email).await? {
+            Some((author_id, password_hash)) => (Some(author_id), password_hash),
+            None => (None, None),
+        };
+
+    // No early return for unknown emails; see `verify_password`.
+    let verified = password::verify_password(payload.password, password_hash).await?;
+    let Some(author_id) = author_id.filter(|_| verified) else {
+        return Err(invalid());
+    };
+
+    token_response(&state, author_id)
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/password-resets",
+    tag = "auth",
+    request_body = PasswordResetRequest,
+    security(("admin_token" = [])),
+    responses(
+        (status = 201, description = "One-time token for setting the author's password", body = PasswordResetResponse),
+        (status = 401, description = "Wrong or missing admin token", body = ErrorResponse),
+        (status = 403, description = "Admin endpoints are disabled", body = ErrorResponse),
+        (status = 404, description = "No author has that email", body = ErrorResponse),
+        (status = 422, description = "Invalid input", body = ErrorResponse),
+    )
+)]
+pub async fn issue_password_reset(
+    State(state): State<SharedState>,
+    _admin: AdminAccess,
+    ValidJson(payload): ValidJson<PasswordResetRequest>,
+) -> Result<(StatusCode, Json<PasswordResetResponse>), AppError> {
+    let reset_token = new_reset_token();
+    let expires_at = Utc::now() + TimeDelta::seconds(RESET_TOKEN_TTL_SECONDS);
+    let hash = reset_token_hash(&reset_token);
+    queries::create_password_reset(&state, &payload.email, &hash, expires_at)
+        .await?
+        .ok_or_else(|| {
+            AppError::NotFound(
+                ErrorCode::AuthorNotFound,
+                format!("No author with email {}", payload.email),
+            )
+        })?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(PasswordResetResponse {
+            reset_token,
+            expires_in: RESET_TOKEN_TTL_SECONDS,
+        }),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/password",
+    tag = "auth",
+    request_body = SetPasswordRequest,
+    responses(
+        (status = 200, description = "Password set; bearer token", body = TokenResponse),
+        (status = 401, description = "Unknown, used or expired reset token", body = ErrorResponse),
+        (status = 422, description = "Invalid input", body = ErrorResponse),
+    )
+)]
+pub async fn reset_password(
+    State(state): State<SharedState>,
+    ValidJson(payload): ValidJson<SetPasswordRequest>,
+) -> Result<Json<TokenResponse>, AppError> {
+    let password_hash = password::hash_password(payload.password).await?;
+    let hash = reset_token_hash(&payload.reset_token);
+    let author_id = queries::redeem_password_reset(&state, &hash, &password_hash)
+        .await?
+        .ok_or_else(|| {
+            AppError::Unauthorized(
+                ErrorCode::AuthInvalidResetToken,
+                "Invalid or expired reset token".to_string(),
+            )
+        })?;
+
+    token_response(&state, author_id)
+}
+
+fn token_response(state: &SharedState, author_id: Uuid) -> Result<Json<TokenResponse>, AppError> {
+    let access_token = state.jwt.issue(author_id)?;
+    Ok(Json(TokenResponse {
+        access_token,
+        token_type: "Bearer",
+        expires_in: state.jwt.ttl_seconds,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::state::{AppState, SharedState};
+    use crate::app::test_support::{send, send_with_headers};
+    use crate::auth::ADMIN_TOKEN_HEADER;
+    use crate::db::DbPool;
+    use axum::http::{HeaderName, Method, StatusCode};
+    use secrecy::SecretString;
+    use serde_json::json;
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    const ADMIN_TOKEN: &str = "admin-secret";
+
+    #[tokio::test]
+    async fn login_bodies_of_the_wrong_shape_are_problem_details() {
+        let state = AppState::for_tests_in_memory();
+        let body = json!({ "email": 42, "password": "secret" });
+
+        let (status, error) = send(&state, Method::POST, "/auth/login", None, Some(body)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error["code"], "request.invalid_body");
+        assert_eq!(error["errors"][0]["field"], "email");
+    }
+
+    fn with_admin_token(state: SharedState) -> SharedState {
+        let mut state = Arc::into_inner(state).expect("state is not shared yet");
+        state.admin_token = Some(SecretString::from(ADMIN_TOKEN));
+        SharedState::new(state)
+    }
+
+    #[sqlx::test(migrator = "crate::db::MIGRATOR")]
+    #[cfg_attr(feature = "postgres", ignore = "needs a Postgres DATABASE_URL")]
+    async fn authors_without_a_password_can_redeem_a_one_time_reset(pool: DbPool) {
+        let state = with_admin_token(AppState::for_tests_with_pool(pool));
+        // As left by the credentials migration or a bulk import.
+        sqlx::query("INSERT INTO authors (id, name, email) VALUES ($1, 'Ada', 'ada@example.com')")
+            .bind(Uuid::new_v4())
+            .execute(&state.db_pool)
+            .await
+            .unwrap();
+        let login = json!({ "email": "ada@example.com", "password": "correct horse" });
+        let (status, _) = send(
+            &state,
+            Method::POST,
+            "/auth/login",
+            None,
+            Some(login.clone()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let admin = [(HeaderName::from_static(ADMIN_TOKEN_HEADER), ADMIN_TOKEN)];
+        let email = json!({ "email": "Ada@Example.com" });
+        let (status, _) = send(
+            &state,
+            Method::POST,
+            "/auth/password-resets",
+            None,
+            Some(email.clone()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _, issued) = send_with_headers(
+            &state,
+            Method::POST,
+            "/auth/password-resets",
+            None,
+            &admin,
+            Some(email),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let reset_token = issued["reset_token"].as_str().unwrap();
+
+        let reset = json!({ "reset_token": reset_token, "password": "correct horse" });
+        let (status, body) = send(
+            &state,
+            Method::POST,
+            "/auth/password",
+            None,
+            Some(reset.clone()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["access_token"].is_string());
+        let (status, _) = send(&state, Method::POST, "/auth/login", None, Some(login)).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, error) = send(&state, Method::POST, "/auth/password", None, Some(reset)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(error["code"], "auth.invalid_reset_token");
+
+        let unknown = json!({ "email": "nobody@example.com" });
+        let (status, _, _) = send_with_headers(
+            &state,
+            Method::POST,
+            "/auth/password-resets",
+            None,
+            &admin,
+            Some(unknown),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
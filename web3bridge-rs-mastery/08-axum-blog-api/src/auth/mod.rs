@@ -0,0 +1,33 @@
+mod admin;
+mod extractor;
+mod handlers;
+pub mod models;
+mod password;
+mod queries;
+mod reset;
+mod token;
+
+use crate::app::state::SharedState;
+use axum::{Router, routing::post};
+use utoipa::OpenApi;
+
+pub use admin::{ADMIN_TOKEN_HEADER, AdminAccess};
+pub use extractor::AuthAuthor;
+pub use password::{MIN_PASSWORD_LENGTH, hash_password};
+pub use token::JwtKeys;
+
+/// OpenAPI paths for the routes under `/auth`.
+#[derive(OpenApi)]
+#[openapi(paths(
+    handlers::login,
+    handlers::issue_password_reset,
+    handlers::reset_password
+))]
+pub struct AuthApi;
+
+pub fn auth_routes() -> Router<SharedState> {
+    Router::new()
+        .route("/login", post(handlers::login))
+        .route("/password-resets", post(handlers::issue_password_reset))
+        .route("/password", post(handlers::reset_password))
+}
@@ -0,0 +1,46 @@
+use crate::auth::MIN_PASSWORD_LENGTH;
+use crate::validation;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct LoginRequest {
+    pub email: String,
+    pub password: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub token_type: &'static str,
+    pub expires_in: i64,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct PasswordResetRequest {
+    #[serde(deserialize_with = "validation::email_address")]
+    #[validate(custom(function = "validation::email"))]
+    pub email: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PasswordResetResponse {
+    /// Pass to the author; it is shown only once.
+    pub reset_token: String,
+    pub expires_in: i64,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct SetPasswordRequest {
+    pub reset_token: String,
+    #[validate(length(min = MIN_PASSWORD_LENGTH))]
+    pub password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: String,
+    pub iat: i64,
+    pub exp: i64,
+}
@@ -0,0 +1,83 @@
+use crate::error::AppError;
+use argon2::{
+    Argon2,
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString, rand_core::OsRng},
+};
+use std::sync::LazyLock;
+
+pub const MIN_PASSWORD_LENGTH: u64 = 8;
+
+// Checked in place of a missing hash, so a login for an unknown email or an author without a
+// password takes as long as one with a wrong password and timing does not reveal which emails
+// are registered.
+static DUMMY_HASH: LazyLock<String> = LazyLock::new(|| {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(b"no password set", &salt)
+        .expect("hashing a fixed password succeeds")
+        .to_string()
+});
+
+// Argon2 is deliberately slow, so both helpers run on the blocking pool.
+pub async fn hash_password(password: String) -> Result<String, AppError> {
+    tokio::task::spawn_blocking(move || {
+        let salt = SaltString::generate(&mut OsRng);
+        Argon2::default()
+            .hash_password(password.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|err| AppError::InternalServerError(format!("Failed to hash password: {err}")))
+    })
+    .await
+    .map_err(|err| AppError::InternalServerError(format!("Failed to hash password: {err}")))?
+}
+
+/// `false` when there is no stored hash, after the same work as checking one.
+pub async fn verify_password(
+    password: String,
+    password_hash: Option<String>,
+) -> Result<bool, AppError> {
+    tokio::task::spawn_blocking(move || {
+        let stored = password_hash.as_deref().unwrap_or(&DUMMY_HASH);
+        let parsed = PasswordHash::new(stored).map_err(|err| {
+            AppError::InternalServerError(format!("Stored password hash is invalid: {err}"))
+        })?;
+
+        let matches = Argon2::default()
+            .verify_password(password.as_bytes(), &parsed)
+            .is_ok();
+        Ok(matches && password_hash.is_some())
+    })
+    .await
+    .map_err(|err| AppError::InternalServerError(format!("Failed to verify password: {err}")))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn hashed_password_verifies() {
+        let hash = hash_password("correct horse".to_string()).await.unwrap();
+
+        assert!(hash.starts_with("$argon2"));
+        assert!(
+            verify_password("correct horse".to_string(), Some(hash.clone()))
+                .await
+                .unwrap()
+        );
+        assert!(
+            !verify_password("battery staple".to_string(), Some(hash))
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn a_missing_hash_never_verifies() {
+        assert!(
+            !verify_password("no password set".to_string(), None)
+                .await
+                .unwrap()
+        );
+    }
+}
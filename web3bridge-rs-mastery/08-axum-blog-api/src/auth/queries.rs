@@ -0,0 +1,95 @@
+use crate::app::state::SharedState;
+use crate::error::AppError;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Returns the author id and stored hash for `email`; the hash is `None` until a password is set.
+pub async fn find_credentials_by_email(
+    state: &SharedState,
+    email: &str,
+) -> Result<Option<(Uuid, Option<String>)>, AppError> {
+    let credentials = sqlx::query_as::<_, (Uuid, Option<String>)>(
+        "SELECT id, password_hash FROM authors WHERE email = $1",
+    )
+    .bind(email)
+    .fetch_optional(&state.db_pool)
+    .await
+    .map_err(|err| AppError::InternalServerError(format!("Failed to fetch credentials: {err}")))?;
+
+    Ok(credentials)
+}
+
+/// Stores a reset token for the author with `email`, replacing any earlier one.
+///
+/// Returns the author id, or `None` when no author has that email.
+pub async fn create_password_reset(
+    state: &SharedState,
+    email: &str,
+    token_hash: &str,
+    expires_at: DateTime<Utc>,
+) -> Result<Option<Uuid>, AppError> {
+    let failed =
+        |err| AppError::InternalServerError(format!("Failed to create password reset: {err}"));
+    let mut tx = state.db_pool.begin().await.map_err(failed)?;
+
+    let author_id = sqlx::query_scalar::<_, Uuid>("SELECT id FROM authors WHERE email = $1")
+        .bind(email)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(failed)?;
+    let Some(author_id) = author_id else {
+        return Ok(None);
+    };
+
+    sqlx::query("DELETE FROM password_resets WHERE author_id = $1")
+        .bind(author_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(failed)?;
+    sqlx::query(
+        "INSERT INTO password_resets (token_hash, author_id, expires_at) VALUES ($1, $2, $3)",
+    )
+    .bind(token_hash)
+    .bind(author_id)
+    .bind(expires_at)
+    .execute(&mut *tx)
+    .await
+    .map_err(failed)?;
+
+    tx.commit().await.map_err(failed)?;
+    Ok(Some(author_id))
+}
+
+/// Redeems an unexpired reset token by setting its author's password; each token works once.
+///
+/// Returns the author id, or `None` when the token is unknown, used or expired.
+pub async fn redeem_password_reset(
+    state: &SharedState,
+    token_hash: &str,
+    password_hash: &str,
+) -> Result<Option<Uuid>, AppError> {
+    let failed = |err| AppError::InternalServerError(format!("Failed to reset password: {err}"));
+    let mut tx = state.db_pool.begin().await.map_err(failed)?;
+
+    let author_id = sqlx::query_scalar::<_, Uuid>(
+        "DELETE FROM password_resets WHERE token_hash = $1 AND expires_at > $2 RETURNING author_id",
+    )
+    .bind(token_hash)
+    .bind(Utc::now())
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(failed)?;
+    let Some(author_id) = author_id else {
+        return Ok(None);
+    };
+
+    sqlx::query("UPDATE authors SET password_hash = $2 WHERE id = $1")
+        .bind(author_id)
+        .bind(password_hash)
+        .execute(&mut *tx)
+        .await
+        .map_err(failed)?;
+
+    tx.commit().await.map_err(failed)?;
+    Ok(Some(author_id))
+}
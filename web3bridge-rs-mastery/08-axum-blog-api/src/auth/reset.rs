@@ -0,0 +1,33 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use sha2::{Digest, Sha256};
+
+/// How long an issued reset token can be redeemed.
+pub const RESET_TOKEN_TTL_SECONDS: i64 = 24 * 60 * 60;
+
+/// A new random reset token, handed out once and stored only as `reset_token_hash`.
+pub fn new_reset_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// What `password_resets` stores, so a leaked table cannot be redeemed. The tokens are
+/// random rather than chosen, so a plain hash is enough.
+pub fn reset_token_hash(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_are_random_and_hashed_stably() {
+        let token = new_reset_token();
+        assert_eq!(token.len(), 43);
+        assert_ne!(token, new_reset_token());
+        assert_eq!(reset_token_hash(&token), reset_token_hash(&token));
+        assert_ne!(reset_token_hash(&token), token);
+    }
+}
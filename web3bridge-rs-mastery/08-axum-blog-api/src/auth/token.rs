@@ -0,0 +1,91 @@
+use crate::auth::models::Claims;
+use crate::error::{AppError, ErrorCode};
+use chrono::Utc;
+use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use secrecy::{ExposeSecret, SecretString};
+use std::fmt;
+use uuid::Uuid;
+
+/// HS256 signing material plus the lifetime given to issued tokens.
+#[derive(Clone)]
+pub struct JwtKeys {
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+    pub ttl_seconds: i64,
+}
+
+impl JwtKeys {
+    pub fn new(secret: &SecretString, ttl_seconds: i64) -> Self {
+        let secret = secret.expose_secret().as_bytes();
+        Self {
+            encoding: EncodingKey::from_secret(secret),
+            decoding: DecodingKey::from_secret(secret),
+            ttl_seconds,
+        }
+    }
+
+    pub fn issue(&self, author_id: Uuid) -> Result<String, AppError> {
+        let now = Utc::now().timestamp();
+        let claims = Claims {
+            sub: author_id.to_string(),
+            iat: now,
+            exp: now + self.ttl_seconds,
+        };
+
+        encode(&Header::default(), &claims, &self.encoding)
+            .map_err(|err| AppError::InternalServerError(format!("Failed to issue token: {err}")))
+    }
+
+    pub fn verify(&self, token: &str) -> Result<Uuid, AppError> {
+        let data =
+            decode::<Claims>(token, &self.decoding, &Validation::default()).map_err(|_| {
+                AppError::Unauthorized(
+                    ErrorCode::AuthInvalidToken,
+                    "Invalid or expired token".to_string(),
+                )
+            })?;
+
+        Uuid::parse_str(&data.claims.sub).map_err(|_| {
+            AppError::Unauthorized(
+                ErrorCode::AuthInvalidToken,
+                "Invalid or expired token".to_string(),
+            )
+        })
+    }
+}
+
+impl fmt::Debug for JwtKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JwtKeys")
+            .field("ttl_seconds", &self.ttl_seconds)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(secret: &str) -> JwtKeys {
+        JwtKeys::new(&SecretString::from(secret), 60)
+    }
+
+    #[test]
+    fn issued_token_verifies() {
+        let keys = keys("test-secret");
+        let author_id = Uuid::new_v4();
+
+        let token = keys.issue(author_id).unwrap();
+        assert_eq!(keys.verify(&token).unwrap(), author_id);
+    }
+
+    #[test]
+    fn token_signed_with_other_secret_is_rejected() {
+        let token = keys("one-secret").issue(Uuid::new_v4()).unwrap();
+
+        assert!(matches!(
+            keys("another-secret").verify(&token),
+            Err(AppError::Unauthorized(ErrorCode::AuthInvalidToken, _))
+        ));
+    }
+}
@@ -0,0 +1,51 @@
+use crate::author::models::Author;
+use crate::cache::{self, Cache};
+use crate::config::CacheConfig;
+use crate::pagination::{Cursor, Page};
+use crate::telemetry::Metrics;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// The caches behind `author::queries`: single authors, listing pages and the total.
+///
+/// Listings are not filtered, so every author write drops all of them.
+#[derive(Debug, Clone)]
+pub struct AuthorCache {
+    pub(super) by_id: Arc<dyn Cache<Uuid, Author>>,
+    pub(super) lists: Arc<dyn Cache<ListKey, Page<Author>>>,
+    pub(super) count: Arc<dyn Cache<(), i64>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(super) struct ListKey {
+    pub cursor: Option<Cursor>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl AuthorCache {
+    pub fn new(config: &CacheConfig, metrics: &Metrics) -> Self {
+        Self {
+            by_id: cache::build("authors", config, metrics),
+            lists: cache::build("author_lists", config, metrics),
+            count: cache::build("author_counts", config, metrics),
+        }
+    }
+
+    /// After an author is created.
+    pub fn invalidate_listings(&self) {
+        self.lists.clear();
+        self.count.clear();
+    }
+
+    /// After the author is updated or deleted.
+    pub fn invalidate_author(&self, id: Uuid) {
+        self.by_id.remove(&id);
+        self.invalidate_listings();
+    }
+
+    pub fn clear(&self) {
+        self.by_id.clear();
+        self.invalidate_listings();
+    }
+}
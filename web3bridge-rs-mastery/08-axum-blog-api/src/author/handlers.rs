@@ -1,40 +1,55 @@
 use crate::app::state::SharedState;
+use crate::auth::{AuthAuthor, hash_password};
 use crate::author::models::{AuthorFilter, AuthorListResponse, AuthorResponse, CreateAuthorRequest, UpdateAuthorRequest};
-use crate::author::queries;
-use crate::error::AppError;
+use crate::error::{AppError, ErrorCode, ErrorResponse};
+use crate::extract::{Path, Query};
+use crate::pagination::Cursor;
+use crate::response::MessageResponse;
+use crate::validation::{ValidJson, parse_uuid};
 use axum::{
     Json,
-    extract::{Path, Query, State},
-    http::StatusCode,
-    response::IntoResponse,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
 };
-use serde_json::json;
-use uuid::Uuid;
 
+#[utoipa::path(
+    post,
+    path = "/authors",
+    tag = "authors",
+    request_body = CreateAuthorRequest,
+    responses(
+        (status = 201, description = "Author created", body = AuthorResponse, headers(("ETag" = String))),
+        (status = 422, description = "Invalid input", body = ErrorResponse),
+    )
+)]
 pub async fn create_author(
     State(state): State<SharedState>,
-    Json(payload): Json<CreateAuthorRequest>,
+    ValidJson(payload): ValidJson<CreateAuthorRequest>,
 ) -> Result<impl IntoResponse, AppError> {
-    let name = payload.name.trim();
-    if name.is_empty() {
-        return Err(AppError::UnProcessableEntity {
-            field: "name".to_string(),
-            message: "Name cannot be empty".to_string(),
-        });
-    }
-
-    let email = payload.email.trim().to_lowercase();
-    if !is_valid_email(&email) {
-        return Err(AppError::UnProcessableEntity {
-            field: "email".to_string(),
-            message: "Invalid email format".to_string(),
-        });
-    }
+    let password_hash = hash_password(payload.password).await?;
 
-    let author = queries::insert_author(&state, name, &email).await?;
-    Ok((StatusCode::CREATED, Json(AuthorResponse::from(author))))
+    let author = state
+        .authors
+        .insert(&payload.name, &payload.email, &password_hash)
+        .await?;
+    Ok((
+        StatusCode::CREATED,
+        author.etag(),
+        Json(AuthorResponse::from(author)),
+    ))
 }
 
+#[utoipa::path(
+    get,
+    path = "/authors",
+    tag = "authors",
+    params(AuthorFilter),
+    responses(
+        (status = 200, description = "One page of authors", body = AuthorListResponse),
+        (status = 422, description = "Invalid input", body = ErrorResponse),
+    )
+)]
 pub async fn get_authors(
     State(state): State<SharedState>,
     Query(filter): Query<AuthorFilter>,
@@ -42,89 +57,373 @@ pub async fn get_authors(
     let limit = filter.limit.unwrap_or(20).clamp(1, 100);
     let offset = filter.offset.unwrap_or(0).max(0);
 
-    let authors = queries::list_authors(&state, limit, offset).await?;
-    let total = authors.len();
-    let data = authors.into_iter().map(AuthorResponse::from).collect();
+    let cursor = filter.cursor.as_deref().map(Cursor::decode).transpose()?;
+
+    let page = state.authors.list(cursor, limit, offset).await?;
+    let total = if filter.include_total.unwrap_or(true) {
+        Some(state.authors.count().await?)
+    } else {
+        None
+    };
+    let data = page.items.into_iter().map(AuthorResponse::from).collect();
+    let next_cursor = page.next_cursor.map(|cursor| cursor.encode());
 
-    Ok(Json(AuthorListResponse { data, total }))
+    Ok(Json(AuthorListResponse {
+        data,
+        total,
+        limit,
+        offset: if cursor.is_some() { 0 } else { offset },
+        has_more: page.has_more,
+        next_cursor,
+    }))
 }
 
+#[utoipa::path(
+    get,
+    path = "/authors/{id}",
+    tag = "authors",
+    params(
+        ("id" = String, Path, description = "Author UUID"),
+        ("If-None-Match" = Option<String>, Header, description = "ETag of a cached copy"),
+    ),
+    responses(
+        (status = 200, description = "The author", body = AuthorResponse, headers(("ETag" = String))),
+        (status = 304, description = "The cached copy is current"),
+        (status = 404, description = "Author not found", body = ErrorResponse),
+        (status = 422, description = "Invalid input", body = ErrorResponse),
+    )
+)]
 pub async fn get_author_by_id(
     State(state): State<SharedState>,
+    headers: HeaderMap,
     Path(id): Path<String>,
-) -> Result<Json<AuthorResponse>, AppError> {
-    let author_id = parse_uuid_field("id", &id)?;
-    let author = queries::find_author_by_id(&state, author_id).await?;
-    Ok(Json(AuthorResponse::from(author)))
+) -> Result<Response, AppError> {
+    let author_id = parse_uuid("id", &id)?;
+    let author = state.authors.find_by_id(author_id).await?;
+
+    let etag = author.etag();
+    if etag.is_fresh(&headers) {
+        return Ok(etag.not_modified());
+    }
+    Ok((etag, Json(AuthorResponse::from(author))).into_response())
 }
 
+#[utoipa::path(
+    patch,
+    path = "/authors/{id}",
+    tag = "authors",
+    params(
+        ("id" = String, Path, description = "Author UUID"),
+        ("If-Match" = String, Header, description = "ETag from the last read"),
+    ),
+    request_body = UpdateAuthorRequest,
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Updated author", body = AuthorResponse, headers(("ETag" = String))),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not the owner", body = ErrorResponse),
+        (status = 404, description = "Author not found", body = ErrorResponse),
+        (status = 412, description = "Changed since the given ETag", body = ErrorResponse),
+        (status = 422, description = "Invalid input", body = ErrorResponse),
+        (status = 428, description = "If-Match is missing", body = ErrorResponse),
+    )
+)]
 pub async fn patch_author(
     State(state): State<SharedState>,
+    auth: AuthAuthor,
+    headers: HeaderMap,
     Path(id): Path<String>,
-    Json(payload): Json<UpdateAuthorRequest>,
-) -> Result<Json<AuthorResponse>, AppError> {
-    let author_id = parse_uuid_field("id", &id)?;
-
-    let name = payload.name.as_deref().map(str::trim);
-    if let Some(name) = name {
-        if name.is_empty() {
-            return Err(AppError::UnProcessableEntity {
-                field: "name".to_string(),
-                message: "Name cannot be empty".to_string(),
-            });
-        }
-    }
+    ValidJson(payload): ValidJson<UpdateAuthorRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let author_id = parse_uuid("id", &id)?;
+    auth.ensure_owner(author_id)?;
+    let current = state.authors.find_by_id(author_id).await?;
+    current.etag().require_if_match(&headers)?;
 
-    let email_owned = payload.email.map(|value| value.trim().to_lowercase());
-    if let Some(email) = &email_owned {
-        if !is_valid_email(email) {
-            return Err(AppError::UnProcessableEntity {
-                field: "email".to_string(),
-                message: "Invalid email format".to_string(),
-            });
-        }
+    if payload.name.is_none() && payload.email.is_none() && payload.password.is_none() {
+        return Err(AppError::invalid(
+            "body",
+            ErrorCode::ValidationEmptyUpdate,
+            "At least one of name, email or password must be provided",
+        ));
     }
 
-    let email = email_owned.as_deref();
+    let password_hash = match payload.password {
+        Some(password) => Some(hash_password(password).await?),
+        None => None,
+    };
 
-    if name.is_none() && email.is_none() {
-        return Err(AppError::UnProcessableEntity {
-            field: "body".to_string(),
-            message: "At least one of name or email must be provided".to_string(),
-        });
-    }
-
-    let author = queries::update_author(&state, author_id, name, email).await?;
-    Ok(Json(AuthorResponse::from(author)))
+    let author = state
+        .authors
+        .update(
+            author_id,
+            current.version,
+            payload.name.as_deref(),
+            payload.email.as_deref(),
+            password_hash.as_deref(),
+        )
+        .await?;
+    Ok((author.etag(), Json(AuthorResponse::from(author))))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/authors/{id}",
+    tag = "authors",
+    params(
+        ("id" = String, Path, description = "Author UUID"),
+        ("If-Match" = String, Header, description = "ETag from the last read"),
+    ),
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Author deleted", body = MessageResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not the owner", body = ErrorResponse),
+        (status = 404, description = "Author not found", body = ErrorResponse),
+        (status = 412, description = "Changed since the given ETag", body = ErrorResponse),
+        (status = 428, description = "If-Match is missing", body = ErrorResponse),
+    )
+)]
 pub async fn delete_author(
     State(state): State<SharedState>,
+    auth: AuthAuthor,
+    headers: HeaderMap,
     Path(id): Path<String>,
 ) -> Result<impl IntoResponse, AppError> {
-    let author_id = parse_uuid_field("id", &id)?;
-    queries::delete_author(&state, author_id).await?;
+    let author_id = parse_uuid("id", &id)?;
+    auth.ensure_owner(author_id)?;
+    let current = state.authors.find_by_id(author_id).await?;
+    current.etag().require_if_match(&headers)?;
+    state.authors.delete(author_id, current.version).await?;
 
     Ok((
         StatusCode::OK,
-        Json(json!({
-            "status": "ok",
-            "message": "Author deleted successfully"
-        })),
+        Json(MessageResponse::ok("Author deleted successfully")),
     ))
 }
 
-fn parse_uuid_field(field: &str, value: &str) -> Result<Uuid, AppError> {
-    Uuid::parse_str(value).map_err(|_| AppError::UnProcessableEntity {
-        field: field.to_string(),
-        message: "Invalid UUID".to_string(),
-    })
-}
+#[cfg(test)]
+mod tests {
+    use crate::app::state::AppState;
+    use crate::app::test_support::{current_etag, send, send_with_headers, signed_in_author};
+    use axum::http::{
+        Method, StatusCode,
+        header::{ETAG, IF_MATCH, IF_NONE_MATCH},
+    };
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn created_author_can_be_fetched_and_duplicate_email_is_rejected() {
+        let state = AppState::for_tests_in_memory();
+        let body =
+            json!({ "name": " Ada ", "email": "Ada@Example.com", "password": "correct horse" });
+
+        let (status, created) =
+            send(&state, Method::POST, "/authors", None, Some(body.clone())).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(created["name"], "Ada");
+        assert_eq!(created["email"], "ada@example.com");
+        assert!(created.get("password_hash").is_none());
+
+        let uri = format!("/authors/{}", created["id"].as_str().unwrap());
+        let (status, fetched) = send(&state, Method::GET, &uri, None, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(fetched, created);
+
+        let (status, error) = send(&state, Method::POST, "/authors", None, Some(body)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error["code"], "author.email_taken");
+        assert_eq!(error["errors"][0]["field"], "email");
+    }
+
+    #[tokio::test]
+    async fn invalid_input_is_rejected_with_the_offending_field() {
+        let state = AppState::for_tests_in_memory();
+
+        for (body, field) in [
+            (
+                json!({ "name": " ", "email": "a@b.co", "password": "long enough" }),
+                "name",
+            ),
+            (
+                json!({ "name": "A", "email": "nope", "password": "long enough" }),
+                "email",
+            ),
+            (
+                json!({ "name": "A", "email": "a@b.co", "password": "short" }),
+                "password",
+            ),
+        ] {
+            let (status, error) = send(&state, Method::POST, "/authors", None, Some(body)).await;
+            assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+            assert_eq!(error["errors"][0]["field"], field);
+        }
 
-fn is_valid_email(email: &str) -> bool {
-    let mut parts = email.split('@');
-    let local = parts.next().unwrap_or("");
-    let domain = parts.next().unwrap_or("");
+        let body = json!({ "name": " ", "email": "nope", "password": "short" });
+        let (status, error) = send(&state, Method::POST, "/authors", None, Some(body)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error["code"], "validation.failed");
+        let fields: Vec<_> = error["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|error| error["field"].as_str().unwrap())
+            .collect();
+        assert_eq!(fields, ["email", "name", "password"]);
 
-    !local.is_empty() && domain.contains('.') && parts.next().is_none()
+        let (status, error) = send(&state, Method::GET, "/authors/not-a-uuid", None, None).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error["errors"][0]["field"], "id");
+    }
+
+    #[tokio::test]
+    async fn column_limits_and_malformed_bodies_are_rejected() {
+        let state = AppState::for_tests_in_memory();
+
+        for (body, field, code) in [
+            (
+                json!({ "name": "x".repeat(256), "email": "a@b.co", "password": "long enough" }),
+                "name",
+                "validation.invalid_length",
+            ),
+            (
+                json!({ "name": "A", "email": "a..b@example.com", "password": "long enough" }),
+                "email",
+                "validation.invalid_email",
+            ),
+            (json!({ "name": "A" }), "body", "request.invalid_body"),
+        ] {
+            let (status, error) = send(&state, Method::POST, "/authors", None, Some(body)).await;
+            assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+            assert_eq!(error["errors"][0]["field"], field);
+            assert_eq!(error["code"], code);
+        }
+    }
+
+    #[tokio::test]
+    async fn listing_pages_with_a_cursor() {
+        let state = AppState::for_tests_in_memory();
+        for n in 0..3 {
+            signed_in_author(&state, &format!("author{n}@example.com")).await;
+        }
+
+        let (status, first) = send(&state, Method::GET, "/authors?limit=2", None, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(first["total"], 3);
+        assert_eq!(first["data"].as_array().unwrap().len(), 2);
+        assert_eq!(first["has_more"], true);
+
+        let uri = format!(
+            "/authors?limit=2&cursor={}",
+            first["next_cursor"].as_str().unwrap()
+        );
+        let (_, second) = send(&state, Method::GET, &uri, None, None).await;
+        assert_eq!(second["data"].as_array().unwrap().len(), 1);
+        assert_eq!(second["has_more"], false);
+        assert!(second["next_cursor"].is_null());
+    }
+
+    #[tokio::test]
+    async fn only_the_author_can_update_or_delete_their_account() {
+        let state = AppState::for_tests_in_memory();
+        let (author_id, token) = signed_in_author(&state, "owner@example.com").await;
+        let (_, other_token) = signed_in_author(&state, "other@example.com").await;
+        let uri = format!("/authors/{author_id}");
+        let rename = json!({ "name": "Renamed" });
+
+        let (status, _) = send(&state, Method::PATCH, &uri, None, Some(rename.clone())).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = send(
+            &state,
+            Method::PATCH,
+            &uri,
+            Some(&other_token),
+            Some(rename.clone()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let etag = current_etag(&state, &uri, None).await;
+        let if_match = [(IF_MATCH, etag.as_str())];
+        let (status, _, updated) = send_with_headers(
+            &state,
+            Method::PATCH,
+            &uri,
+            Some(&token),
+            &if_match,
+            Some(rename),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(updated["name"], "Renamed");
+
+        let etag = current_etag(&state, &uri, None).await;
+        let if_match = [(IF_MATCH, etag.as_str())];
+        let (status, _, _) =
+            send_with_headers(&state, Method::DELETE, &uri, Some(&token), &if_match, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = send(&state, Method::GET, &uri, None, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn conditional_requests_use_the_etag() {
+        let state = AppState::for_tests_in_memory();
+        let (author_id, token) = signed_in_author(&state, "owner@example.com").await;
+        let uri = format!("/authors/{author_id}");
+        let rename = json!({ "name": "Renamed" });
+
+        let (status, headers, _) =
+            send_with_headers(&state, Method::GET, &uri, None, &[], None).await;
+        assert_eq!(status, StatusCode::OK);
+        let etag = headers[ETAG].to_str().unwrap().to_string();
+
+        let if_none_match = [(IF_NONE_MATCH, etag.as_str())];
+        let (status, headers, body) =
+            send_with_headers(&state, Method::GET, &uri, None, &if_none_match, None).await;
+        assert_eq!(status, StatusCode::NOT_MODIFIED);
+        assert_eq!(headers[ETAG], etag.as_str());
+        assert!(body.is_null());
+
+        let (status, _) = send(
+            &state,
+            Method::PATCH,
+            &uri,
+            Some(&token),
+            Some(rename.clone()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::PRECONDITION_REQUIRED);
+
+        let if_match = [(IF_MATCH, etag.as_str())];
+        let (status, headers, _) = send_with_headers(
+            &state,
+            Method::PATCH,
+            &uri,
+            Some(&token),
+            &if_match,
+            Some(rename.clone()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_ne!(headers[ETAG], etag.as_str());
+
+        // A second editor still holding the old ETag is refused instead of overwriting.
+        let (status, _, _) = send_with_headers(
+            &state,
+            Method::PATCH,
+            &uri,
+            Some(&token),
+            &if_match,
+            Some(rename),
+        )
+        .await;
+        assert_eq!(status, StatusCode::PRECONDITION_FAILED);
+        let (status, _, _) =
+            send_with_headers(&state, Method::DELETE, &uri, Some(&token), &if_match, None).await;
+        assert_eq!(status, StatusCode::PRECONDITION_FAILED);
+        let (status, _, _) =
+            send_with_headers(&state, Method::GET, &uri, None, &if_none_match, None).await;
+        assert_eq!(status, StatusCode::OK);
+    }
 }
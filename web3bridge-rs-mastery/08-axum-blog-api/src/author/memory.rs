@@ -0,0 +1,164 @@
+use crate::author::models::Author;
+use crate::author::repository::AuthorRepository;
+use crate::error::{AppError, ErrorCode};
+use crate::pagination::{Cursor, Page};
+use async_trait::async_trait;
+use chrono::Utc;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// Keeps authors in a `Vec` so handler tests run without a database.
+#[derive(Debug, Default)]
+pub struct InMemoryAuthorRepository {
+    authors: Mutex<Vec<Author>>,
+}
+
+impl InMemoryAuthorRepository {
+    fn email_taken(authors: &[Author], email: &str, except: Option<Uuid>) -> Result<(), AppError> {
+        if authors
+            .iter()
+            .any(|author| author.email == email && Some(author.id) != except)
+        {
+            return Err(AppError::invalid(
+                "email",
+                ErrorCode::AuthorEmailTaken,
+                "Email already exists",
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn check_version(stored: i64, expected: i64) -> Result<(), AppError> {
+    if stored != expected {
+        return Err(AppError::PreconditionFailed(
+            "Author was modified by another request".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl AuthorRepository for InMemoryAuthorRepository {
+    async fn insert(
+        &self,
+        name: &str,
+        email: &str,
+        _password_hash: &str,
+    ) -> Result<Author, AppError> {
+        let mut authors = self.authors.lock().unwrap();
+        Self::email_taken(&authors, email, None)?;
+
+        let now = Utc::now();
+        let author = Author {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            email: email.to_string(),
+            version: 1,
+            created_at: now,
+            updated_at: now,
+        };
+        authors.push(author.clone());
+        Ok(author)
+    }
+
+    async fn list(
+        &self,
+        cursor: Option<Cursor>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Page<Author>, AppError> {
+        let mut rows = self.authors.lock().unwrap().clone();
+        rows.sort_by_key(|row| std::cmp::Reverse((row.created_at, row.id)));
+
+        let rows: Vec<Author> = match cursor {
+            Some(cursor) => rows
+                .into_iter()
+                .filter(|author| (author.created_at, author.id) < (cursor.created_at, cursor.id))
+                .collect(),
+            None => rows
+                .into_iter()
+                .skip(usize::try_from(offset).unwrap_or(0))
+                .collect(),
+        };
+        let rows = rows
+            .into_iter()
+            .take(usize::try_from(limit + 1).unwrap_or(0))
+            .collect();
+
+        Ok(Page::from_rows(rows, limit, |author| {
+            Cursor::new(author.created_at, author.id)
+        }))
+    }
+
+    async fn count(&self) -> Result<i64, AppError> {
+        Ok(self.authors.lock().unwrap().len() as i64)
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Author, AppError> {
+        self.authors
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|author| author.id == id)
+            .cloned()
+            .ok_or_else(|| {
+                AppError::NotFound(ErrorCode::AuthorNotFound, format!("Author not found: {id}"))
+            })
+    }
+
+    async fn update(
+        &self,
+        id: Uuid,
+        expected_version: i64,
+        name: Option<&str>,
+        email: Option<&str>,
+        _password_hash: Option<&str>,
+    ) -> Result<Author, AppError> {
+        let mut authors = self.authors.lock().unwrap();
+        if let Some(email) = email {
+            Self::email_taken(&authors, email, Some(id))?;
+        }
+
+        let author = authors
+            .iter_mut()
+            .find(|author| author.id == id)
+            .ok_or_else(|| {
+                AppError::NotFound(ErrorCode::AuthorNotFound, format!("Author not found: {id}"))
+            })?;
+        check_version(author.version, expected_version)?;
+
+        if let Some(name) = name {
+            author.name = name.to_string();
+        }
+        if let Some(email) = email {
+            author.email = email.to_string();
+        }
+        author.version += 1;
+        author.updated_at = Utc::now();
+        Ok(author.clone())
+    }
+
+    async fn delete(&self, id: Uuid, expected_version: i64) -> Result<(), AppError> {
+        let mut authors = self.authors.lock().unwrap();
+        let index = authors
+            .iter()
+            .position(|author| author.id == id)
+            .ok_or_else(|| {
+                AppError::NotFound(ErrorCode::AuthorNotFound, format!("Author not found: {id}"))
+            })?;
+        check_version(authors[index].version, expected_version)?;
+
+        authors.remove(index);
+        Ok(())
+    }
+
+    async fn exists(&self, id: Uuid) -> Result<bool, AppError> {
+        Ok(self
+            .authors
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|author| author.id == id))
+    }
+}
@@ -1,14 +1,33 @@
+mod cache;
 mod handlers;
+#[cfg(test)]
+mod memory;
 pub mod models;
 mod queries;
+mod repository;
 
 use crate::app::state::SharedState;
-use crate::error::AppError;
 use axum::{
     Router,
     routing::{get, post},
 };
-use uuid::Uuid;
+use utoipa::OpenApi;
+
+pub use cache::AuthorCache;
+#[cfg(test)]
+pub use memory::InMemoryAuthorRepository;
+pub use repository::{AuthorRepository, SqlAuthorRepository};
+
+/// OpenAPI paths for the routes under `/authors`.
+#[derive(OpenApi)]
+#[openapi(paths(
+    handlers::create_author,
+    handlers::get_authors,
+    handlers::get_author_by_id,
+    handlers::patch_author,
+    handlers::delete_author,
+))]
+pub struct AuthorApi;
 
 pub fn author_routes() -> Router<SharedState> {
     Router::new()
@@ -23,7 +42,3 @@ pub fn author_routes() -> Router<SharedState> {
                 .delete(handlers::delete_author),
         )
 }
-
-pub async fn author_exists(state: &SharedState, id: Uuid) -> Result<bool, AppError> {
-    queries::author_exists(state, id).await
-}
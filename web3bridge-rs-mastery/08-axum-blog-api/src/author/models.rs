@@ -1,36 +1,65 @@
+use crate::auth::MIN_PASSWORD_LENGTH;
+use crate::etag::ETag;
+use crate::validation::{self, MAX_VARCHAR_LENGTH};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
+use validator::Validate;
 
 #[derive(Debug, Clone, FromRow)]
 pub struct Author {
     pub id: Uuid,
     pub name: String,
     pub email: String,
+    /// Incremented on every update; see `crate::etag`.
+    pub version: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize)]
+impl Author {
+    pub fn etag(&self) -> ETag {
+        ETag::new(self.version, self.updated_at)
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
 pub struct CreateAuthorRequest {
+    #[serde(deserialize_with = "validation::trim")]
+    #[validate(length(min = 1, max = MAX_VARCHAR_LENGTH))]
     pub name: String,
+    /// Stored lowercased.
+    #[serde(deserialize_with = "validation::email_address")]
+    #[validate(custom(function = "validation::email"))]
     pub email: String,
+    #[validate(length(min = MIN_PASSWORD_LENGTH))]
+    pub password: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema, Validate)]
 pub struct UpdateAuthorRequest {
+    #[serde(default, deserialize_with = "validation::trim_opt")]
+    #[validate(length(min = 1, max = MAX_VARCHAR_LENGTH))]
     pub name: Option<String>,
+    #[serde(default, deserialize_with = "validation::email_address_opt")]
+    #[validate(custom(function = "validation::email"))]
     pub email: Option<String>,
+    #[validate(length(min = MIN_PASSWORD_LENGTH))]
+    pub password: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct AuthorFilter {
     pub limit: Option<i64>,
     pub offset: Option<i64>,
+    pub cursor: Option<String>,
+    pub include_total: Option<bool>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct AuthorResponse {
     pub id: String,
     pub name: String,
@@ -39,10 +68,14 @@ pub struct AuthorResponse {
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct AuthorListResponse {
     pub data: Vec<AuthorResponse>,
-    pub total: usize,
+    pub total: Option<i64>,
+    pub limit: i64,
+    pub offset: i64,
+    pub has_more: bool,
+    pub next_cursor: Option<String>,
 }
 
 impl From<Author> for AuthorResponse {
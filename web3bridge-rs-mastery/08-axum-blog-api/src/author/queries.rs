@@ -1,120 +1,229 @@
-use crate::app::state::SharedState;
+use crate::author::cache::{AuthorCache, ListKey};
 use crate::author::models::Author;
-use crate::error::AppError;
+use crate::cache::get_or_load;
+use crate::db::{self, DbPool, NOW};
+use crate::error::{AppError, ErrorCode};
+use crate::outbox;
+use crate::pagination::{self, Cursor, Page};
+use crate::post::models::Post;
+use crate::post::{PostCache, PostEventKind, PostEvents, event_payload};
 use sqlx::QueryBuilder;
 use uuid::Uuid;
 
-pub async fn insert_author(state: &SharedState, name: &str, email: &str) -> Result<Author, AppError> {
+pub async fn insert_author(
+    pool: &DbPool,
+    cache: &AuthorCache,
+    name: &str,
+    email: &str,
+    password_hash: &str,
+) -> Result<Author, AppError> {
     let author_id = Uuid::new_v4();
 
     let author = sqlx::query_as::<_, Author>(
-        "INSERT INTO authors (id, name, email) VALUES ($1, $2, $3) RETURNING id, name, email, created_at, updated_at",
+        "INSERT INTO authors (id, name, email, password_hash) VALUES ($1, $2, $3, $4) RETURNING id, name, email, version, created_at, updated_at",
     )
     .bind(author_id)
     .bind(name)
     .bind(email)
-    .fetch_one(&state.db_pool)
+    .bind(password_hash)
+    .fetch_one(pool)
     .await
     .map_err(|err| {
-        if let sqlx::Error::Database(db_err) = &err {
-            if db_err.code().as_deref() == Some("23505") {
-                return AppError::UnProcessableEntity {
-                    field: "email".to_string(),
-                    message: "Email already exists".to_string(),
-                };
-            }
+        if db::is_unique_violation(&err) {
+            return AppError::invalid(
+                "email",
+                ErrorCode::AuthorEmailTaken,
+                "Email already exists",
+            );
         }
         AppError::InternalServerError(format!("Failed to create author: {err}"))
     })?;
+    cache.invalidate_listings();
 
     Ok(author)
 }
 
 pub async fn list_authors(
-    state: &SharedState,
+    pool: &DbPool,
+    cache: &AuthorCache,
+    cursor: Option<Cursor>,
     limit: i64,
     offset: i64,
-) -> Result<Vec<Author>, AppError> {
+) -> Result<Page<Author>, AppError> {
+    let key = ListKey {
+        cursor,
+        limit,
+        offset,
+    };
+    get_or_load(
+        &*cache.lists,
+        key,
+        load_authors(pool, cursor, limit, offset),
+    )
+    .await
+}
+
+async fn load_authors(
+    pool: &DbPool,
+    cursor: Option<Cursor>,
+    limit: i64,
+    offset: i64,
+) -> Result<Page<Author>, AppError> {
     let mut builder = QueryBuilder::new(
-        "SELECT id, name, email, created_at, updated_at FROM authors",
+        "SELECT id, name, email, version, created_at, updated_at FROM authors WHERE TRUE",
     );
-
-    builder
-        .push(" ORDER BY created_at DESC LIMIT ")
-        .push_bind(limit)
-        .push(" OFFSET ")
-        .push_bind(offset);
+    pagination::push_after(&mut builder, cursor);
+    builder.push(" ORDER BY ");
+    pagination::push_page_bounds(&mut builder, cursor, limit, offset);
 
     let authors = builder
         .build_query_as::<Author>()
-        .fetch_all(&state.db_pool)
+        .fetch_all(pool)
         .await
         .map_err(|err| AppError::InternalServerError(format!("Failed to fetch authors: {err}")))?;
 
-    Ok(authors)
+    Ok(Page::from_rows(authors, limit, |author| {
+        Cursor::new(author.created_at, author.id)
+    }))
+}
+
+pub async fn count_authors(pool: &DbPool, cache: &AuthorCache) -> Result<i64, AppError> {
+    get_or_load(&*cache.count, (), load_count(pool)).await
+}
+
+async fn load_count(pool: &DbPool) -> Result<i64, AppError> {
+    let total = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM authors")
+        .fetch_one(pool)
+        .await
+        .map_err(|err| AppError::InternalServerError(format!("Failed to count authors: {err}")))?;
+
+    Ok(total)
+}
+
+pub async fn find_author_by_id(
+    pool: &DbPool,
+    cache: &AuthorCache,
+    id: Uuid,
+) -> Result<Author, AppError> {
+    get_or_load(&*cache.by_id, id, load_author(pool, id)).await
 }
 
-pub async fn find_author_by_id(state: &SharedState, id: Uuid) -> Result<Author, AppError> {
+async fn load_author(pool: &DbPool, id: Uuid) -> Result<Author, AppError> {
     let author = sqlx::query_as::<_, Author>(
-        "SELECT id, name, email, created_at, updated_at FROM authors WHERE id = $1",
+        "SELECT id, name, email, version, created_at, updated_at FROM authors WHERE id = $1",
     )
     .bind(id)
-    .fetch_optional(&state.db_pool)
+    .fetch_optional(pool)
     .await
     .map_err(|err| AppError::InternalServerError(format!("Failed to fetch author: {err}")))?
-    .ok_or_else(|| AppError::NotFound(format!("Author not found: {id}")))?;
+    .ok_or_else(|| {
+        AppError::NotFound(ErrorCode::AuthorNotFound, format!("Author not found: {id}"))
+    })?;
 
     Ok(author)
 }
 
 pub async fn update_author(
-    state: &SharedState,
+    pool: &DbPool,
+    cache: &AuthorCache,
     id: Uuid,
+    expected_version: i64,
     name: Option<&str>,
     email: Option<&str>,
+    password_hash: Option<&str>,
 ) -> Result<Author, AppError> {
     let author = sqlx::query_as::<_, Author>(
-        "UPDATE authors SET name = COALESCE($2, name), email = COALESCE($3, email), updated_at = NOW() WHERE id = $1 RETURNING id, name, email, created_at, updated_at",
+        &format!("UPDATE authors SET name = COALESCE($2, name), email = COALESCE($3, email), password_hash = COALESCE($4, password_hash), version = version + 1, updated_at = {NOW} WHERE id = $1 AND version = $5 RETURNING id, name, email, version, created_at, updated_at"),
     )
     .bind(id)
     .bind(name)
     .bind(email)
-    .fetch_optional(&state.db_pool)
+    .bind(password_hash)
+    .bind(expected_version)
+    .fetch_optional(pool)
     .await
     .map_err(|err| {
-        if let sqlx::Error::Database(db_err) = &err {
-            if db_err.code().as_deref() == Some("23505") {
-                return AppError::UnProcessableEntity {
-                    field: "email".to_string(),
-                    message: "Email already exists".to_string(),
-                };
-            }
+        if db::is_unique_violation(&err) {
+            return AppError::invalid(
+                "email",
+                ErrorCode::AuthorEmailTaken,
+                "Email already exists",
+            );
         }
         AppError::InternalServerError(format!("Failed to update author: {err}"))
-    })?
-    .ok_or_else(|| AppError::NotFound(format!("Author not found: {id}")))?;
+    })?;
+
+    let Some(author) = author else {
+        return Err(missing_or_stale(pool, id).await);
+    };
+    cache.invalidate_author(id);
 
     Ok(author)
 }
 
-pub async fn delete_author(state: &SharedState, id: Uuid) -> Result<(), AppError> {
-    let result = sqlx::query("DELETE FROM authors WHERE id = $1")
+/// `ON DELETE CASCADE` removes the author's posts too, so `post_cache` forgets them and
+/// each live one is announced as deleted, to the outbox and to `post_events`.
+pub async fn delete_author(
+    pool: &DbPool,
+    cache: &AuthorCache,
+    post_cache: &PostCache,
+    post_events: &PostEvents,
+    id: Uuid,
+    expected_version: i64,
+) -> Result<(), AppError> {
+    let failed =
+        |err: sqlx::Error| AppError::InternalServerError(format!("Failed to delete author: {err}"));
+    let mut tx = pool.begin().await.map_err(failed)?;
+
+    let posts = sqlx::query_as::<_, Post>(
+        "SELECT id, title, content, content_html, author_id, status, published_at, is_premium, deleted_at, version, created_at, updated_at FROM posts WHERE author_id = $1 AND deleted_at IS NULL",
+    )
+    .bind(id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(failed)?;
+
+    let result = sqlx::query("DELETE FROM authors WHERE id = $1 AND version = $2")
         .bind(id)
-        .execute(&state.db_pool)
+        .bind(expected_version)
+        .execute(&mut *tx)
         .await
-        .map_err(|err| AppError::InternalServerError(format!("Failed to delete author: {err}")))?;
-
+        .map_err(failed)?;
     if result.rows_affected() == 0 {
-        return Err(AppError::NotFound(format!("Author not found: {id}")));
+        return Err(missing_or_stale(pool, id).await);
+    }
+
+    for post in &posts {
+        outbox::enqueue(&mut tx, outbox::POST_DELETED, post.id, event_payload(post)).await?;
+    }
+    tx.commit().await.map_err(failed)?;
+
+    cache.invalidate_author(id);
+    post_cache.invalidate_author(id);
+    for post in &posts {
+        post_events.publish(PostEventKind::Deleted, post);
     }
 
     Ok(())
 }
 
-pub async fn author_exists(state: &SharedState, id: Uuid) -> Result<bool, AppError> {
+// A versioned write matched no row: either the author is gone or someone else wrote first.
+async fn missing_or_stale(pool: &DbPool, id: Uuid) -> AppError {
+    match author_exists(pool, id).await {
+        Ok(true) => {
+            AppError::PreconditionFailed(format!("Author {id} was modified by another request"))
+        }
+        Ok(false) => {
+            AppError::NotFound(ErrorCode::AuthorNotFound, format!("Author not found: {id}"))
+        }
+        Err(err) => err,
+    }
+}
+
+pub async fn author_exists(pool: &DbPool, id: Uuid) -> Result<bool, AppError> {
     let exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM authors WHERE id = $1)")
         .bind(id)
-        .fetch_one(&state.db_pool)
+        .fetch_one(pool)
         .await
         .map_err(|err| AppError::InternalServerError(format!("Failed to check author: {err}")))?;
 
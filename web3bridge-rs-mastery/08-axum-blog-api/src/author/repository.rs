@@ -0,0 +1,311 @@
+use crate::author::cache::AuthorCache;
+use crate::author::models::Author;
+use crate::author::queries;
+use crate::db::DbPool;
+use crate::error::AppError;
+use crate::pagination::{Cursor, Page};
+use crate::post::{PostCache, PostEvents};
+use async_trait::async_trait;
+use std::fmt::Debug;
+use uuid::Uuid;
+
+/// Storage for author accounts, so handlers do not depend on a particular database.
+#[async_trait]
+pub trait AuthorRepository: Debug + Send + Sync {
+    /// Fails with a `422` on `email` when the address is already taken.
+    async fn insert(
+        &self,
+        name: &str,
+        email: &str,
+        password_hash: &str,
+    ) -> Result<Author, AppError>;
+
+    /// Newest first; `offset` is ignored when a cursor is given.
+    async fn list(
+        &self,
+        cursor: Option<Cursor>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Page<Author>, AppError>;
+
+    async fn count(&self) -> Result<i64, AppError>;
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Author, AppError>;
+
+    /// Fields left as `None` keep their stored value. Fails with a `412` unless the stored
+    /// version is still `expected_version`.
+    async fn update(
+        &self,
+        id: Uuid,
+        expected_version: i64,
+        name: Option<&str>,
+        email: Option<&str>,
+        password_hash: Option<&str>,
+    ) -> Result<Author, AppError>;
+
+    /// Fails with a `412` unless the stored version is still `expected_version`.
+    async fn delete(&self, id: Uuid, expected_version: i64) -> Result<(), AppError>;
+
+    async fn exists(&self, id: Uuid) -> Result<bool, AppError>;
+}
+
+/// Database-backed authors. Reads are cached; deleting an author also clears
+/// their posts from `post_cache` and publishes their deletion to `post_events`.
+#[derive(Debug, Clone)]
+pub struct SqlAuthorRepository {
+    pool: DbPool,
+    cache: AuthorCache,
+    post_cache: PostCache,
+    post_events: PostEvents,
+}
+
+impl SqlAuthorRepository {
+    pub fn new(
+        pool: DbPool,
+        cache: AuthorCache,
+        post_cache: PostCache,
+        post_events: PostEvents,
+    ) -> Self {
+        Self {
+            pool,
+            cache,
+            post_cache,
+            post_events,
+        }
+    }
+}
+
+#[async_trait]
+impl AuthorRepository for SqlAuthorRepository {
+    async fn insert(
+        &self,
+        name: &str,
+        email: &str,
+        password_hash: &str,
+    ) -> Result<Author, AppError> {
+        queries::insert_author(&self.pool, &self.cache, name, email, password_hash).await
+    }
+
+    async fn list(
+        &self,
+        cursor: Option<Cursor>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Page<Author>, AppError> {
+        queries::list_authors(&self.pool, &self.cache, cursor, limit, offset).await
+    }
+
+    async fn count(&self) -> Result<i64, AppError> {
+        queries::count_authors(&self.pool, &self.cache).await
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Author, AppError> {
+        queries::find_author_by_id(&self.pool, &self.cache, id).await
+    }
+
+    async fn update(
+        &self,
+        id: Uuid,
+        expected_version: i64,
+        name: Option<&str>,
+        email: Option<&str>,
+        password_hash: Option<&str>,
+    ) -> Result<Author, AppError> {
+        queries::update_author(
+            &self.pool,
+            &self.cache,
+            id,
+            expected_version,
+            name,
+            email,
+            password_hash,
+        )
+        .await
+    }
+
+    async fn delete(&self, id: Uuid, expected_version: i64) -> Result<(), AppError> {
+        queries::delete_author(
+            &self.pool,
+            &self.cache,
+            &self.post_cache,
+            &self.post_events,
+            id,
+            expected_version,
+        )
+        .await
+    }
+
+    async fn exists(&self, id: Uuid) -> Result<bool, AppError> {
+        queries::author_exists(&self.pool, id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::state::Caches;
+    use crate::config::CacheConfig;
+    use crate::post::models::{PostCriteria, PostStatus};
+    use crate::post::{
+        Delivery, NewPost, PostChanges, PostEventKind, PostRepository, SqlPostRepository,
+    };
+    use crate::telemetry::Metrics;
+    use chrono::Utc;
+    use futures_util::StreamExt;
+
+    #[sqlx::test(migrator = "crate::db::MIGRATOR")]
+    #[cfg_attr(feature = "postgres", ignore = "needs a Postgres DATABASE_URL")]
+    async fn author_listings_are_cached_until_any_author_is_written(pool: DbPool) {
+        let metrics = Metrics::new();
+        let caches = Caches::new(&CacheConfig::default(), &metrics);
+        let authors = SqlAuthorRepository::new(
+            pool,
+            caches.authors.clone(),
+            caches.posts.clone(),
+            PostEvents::default(),
+        );
+        let names = |page: Page<Author>| -> Vec<String> {
+            let mut names: Vec<_> = page.items.into_iter().map(|author| author.name).collect();
+            names.sort();
+            names
+        };
+
+        authors
+            .insert("Ada", "ada@example.com", "hash")
+            .await
+            .unwrap();
+        assert_eq!(authors.count().await.unwrap(), 1);
+        assert_eq!(authors.count().await.unwrap(), 1);
+        authors.list(None, 10, 0).await.unwrap();
+        authors.list(None, 10, 0).await.unwrap();
+        assert_eq!(metrics.cache_counters("author_counts").hits.get(), 1);
+        assert_eq!(metrics.cache_counters("author_lists").hits.get(), 1);
+
+        let grace = authors
+            .insert("Grace", "grace@example.com", "hash")
+            .await
+            .unwrap();
+        assert_eq!(authors.count().await.unwrap(), 2);
+        let grace = authors
+            .update(grace.id, grace.version, Some("Grace Hopper"), None, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            names(authors.list(None, 10, 0).await.unwrap()),
+            ["Ada", "Grace Hopper"]
+        );
+
+        authors.delete(grace.id, grace.version).await.unwrap();
+        assert_eq!(authors.count().await.unwrap(), 1);
+        assert_eq!(names(authors.list(None, 10, 0).await.unwrap()), ["Ada"]);
+    }
+
+    #[sqlx::test(migrator = "crate::db::MIGRATOR")]
+    #[cfg_attr(feature = "postgres", ignore = "needs a Postgres DATABASE_URL")]
+    async fn pages_keep_rows_that_share_a_whole_second(pool: DbPool) {
+        // Stored the way `NOW` writes them on SQLite, with a `.000` fraction.
+        for n in 0..3 {
+            sqlx::query("INSERT INTO authors (id, name, email, created_at) VALUES ($1, $2, $3, '2026-01-01T00:00:00.000+00:00')")
+                .bind(Uuid::new_v4())
+                .bind(format!("Author {n}"))
+                .bind(format!("author{n}@example.com"))
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        let metrics = Metrics::new();
+        let caches = Caches::new(&CacheConfig::default(), &metrics);
+        let authors =
+            SqlAuthorRepository::new(pool, caches.authors, caches.posts, PostEvents::default());
+
+        let mut seen = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = authors.list(cursor, 1, 0).await.unwrap();
+            seen.extend(page.items.into_iter().map(|author| author.id));
+            cursor = page.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        assert_eq!(seen.len(), 3);
+        seen.dedup();
+        assert_eq!(seen.len(), 3);
+    }
+
+    #[sqlx::test(migrator = "crate::db::MIGRATOR")]
+    #[cfg_attr(feature = "postgres", ignore = "needs a Postgres DATABASE_URL")]
+    async fn cached_reads_follow_writes_and_cascading_deletes(pool: DbPool) {
+        let metrics = Metrics::new();
+        let caches = Caches::new(&CacheConfig::default(), &metrics);
+        let events = PostEvents::default();
+        let authors = SqlAuthorRepository::new(
+            pool.clone(),
+            caches.authors.clone(),
+            caches.posts.clone(),
+            events.clone(),
+        );
+        let posts = SqlPostRepository::new(pool, caches.posts.clone(), events.clone());
+        let everyone = PostCriteria::default();
+
+        let author = authors
+            .insert("Ada", "ada@example.com", "hash")
+            .await
+            .unwrap();
+        assert_eq!(posts.count(&everyone).await.unwrap(), 0);
+        let post = posts
+            .insert(NewPost {
+                title: "Hello",
+                content: "hi",
+                content_html: "<p>hi</p>",
+                author_id: author.id,
+                status: PostStatus::Published,
+                published_at: Some(Utc::now()),
+                is_premium: false,
+                tags: &[],
+            })
+            .await
+            .unwrap();
+        assert_eq!(posts.count(&everyone).await.unwrap(), 1);
+
+        posts.find_by_id(post.id).await.unwrap();
+        posts.find_by_id(post.id).await.unwrap();
+        assert_eq!(metrics.cache_counters("posts").hits.get(), 1);
+
+        let changes = PostChanges {
+            title: Some("Hello again"),
+            content: None,
+            content_html: None,
+            status: post.status,
+            published_at: post.published_at,
+            is_premium: None,
+            tags: None,
+            restored_from: None,
+        };
+        posts.update(post.id, post.version, changes).await.unwrap();
+        assert_eq!(
+            posts.find_by_id(post.id).await.unwrap().title,
+            "Hello again"
+        );
+
+        authors.find_by_id(author.id).await.unwrap();
+        authors.delete(author.id, author.version).await.unwrap();
+        assert!(matches!(
+            authors.find_by_id(author.id).await,
+            Err(AppError::NotFound(..))
+        ));
+        assert!(matches!(
+            posts.find_by_id(post.id).await,
+            Err(AppError::NotFound(..))
+        ));
+        assert_eq!(posts.count(&everyone).await.unwrap(), 0);
+
+        events.close();
+        let last = events.subscribe(Some(0)).collect::<Vec<_>>().await.pop();
+        let Some(Delivery::Event(deleted)) = last else {
+            panic!("the cascade published nothing");
+        };
+        assert_eq!(deleted.kind, PostEventKind::Deleted);
+        assert_eq!(deleted.data["id"], post.id.to_string());
+    }
+}
@@ -0,0 +1,680 @@
+use crate::app::state::SharedState;
+use crate::auth::{AdminAccess, hash_password};
+use crate::bulk::models::{
+    AuthorRecord, BulkRecord, ImportOutcome, ImportQuery, ImportReport, PostRecord,
+};
+use crate::bulk::ndjson::{self, LineReader};
+use crate::bulk::queries;
+use crate::db::DbConnection;
+use crate::error::{AppError, ErrorCode, ErrorResponse, FieldErrors};
+use crate::extract::Query;
+use crate::markdown;
+use crate::post::models::{Post, PostStatus};
+use crate::post::{NewPost, PostEventKind, PostEvents};
+use crate::tag::normalize_tags;
+use crate::validation;
+use axum::{Json, body::Body, extract::State, http::header::CONTENT_TYPE, response::IntoResponse};
+use chrono::{DateTime, Utc};
+use sqlx::Acquire;
+use std::collections::{HashMap, HashSet};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use uuid::Uuid;
+
+/// Lines buffered ahead of a slow client before the export query waits.
+const EXPORT_BUFFER: usize = 64;
+
+/// Streams every author and live post as NDJSON.
+///
+/// Authors come first, then posts in creation order, all from one snapshot. Passwords
+/// and soft-deleted posts are left out. The output can be fed straight to `/import`.
+#[utoipa::path(
+    get,
+    path = "/export",
+    tag = "bulk",
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "One `BulkRecord` per line", body = BulkRecord, content_type = "application/x-ndjson"),
+        (status = 401, description = "Wrong or missing admin token", body = ErrorResponse),
+        (status = 403, description = "Admin endpoints are disabled", body = ErrorResponse),
+    )
+)]
+pub async fn export(State(state): State<SharedState>, _admin: AdminAccess) -> impl IntoResponse {
+    let (lines, receiver) = mpsc::channel(EXPORT_BUFFER);
+    let pool = state.db_pool.clone();
+    tokio::spawn(async move {
+        if let Err(err) = queries::export(&pool, &lines).await {
+            tracing::error!(error = %err, "export failed");
+            // Aborts the response body, so the client sees a broken download rather than a short one.
+            let _ = lines
+                .send(Err(std::io::Error::other(err.to_string())))
+                .await;
+        }
+    });
+
+    (
+        [(CONTENT_TYPE, ndjson::CONTENT_TYPE)],
+        Body::from_stream(ReceiverStream::new(receiver)),
+    )
+}
+
+/// Imports NDJSON in the `/export` format, a batch of lines at a time.
+///
+/// Authors are upserted by email; posts are inserted, skipping ids that already
+/// exist. Each batch is read and checked before a transaction is opened for it, and
+/// committed on its own. A line that fails is undone on its own and reported, the
+/// rest still apply. With `dry_run=true` everything is checked and then rolled back.
+#[utoipa::path(
+    post,
+    path = "/import",
+    tag = "bulk",
+    params(ImportQuery),
+    request_body(content = BulkRecord, content_type = "application/x-ndjson", description = "One `BulkRecord` per line"),
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "What was imported and which lines failed", body = ImportReport),
+        (status = 401, description = "Wrong or missing admin token", body = ErrorResponse),
+        (status = 403, description = "Admin endpoints are disabled", body = ErrorResponse),
+        (status = 422, description = "Invalid query", body = ErrorResponse),
+    )
+)]
+pub async fn import(
+    State(state): State<SharedState>,
+    _admin: AdminAccess,
+    Query(query): Query<ImportQuery>,
+    body: Body,
+) -> Result<Json<ImportReport>, AppError> {
+    let mut report = ImportReport {
+        dry_run: query.dry_run.unwrap_or(false),
+        ..ImportReport::default()
+    };
+    let mut dry_run = report.dry_run.then(DryRun::default);
+    let mut announcements = Announcements::default();
+    let mut lines = LineReader::new(body);
+
+    loop {
+        let batch = read_batch(&mut lines, &mut report).await;
+        let applied = apply_batch(
+            &state,
+            batch.lines,
+            &mut report,
+            dry_run.as_mut(),
+            &mut announcements,
+        )
+        .await;
+        if let Err(err) = applied {
+            announcements.finish(&state.post_events);
+            return Err(err);
+        }
+        match batch.end {
+            BatchEnd::Full => {}
+            BatchEnd::Finished => break,
+            // Earlier batches are already committed; the report says where reading stopped.
+            BatchEnd::Unreadable(err) => {
+                report.reject(report.lines + 1, err);
+                break;
+            }
+        }
+    }
+    announcements.finish(&state.post_events);
+
+    tracing::info!(
+        dry_run = report.dry_run,
+        lines = report.lines,
+        failed = report.failed,
+        "import finished"
+    );
+    Ok(Json(report))
+}
+
+/// Lines read and checked before a transaction is opened for them.
+const BATCH_LINES: usize = 500;
+
+/// Bytes of lines in a batch; the line that crosses it is the last one.
+const BATCH_BYTES: usize = 4 * 1024 * 1024;
+
+struct Batch {
+    /// Non-blank lines by line number, checked as far as possible without the database.
+    lines: Vec<(usize, Result<CheckedLine, AppError>)>,
+    end: BatchEnd,
+}
+
+enum BatchEnd {
+    Full,
+    Finished,
+    Unreadable(AppError),
+}
+
+/// A line that parsed and validated, with its slow parts (password hashing, Markdown)
+/// already done so they do not run inside the transaction.
+enum CheckedLine {
+    Author {
+        author: AuthorRecord,
+        password_hash: Option<String>,
+    },
+    Post(CheckedPost),
+}
+
+struct CheckedPost {
+    post: PostRecord,
+    status: PostStatus,
+    published_at: Option<DateTime<Utc>>,
+    tags: Vec<String>,
+    content_html: String,
+}
+
+/// New posts sent to `/posts/stream` one by one. An import that creates more sends a single
+/// `reset` once it ends instead, rather than pushing everything else out of the replay
+/// buffer.
+const ANNOUNCED_POSTS: usize = 32;
+
+/// Posts created by the import, kept only until their batch commits and only while they
+/// will be announced.
+#[derive(Default)]
+struct Announcements {
+    pending: Vec<Post>,
+    // Posts created in the current batch, and in committed ones.
+    uncommitted: usize,
+    committed: usize,
+}
+
+/// What earlier batches of a dry run wrote before they were rolled back, replayed so later
+/// batches see the same database a real import would. Holds one entry per author the run
+/// created or renamed and per post id it created.
+#[derive(Default)]
+struct DryRun {
+    authors: HashMap<String, String>,
+    posts: HashSet<Uuid>,
+}
+
+async fn read_batch(lines: &mut LineReader, report: &mut ImportReport) -> Batch {
+    let mut batch = Vec::new();
+    let mut bytes = 0;
+
+    while batch.len() < BATCH_LINES && bytes < BATCH_BYTES {
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => {
+                return Batch {
+                    lines: batch,
+                    end: BatchEnd::Finished,
+                };
+            }
+            Err(err) => {
+                return Batch {
+                    lines: batch,
+                    end: BatchEnd::Unreadable(err),
+                };
+            }
+        };
+        report.lines += 1;
+        if line.iter().all(u8::is_ascii_whitespace) {
+            continue;
+        }
+        bytes += line.len();
+        batch.push((report.lines, check_line(&line).await));
+    }
+
+    Batch {
+        lines: batch,
+        end: BatchEnd::Full,
+    }
+}
+
+async fn check_line(line: &[u8]) -> Result<CheckedLine, AppError> {
+    let record = serde_json::from_slice::<BulkRecord>(line).map_err(|err| {
+        AppError::invalid(
+            "line",
+            ErrorCode::BulkInvalidRecord,
+            format!("Not a valid record: {err}"),
+        )
+    })?;
+
+    match record {
+        BulkRecord::Author(mut author) => {
+            validation::check(&author)?;
+            let password_hash = match author.password.take() {
+                Some(password) => Some(hash_password(password).await?),
+                None => None,
+            };
+            Ok(CheckedLine::Author {
+                author,
+                password_hash,
+            })
+        }
+        BulkRecord::Post(post) => {
+            let mut errors = FieldErrors::default();
+            errors.capture(validation::check(&post))?;
+            let status = post.status.unwrap_or(PostStatus::Published);
+            let published_at =
+                errors.capture(status.published_at(post.published_at, None, Utc::now()))?;
+            let tags = errors.capture(normalize_tags(&post.tags))?;
+            errors.finish()?;
+
+            Ok(CheckedLine::Post(CheckedPost {
+                content_html: markdown::render(&post.content),
+                post,
+                status,
+                published_at,
+                tags,
+            }))
+        }
+    }
+}
+
+/// Writes a batch in one transaction, committed unless this is a dry run.
+async fn apply_batch(
+    state: &SharedState,
+    lines: Vec<(usize, Result<CheckedLine, AppError>)>,
+    report: &mut ImportReport,
+    mut dry_run: Option<&mut DryRun>,
+    announcements: &mut Announcements,
+) -> Result<(), AppError> {
+    let failed =
+        |err: sqlx::Error| AppError::InternalServerError(format!("Failed to import: {err}"));
+    if lines.iter().all(|(_, line)| line.is_err()) {
+        for (number, line) in lines {
+            if let Err(err) = line {
+                report.reject(number, err);
+            }
+        }
+        return Ok(());
+    }
+
+    let mut tx = state.db_pool.begin().await.map_err(failed)?;
+    for (number, line) in lines {
+        let line = match line {
+            Ok(line) => line,
+            Err(err) => {
+                report.reject(number, err);
+                continue;
+            }
+        };
+
+        // A savepoint per line undoes a failed line without aborting the transaction.
+        let mut savepoint = tx.begin().await.map_err(failed)?;
+        match import_line(&mut savepoint, line, announcements, dry_run.as_deref_mut()).await {
+            Ok(outcome) => {
+                savepoint.commit().await.map_err(failed)?;
+                report.record(outcome);
+            }
+            Err(err) => {
+                savepoint.rollback().await.map_err(failed)?;
+                report.reject(number, err);
+            }
+        }
+    }
+
+    if dry_run.is_some() {
+        tx.rollback().await.map_err(failed)?;
+        announcements.rolled_back();
+    } else {
+        tx.commit().await.map_err(failed)?;
+        // Imported rows bypass the repositories, which would otherwise invalidate precisely
+        // and publish each new post themselves.
+        state.caches.clear();
+        announcements.committed(&state.post_events);
+    }
+    Ok(())
+}
+
+/// Posts created by the line are added to `announcements`.
+async fn import_line(
+    conn: &mut DbConnection,
+    line: CheckedLine,
+    announcements: &mut Announcements,
+    dry_run: Option<&mut DryRun>,
+) -> Result<ImportOutcome, AppError> {
+    match line {
+        CheckedLine::Author {
+            author,
+            password_hash,
+        } => import_author(conn, author, password_hash, dry_run).await,
+        CheckedLine::Post(post) => import_post(conn, post, announcements, dry_run).await,
+    }
+}
+
+async fn import_author(
+    conn: &mut DbConnection,
+    author: AuthorRecord,
+    password_hash: Option<String>,
+    dry_run: Option<&mut DryRun>,
+) -> Result<ImportOutcome, AppError> {
+    if let Some(dry_run) = &dry_run {
+        dry_run.replay_author(conn, &author.email).await?;
+    }
+
+    let outcome = queries::upsert_author(
+        conn,
+        &author.name,
+        &author.email,
+        password_hash.as_deref(),
+        author.created_at,
+    )
+    .await?;
+
+    if let Some(dry_run) = dry_run
+        && outcome != ImportOutcome::AuthorUnchanged
+    {
+        dry_run.authors.insert(author.email, author.name);
+    }
+    Ok(outcome)
+}
+
+async fn import_post(
+    conn: &mut DbConnection,
+    checked: CheckedPost,
+    announcements: &mut Announcements,
+    dry_run: Option<&mut DryRun>,
+) -> Result<ImportOutcome, AppError> {
+    let post = &checked.post;
+    let email = &post.author_email;
+    if let Some(dry_run) = &dry_run {
+        if post.id.is_some_and(|id| dry_run.posts.contains(&id)) {
+            return Ok(ImportOutcome::PostSkipped);
+        }
+        dry_run.replay_author(conn, email).await?;
+    }
+
+    let author_id = queries::find_author_id(conn, email).await?.ok_or_else(|| {
+        AppError::invalid(
+            "author_email",
+            ErrorCode::BulkUnknownAuthor,
+            format!("No author with email {email}; authors must come before their posts"),
+        )
+    })?;
+
+    let created = queries::insert_post(
+        conn,
+        post.id.unwrap_or_else(Uuid::new_v4),
+        NewPost {
+            title: &post.title,
+            content: &post.content,
+            content_html: &checked.content_html,
+            author_id,
+            status: checked.status,
+            published_at: checked.published_at,
+            is_premium: post.is_premium,
+            tags: &checked.tags,
+        },
+        post.created_at,
+    )
+    .await?;
+
+    Ok(match created {
+        Some(created) => {
+            if let (Some(dry_run), Some(id)) = (dry_run, post.id) {
+                dry_run.posts.insert(id);
+            }
+            announcements.push(created);
+            ImportOutcome::PostCreated
+        }
+        None => ImportOutcome::PostSkipped,
+    })
+}
+
+impl Announcements {
+    fn push(&mut self, post: Post) {
+        self.uncommitted += 1;
+        if self.committed + self.uncommitted <= ANNOUNCED_POSTS {
+            self.pending.push(post);
+        }
+    }
+
+    /// Announces the batch's posts, unless the import has gone past `ANNOUNCED_POSTS`.
+    fn committed(&mut self, events: &PostEvents) {
+        self.committed += std::mem::take(&mut self.uncommitted);
+        let pending = std::mem::take(&mut self.pending);
+        if self.committed <= ANNOUNCED_POSTS {
+            for post in &pending {
+                events.publish(PostEventKind::Created, post);
+            }
+        }
+    }
+
+    fn rolled_back(&mut self) {
+        self.uncommitted = 0;
+        self.pending.clear();
+    }
+
+    /// Sends the `reset` standing in for the posts that were not announced.
+    fn finish(&self, events: &PostEvents) {
+        if self.committed > ANNOUNCED_POSTS {
+            events.publish_reset();
+        }
+    }
+}
+
+impl DryRun {
+    /// Writes the author again if an earlier, rolled back batch created or renamed them.
+    async fn replay_author(&self, conn: &mut DbConnection, email: &str) -> Result<(), AppError> {
+        if let Some(name) = self.authors.get(email) {
+            queries::upsert_author(conn, name, email, None, None).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ANNOUNCED_POSTS, BATCH_LINES};
+    use crate::app::router::create_router;
+    use crate::app::state::{AppState, SharedState};
+    use crate::auth::ADMIN_TOKEN_HEADER;
+    use crate::db::DbPool;
+    use crate::post::{Delivery, PostEventKind};
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        response::Response,
+    };
+    use futures_util::StreamExt;
+    use secrecy::SecretString;
+    use serde_json::Value;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+    use tokio_stream::wrappers::ReceiverStream;
+    use tower::ServiceExt;
+
+    const TOKEN: &str = "operator-token-0123456789abcdefgh";
+
+    fn with_admin_token(state: SharedState) -> SharedState {
+        let mut state = Arc::into_inner(state).expect("state is not shared yet");
+        state.admin_token = Some(SecretString::from(TOKEN));
+        SharedState::new(state)
+    }
+
+    async fn call(
+        state: &SharedState,
+        method: &str,
+        uri: &str,
+        token: &str,
+        body: &str,
+    ) -> Response {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(ADMIN_TOKEN_HEADER, token)
+            .header("content-type", "application/x-ndjson")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        create_router(state.clone()).oneshot(request).await.unwrap()
+    }
+
+    async fn text(response: Response) -> String {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn bulk_endpoints_need_the_configured_admin_token() {
+        let disabled = AppState::for_tests();
+        let response = call(&disabled, "GET", "/export", TOKEN, "").await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let state = with_admin_token(AppState::for_tests());
+        let response = call(&state, "POST", "/import", "not-the-token", "").await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = call(&state, "GET", "/export", "", "").await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[sqlx::test(migrator = "crate::db::MIGRATOR")]
+    #[cfg_attr(feature = "postgres", ignore = "needs a Postgres DATABASE_URL")]
+    async fn import_reports_bad_lines_and_round_trips_through_export(pool: DbPool) {
+        let state = with_admin_token(AppState::for_tests_with_pool(pool));
+        let ndjson = [
+            r#"{"type":"author","name":"Ada","email":"Ada@Example.com"}"#,
+            "",
+            r#"{"type":"post","author_email":"ada@example.com","title":"Hello","content":"*hi*","tags":["Rust"]}"#,
+            r#"{"type":"post","author_email":"nobody@example.com","title":"Orphan","content":"x"}"#,
+            "not json",
+            r#"{"type":"author","name":"Ada Lovelace","email":"ada@example.com"}"#,
+        ]
+        .join("\n");
+
+        let dry_run = call(&state, "POST", "/import?dry_run=true", TOKEN, &ndjson).await;
+        assert_eq!(dry_run.status(), StatusCode::OK);
+        let report: Value = serde_json::from_str(&text(dry_run).await).unwrap();
+        assert_eq!(report["dry_run"], true);
+        assert_eq!(report["posts_created"], 1);
+        let authors: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM authors")
+            .fetch_one(&state.db_pool)
+            .await
+            .unwrap();
+        assert_eq!(authors, 0, "a dry run writes nothing");
+
+        let response = call(&state, "POST", "/import", TOKEN, &ndjson).await;
+        let report: Value = serde_json::from_str(&text(response).await).unwrap();
+        assert_eq!(report["lines"], 6);
+        assert_eq!(report["authors_created"], 1);
+        assert_eq!(report["authors_updated"], 1);
+        assert_eq!(report["posts_created"], 1);
+        assert_eq!(report["failed"], 2);
+        assert_eq!(report["errors"][0]["line"], 4);
+        assert!(
+            report["errors"][0]["message"]
+                .as_str()
+                .unwrap()
+                .starts_with("author_email:")
+        );
+        assert_eq!(report["errors"][1]["line"], 5);
+
+        let export = call(&state, "GET", "/export", TOKEN, "").await;
+        assert_eq!(export.headers()["content-type"], "application/x-ndjson");
+        let exported = text(export).await;
+        let lines: Vec<Value> = exported
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["name"], "Ada Lovelace");
+        assert!(lines[0].get("password").is_none());
+        assert_eq!(lines[1]["tags"][0], "rust");
+
+        // Importing an export again changes nothing.
+        let response = call(&state, "POST", "/import", TOKEN, &exported).await;
+        let report: Value = serde_json::from_str(&text(response).await).unwrap();
+        assert_eq!(report["authors_unchanged"], 1);
+        assert_eq!(report["posts_skipped"], 1);
+        assert_eq!(report["failed"], 0);
+
+        // Only the one post that was really created reaches `/posts/stream`.
+        state.post_events.close();
+        let published: Vec<_> = state.post_events.subscribe(Some(0)).collect().await;
+        let [Delivery::Event(created)] = &published[..] else {
+            panic!("expected one event, got {published:?}");
+        };
+        assert_eq!(created.kind, PostEventKind::Created);
+        assert_eq!(created.data["title"], "Hello");
+    }
+
+    #[sqlx::test(migrator = "crate::db::MIGRATOR")]
+    #[cfg_attr(feature = "postgres", ignore = "needs a Postgres DATABASE_URL")]
+    async fn a_dry_run_spanning_batches_reports_what_the_import_does(pool: DbPool) {
+        let state = with_admin_token(AppState::for_tests_with_pool(pool));
+        // The first batch is the author and filler; the rename and the posts land in the
+        // next one, after the first batch of the dry run was rolled back.
+        let mut lines = vec![r#"{"type":"author","name":"Ada","email":"ada@example.com"}"#];
+        lines.extend(std::iter::repeat_n("not json", BATCH_LINES - 1));
+        let post = r#"{"type":"post","id":"7f1b0e6c-2d5e-4c8e-9d61-2b4f3c1a9e10","author_email":"ada@example.com","title":"Hello","content":"hi"}"#;
+        lines.extend([
+            r#"{"type":"author","name":"Ada Lovelace","email":"ada@example.com"}"#,
+            post,
+            post,
+        ]);
+        let ndjson = lines.join("\n");
+
+        let mut reports = Vec::new();
+        for uri in ["/import?dry_run=true", "/import"] {
+            let response = call(&state, "POST", uri, TOKEN, &ndjson).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            let mut report: Value = serde_json::from_str(&text(response).await).unwrap();
+            report["dry_run"] = Value::Null;
+            reports.push(report);
+        }
+
+        assert_eq!(reports[0], reports[1]);
+        assert_eq!(reports[1]["authors_created"], 1);
+        assert_eq!(reports[1]["authors_updated"], 1);
+        assert_eq!(reports[1]["posts_created"], 1);
+        assert_eq!(reports[1]["posts_skipped"], 1);
+        assert_eq!(reports[1]["failed"], BATCH_LINES - 1);
+    }
+
+    #[sqlx::test(migrator = "crate::db::MIGRATOR")]
+    #[cfg_attr(feature = "postgres", ignore = "needs a Postgres DATABASE_URL")]
+    async fn a_slow_body_holds_no_connection_while_it_is_read(pool: DbPool) {
+        let state = with_admin_token(AppState::for_tests_with_pool(pool));
+        let (chunks, receiver) = mpsc::channel::<Result<String, std::io::Error>>(1);
+        let request = Request::builder()
+            .method("POST")
+            .uri("/import")
+            .header(ADMIN_TOKEN_HEADER, TOKEN)
+            .header("content-type", "application/x-ndjson")
+            .body(Body::from_stream(ReceiverStream::new(receiver)))
+            .unwrap();
+        let import = tokio::spawn(create_router(state.clone()).oneshot(request));
+
+        let author = r#"{"type":"author","name":"Ada","email":"ada@example.com"}"#;
+        chunks.send(Ok(format!("{author}\n"))).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(state.db_pool.num_idle() as u32, state.db_pool.size());
+
+        let post =
+            r#"{"type":"post","author_email":"ada@example.com","title":"Hello","content":"hi"}"#;
+        chunks.send(Ok(post.to_string())).await.unwrap();
+        drop(chunks);
+        let response = import.await.unwrap().unwrap();
+        let report: Value = serde_json::from_str(&text(response).await).unwrap();
+        assert_eq!(report["authors_created"], 1);
+        assert_eq!(report["posts_created"], 1);
+        assert_eq!(report["failed"], 0);
+    }
+
+    #[sqlx::test(migrator = "crate::db::MIGRATOR")]
+    #[cfg_attr(feature = "postgres", ignore = "needs a Postgres DATABASE_URL")]
+    async fn a_large_import_sends_one_reset_instead_of_every_post(pool: DbPool) {
+        let state = with_admin_token(AppState::for_tests_with_pool(pool));
+        let mut lines =
+            vec![r#"{"type":"author","name":"Ada","email":"ada@example.com"}"#.to_string()];
+        lines.extend((0..=ANNOUNCED_POSTS).map(|n| {
+            format!(r#"{{"type":"post","author_email":"ada@example.com","title":"Post {n}","content":"hi"}}"#)
+        }));
+        let subscription = state.post_events.subscribe(None);
+
+        let response = call(&state, "POST", "/import", TOKEN, &lines.join("\n")).await;
+        let report: Value = serde_json::from_str(&text(response).await).unwrap();
+        assert_eq!(report["posts_created"], ANNOUNCED_POSTS + 1);
+
+        state.post_events.close();
+        let published: Vec<_> = subscription.collect().await;
+        assert!(
+            matches!(&published[..], [Delivery::Missed]),
+            "expected one reset, got {published:?}"
+        );
+    }
+}
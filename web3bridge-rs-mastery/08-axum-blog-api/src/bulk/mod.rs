@@ -0,0 +1,27 @@
+mod handlers;
+pub mod models;
+mod ndjson;
+mod queries;
+
+use crate::app::state::SharedState;
+use axum::{
+    Router,
+    routing::{get, post},
+};
+use models::BulkRecord;
+use utoipa::OpenApi;
+
+/// OpenAPI paths for `/export` and `/import`.
+#[derive(OpenApi)]
+#[openapi(
+    paths(handlers::export, handlers::import),
+    components(schemas(BulkRecord))
+)]
+pub struct BulkApi;
+
+/// NDJSON export and import, for operators holding the admin token.
+pub fn bulk_routes() -> Router<SharedState> {
+    Router::new()
+        .route("/export", get(handlers::export))
+        .route("/import", post(handlers::import))
+}
@@ -0,0 +1,145 @@
+use crate::auth::MIN_PASSWORD_LENGTH;
+use crate::error::{AppError, ErrorCode};
+use crate::post::models::PostStatus;
+use crate::validation::{self, MAX_VARCHAR_LENGTH};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+/// One NDJSON line of an export or import, tagged by `type`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum BulkRecord {
+    Author(AuthorRecord),
+    Post(PostRecord),
+}
+
+/// Authors are matched on `email`, so importing one that exists updates its name.
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct AuthorRecord {
+    #[serde(deserialize_with = "validation::trim")]
+    #[validate(length(min = 1, max = MAX_VARCHAR_LENGTH))]
+    pub name: String,
+    #[serde(deserialize_with = "validation::email_address")]
+    #[validate(custom(function = "validation::email"))]
+    pub email: String,
+    /// Import only: sets the password of an author created by the import. Never exported.
+    #[serde(default, skip_serializing)]
+    #[sqlx(skip)]
+    #[validate(length(min = MIN_PASSWORD_LENGTH))]
+    pub password: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Posts refer to their author by email, which must appear on an earlier line or
+/// already exist.
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct PostRecord {
+    /// Kept on import, so a post that already exists is skipped rather than copied.
+    pub id: Option<Uuid>,
+    #[serde(deserialize_with = "validation::email_address")]
+    pub author_email: String,
+    #[serde(deserialize_with = "validation::trim")]
+    #[validate(length(min = 1, max = MAX_VARCHAR_LENGTH))]
+    pub title: String,
+    /// Markdown source, stored as written.
+    #[validate(custom(function = "validation::not_blank"))]
+    pub content: String,
+    /// Defaults to `published`.
+    pub status: Option<PostStatus>,
+    pub published_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub is_premium: bool,
+    #[serde(default)]
+    #[cfg_attr(feature = "sqlite", sqlx(json))]
+    pub tags: Vec<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ImportQuery {
+    /// Validate and apply every line, then roll everything back.
+    pub dry_run: Option<bool>,
+}
+
+/// Rejected lines listed in a report; a bad file should not produce a huge response.
+pub const MAX_REPORTED_ERRORS: usize = 100;
+
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    /// Lines read, including blank ones.
+    pub lines: usize,
+    pub authors_created: usize,
+    pub authors_updated: usize,
+    /// Authors that already existed with the same name.
+    pub authors_unchanged: usize,
+    pub posts_created: usize,
+    /// Posts whose `id` already exists.
+    pub posts_skipped: usize,
+    /// Lines rejected; nothing from them was written.
+    pub failed: usize,
+    /// The first rejected lines, in order; `failed` has the full count.
+    pub errors: Vec<ImportLineError>,
+}
+
+impl ImportReport {
+    pub fn record(&mut self, outcome: ImportOutcome) {
+        let count = match outcome {
+            ImportOutcome::AuthorCreated => &mut self.authors_created,
+            ImportOutcome::AuthorUpdated => &mut self.authors_updated,
+            ImportOutcome::AuthorUnchanged => &mut self.authors_unchanged,
+            ImportOutcome::PostCreated => &mut self.posts_created,
+            ImportOutcome::PostSkipped => &mut self.posts_skipped,
+        };
+        *count += 1;
+    }
+
+    pub fn reject(&mut self, line: usize, err: AppError) {
+        self.failed += 1;
+        let code = err.code();
+        let message = match err {
+            AppError::UnProcessableEntity(errors) => errors
+                .iter()
+                .map(|error| format!("{}: {}", error.field, error.message))
+                .collect::<Vec<_>>()
+                .join("; "),
+            AppError::InternalServerError(message) => {
+                tracing::error!(line, error = %message, "import line failed");
+                "An unexpected error occurred".to_string()
+            }
+            other => other.to_string(),
+        };
+        if self.errors.len() < MAX_REPORTED_ERRORS {
+            self.errors.push(ImportLineError {
+                line,
+                code,
+                message,
+            });
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImportLineError {
+    /// 1-based line number in the request body.
+    pub line: usize,
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+/// What a successfully imported line did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportOutcome {
+    AuthorCreated,
+    AuthorUpdated,
+    AuthorUnchanged,
+    PostCreated,
+    PostSkipped,
+}
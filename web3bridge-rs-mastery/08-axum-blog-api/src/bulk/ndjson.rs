@@ -0,0 +1,114 @@
+use crate::error::{AppError, ErrorCode};
+use axum::body::{Body, BodyDataStream};
+use futures_util::StreamExt;
+
+pub const CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// Longest line accepted; generous enough for a post with a very long body.
+pub const MAX_LINE_BYTES: usize = 1024 * 1024;
+
+/// Splits a request body into lines as it arrives, so an import only ever buffers the
+/// line it is reading.
+pub struct LineReader {
+    stream: BodyDataStream,
+    buffer: Vec<u8>,
+    // Bytes of `buffer` already searched for a newline.
+    scanned: usize,
+    finished: bool,
+}
+
+impl LineReader {
+    pub fn new(body: Body) -> Self {
+        Self {
+            stream: body.into_data_stream(),
+            buffer: Vec::new(),
+            scanned: 0,
+            finished: false,
+        }
+    }
+
+    /// The next line without its `\n` or `\r\n`, or `None` once the body is exhausted.
+    ///
+    /// Bytes are returned as-is; UTF-8 is checked when the line is parsed, so a bad
+    /// line is reported on its own instead of failing the whole body.
+    pub async fn next_line(&mut self) -> Result<Option<Vec<u8>>, AppError> {
+        loop {
+            if let Some(offset) = self.buffer[self.scanned..].iter().position(|b| *b == b'\n') {
+                let mut line: Vec<u8> = self.buffer.drain(..=self.scanned + offset).collect();
+                self.scanned = 0;
+                line.pop();
+                if line.last() == Some(&b'\r') {
+                    line.pop();
+                }
+                return Ok(Some(line));
+            }
+            self.scanned = self.buffer.len();
+
+            if self.buffer.len() > MAX_LINE_BYTES {
+                return Err(AppError::invalid(
+                    "body",
+                    ErrorCode::BulkLineTooLong,
+                    format!("Lines cannot be longer than {MAX_LINE_BYTES} bytes"),
+                ));
+            }
+            if self.finished {
+                self.scanned = 0;
+                return Ok((!self.buffer.is_empty()).then(|| std::mem::take(&mut self.buffer)));
+            }
+
+            match self.stream.next().await {
+                Some(Ok(chunk)) => self.buffer.extend_from_slice(&chunk),
+                Some(Err(err)) => {
+                    return Err(AppError::invalid(
+                        "body",
+                        ErrorCode::BulkUnreadableBody,
+                        format!("Failed to read request body: {err}"),
+                    ));
+                }
+                None => self.finished = true,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunked(chunks: &[&str]) -> Body {
+        let chunks: Vec<_> = chunks
+            .iter()
+            .map(|chunk| Ok::<_, std::io::Error>(chunk.to_string()))
+            .collect();
+        Body::from_stream(futures_util::stream::iter(chunks))
+    }
+
+    async fn lines(body: Body) -> Result<Vec<String>, AppError> {
+        let mut reader = LineReader::new(body);
+        let mut lines = Vec::new();
+        while let Some(line) = reader.next_line().await? {
+            lines.push(String::from_utf8(line).unwrap());
+        }
+        Ok(lines)
+    }
+
+    #[tokio::test]
+    async fn lines_may_span_chunks_and_end_without_a_newline() {
+        let body = chunked(&["{\"a\"", ":1}\r\n\n{\"b\":", "2}\n{\"c\":3}"]);
+
+        assert_eq!(
+            lines(body).await.unwrap(),
+            [r#"{"a":1}"#, "", r#"{"b":2}"#, r#"{"c":3}"#]
+        );
+    }
+
+    #[tokio::test]
+    async fn overlong_lines_are_refused() {
+        let long = "x".repeat(MAX_LINE_BYTES + 1);
+
+        assert!(matches!(
+            lines(chunked(&[&long])).await,
+            Err(err) if err.code() == ErrorCode::BulkLineTooLong
+        ));
+    }
+}
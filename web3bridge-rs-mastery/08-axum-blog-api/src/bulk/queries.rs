@@ -0,0 +1,150 @@
+use crate::bulk::models::{AuthorRecord, BulkRecord, ImportOutcome, PostRecord};
+use crate::db::{self, DbConnection, DbPool, NOW};
+use crate::error::AppError;
+use crate::outbox;
+use crate::post::{self, NewPost, models::Post};
+use crate::revision;
+use crate::tag;
+use axum::body::Bytes;
+use chrono::{DateTime, Utc};
+use futures_util::TryStreamExt;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+pub type ExportSender = mpsc::Sender<Result<Bytes, std::io::Error>>;
+
+/// Writes every author, then every live post, to `lines` as NDJSON.
+///
+/// Both reads share one repeatable-read snapshot, so every post line refers to an
+/// author line above it. Stops early, without error, once the client goes away.
+pub async fn export(pool: &DbPool, lines: &ExportSender) -> Result<(), AppError> {
+    let failed =
+        |err: sqlx::Error| AppError::InternalServerError(format!("Failed to export: {err}"));
+    let mut tx = pool.begin().await.map_err(failed)?;
+    // SQLite transactions already read from one snapshot.
+    #[cfg(feature = "postgres")]
+    sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+        .execute(&mut *tx)
+        .await
+        .map_err(failed)?;
+
+    let mut authors = sqlx::query_as::<_, AuthorRecord>(
+        "SELECT name, email, created_at FROM authors ORDER BY created_at, id",
+    )
+    .fetch(&mut *tx);
+    while let Some(author) = authors.try_next().await.map_err(failed)? {
+        if !send(lines, BulkRecord::Author(author)).await {
+            return Ok(());
+        }
+    }
+    drop(authors);
+
+    let mut posts = sqlx::query_as::<_, PostRecord>(EXPORT_POSTS).fetch(&mut *tx);
+    while let Some(post) = posts.try_next().await.map_err(failed)? {
+        if !send(lines, BulkRecord::Post(post)).await {
+            return Ok(());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "postgres")]
+const EXPORT_POSTS: &str = "SELECT p.id, a.email AS author_email, p.title, p.content, p.status, p.published_at, p.is_premium, ARRAY(SELECT t.name FROM post_tags pt JOIN tags t ON t.id = pt.tag_id WHERE pt.post_id = p.id ORDER BY t.name) AS tags, p.created_at FROM posts p JOIN authors a ON a.id = p.author_id WHERE p.deleted_at IS NULL ORDER BY p.created_at, p.id";
+
+// Tags come back as a JSON array; see `PostRecord::tags`.
+#[cfg(feature = "sqlite")]
+const EXPORT_POSTS: &str = "SELECT p.id, a.email AS author_email, p.title, p.content, p.status, p.published_at, p.is_premium, (SELECT json_group_array(name) FROM (SELECT t.name FROM post_tags pt JOIN tags t ON t.id = pt.tag_id WHERE pt.post_id = p.id ORDER BY t.name)) AS tags, p.created_at FROM posts p JOIN authors a ON a.id = p.author_id WHERE p.deleted_at IS NULL ORDER BY p.created_at, p.id";
+
+// False once the receiving response body has been dropped.
+async fn send(lines: &ExportSender, record: BulkRecord) -> bool {
+    let mut line = serde_json::to_vec(&record).expect("bulk records always serialize");
+    line.push(b'\n');
+    lines.send(Ok(Bytes::from(line))).await.is_ok()
+}
+
+/// Creates the author, or renames the one that already has this email.
+///
+/// An existing author's password is never changed by an import.
+pub async fn upsert_author(
+    conn: &mut DbConnection,
+    name: &str,
+    email: &str,
+    password_hash: Option<&str>,
+    created_at: Option<DateTime<Utc>>,
+) -> Result<ImportOutcome, AppError> {
+    // Inserted rows start at version 1 and updates bump it; the WHERE skips no-op updates.
+    let inserted = sqlx::query_scalar::<_, bool>(
+        &format!("INSERT INTO authors (id, name, email, password_hash, created_at, updated_at) VALUES ($1, $2, $3, $4, COALESCE($5, {NOW}), COALESCE($5, {NOW})) ON CONFLICT (email) DO UPDATE SET name = EXCLUDED.name, version = authors.version + 1, updated_at = {NOW} WHERE authors.name IS DISTINCT FROM EXCLUDED.name RETURNING (version = 1)"),
+    )
+    .bind(Uuid::new_v4())
+    .bind(name)
+    .bind(email)
+    .bind(password_hash)
+    .bind(created_at.map(db::timestamp))
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|err| AppError::InternalServerError(format!("Failed to import author: {err}")))?;
+
+    Ok(match inserted {
+        Some(true) => ImportOutcome::AuthorCreated,
+        Some(false) => ImportOutcome::AuthorUpdated,
+        None => ImportOutcome::AuthorUnchanged,
+    })
+}
+
+pub async fn find_author_id(
+    conn: &mut DbConnection,
+    email: &str,
+) -> Result<Option<Uuid>, AppError> {
+    sqlx::query_scalar::<_, Uuid>("SELECT id FROM authors WHERE email = $1")
+        .bind(email)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|err| AppError::InternalServerError(format!("Failed to look up author: {err}")))
+}
+
+/// Inserts the post under `id` along with its tags, first revision and outbox event.
+///
+/// Returns `None`, writing nothing, when a post with that id already exists.
+pub async fn insert_post(
+    conn: &mut DbConnection,
+    id: Uuid,
+    new: NewPost<'_>,
+    created_at: Option<DateTime<Utc>>,
+) -> Result<Option<Post>, AppError> {
+    let post = sqlx::query_as::<_, Post>(
+        &format!("INSERT INTO posts (id, title, content, content_html, author_id, status, published_at, is_premium, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, COALESCE($9, {NOW}), COALESCE($9, {NOW})) ON CONFLICT (id) DO NOTHING RETURNING id, title, content, content_html, author_id, status, published_at, is_premium, deleted_at, version, created_at, updated_at"),
+    )
+    .bind(id)
+    .bind(new.title)
+    .bind(new.content)
+    .bind(new.content_html)
+    .bind(new.author_id)
+    .bind(new.status)
+    .bind(new.published_at)
+    .bind(new.is_premium)
+    .bind(created_at.map(db::timestamp))
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|err| AppError::InternalServerError(format!("Failed to import post: {err}")))?;
+    let Some(mut post) = post else {
+        return Ok(None);
+    };
+
+    if !new.tags.is_empty() {
+        tag::set_post_tags(conn, post.id, new.tags).await?;
+    }
+    post.tags = new.tags.to_vec();
+
+    revision::record_revision(conn, &post, None).await?;
+    outbox::enqueue(
+        conn,
+        outbox::POST_CREATED,
+        post.id,
+        post::event_payload(&post),
+    )
+    .await?;
+
+    Ok(Some(post))
+}
@@ -0,0 +1,165 @@
+use crate::cache::{Cache, CacheCounters, Generation};
+use lru::LruCache;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Keeps at most `capacity` entries, evicting the least recently used, and drops entries
+/// older than `ttl` when they are next read. Per process: writes made by another instance
+/// are only seen once the entry expires.
+#[derive(Debug)]
+pub struct InMemoryCache<K: Hash + Eq, V> {
+    entries: Mutex<Entries<K, V>>,
+    ttl: Duration,
+    counters: CacheCounters,
+}
+
+#[derive(Debug)]
+struct Entries<K: Hash + Eq, V> {
+    lru: LruCache<K, Entry<V>>,
+    generation: u64,
+}
+
+#[derive(Debug)]
+struct Entry<V> {
+    value: V,
+    expires_at: Instant,
+}
+
+impl<K: Hash + Eq, V> InMemoryCache<K, V> {
+    pub fn new(capacity: NonZeroUsize, ttl: Duration, counters: CacheCounters) -> Self {
+        Self {
+            entries: Mutex::new(Entries {
+                lru: LruCache::new(capacity),
+                generation: 0,
+            }),
+            ttl,
+            counters,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Entries<K, V>> {
+        self.entries.lock().expect("cache poisoned")
+    }
+
+    fn get_at(&self, key: &K, now: Instant) -> Option<V>
+    where
+        V: Clone,
+    {
+        let mut entries = self.lock();
+        let value = match entries.lru.get(key) {
+            Some(entry) if entry.expires_at > now => Some(entry.value.clone()),
+            Some(_) => {
+                entries.lru.pop(key);
+                None
+            }
+            None => None,
+        };
+
+        match value {
+            Some(_) => self.counters.hits.inc(),
+            None => self.counters.misses.inc(),
+        };
+        value
+    }
+
+    fn insert_at(&self, key: K, value: V, generation: Generation, now: Instant) {
+        let mut entries = self.lock();
+        if entries.generation == generation.0 {
+            let expires_at = now + self.ttl;
+            entries.lru.put(key, Entry { value, expires_at });
+        }
+    }
+}
+
+impl<K, V> Cache<K, V> for InMemoryCache<K, V>
+where
+    K: Debug + Hash + Eq + Send,
+    V: Debug + Clone + Send,
+{
+    fn get(&self, key: &K) -> Option<V> {
+        self.get_at(key, Instant::now())
+    }
+
+    fn generation(&self) -> Generation {
+        Generation(self.lock().generation)
+    }
+
+    fn insert(&self, key: K, value: V, generation: Generation) {
+        self.insert_at(key, value, generation, Instant::now());
+    }
+
+    fn remove(&self, key: &K) {
+        let mut entries = self.lock();
+        entries.generation += 1;
+        entries.lru.pop(key);
+    }
+
+    fn remove_where(&self, stale: &dyn Fn(&K, &V) -> bool) {
+        let mut entries = self.lock();
+        entries.generation += 1;
+        entries.lru.retain(|key, entry| !stale(key, &entry.value));
+    }
+
+    fn clear(&self) {
+        let mut entries = self.lock();
+        entries.generation += 1;
+        entries.lru.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(capacity: usize) -> (InMemoryCache<u32, &'static str>, CacheCounters) {
+        let counters = CacheCounters::default();
+        let capacity = NonZeroUsize::new(capacity).unwrap();
+        let cache = InMemoryCache::new(capacity, Duration::from_secs(60), counters.clone());
+        (cache, counters)
+    }
+
+    #[test]
+    fn least_recently_used_entries_are_evicted_and_expired_ones_dropped() {
+        let (cache, counters) = cache(2);
+        let start = Instant::now();
+        let generation = cache.generation();
+        cache.insert_at(1, "one", generation, start);
+        cache.insert_at(2, "two", generation, start);
+
+        assert_eq!(cache.get_at(&1, start), Some("one"));
+        cache.insert_at(3, "three", generation, start);
+        assert_eq!(cache.get_at(&2, start), None);
+        assert_eq!(cache.get_at(&3, start), Some("three"));
+
+        let later = start + Duration::from_secs(60);
+        assert_eq!(cache.get_at(&1, later), None);
+        assert_eq!(counters.hits.get(), 2);
+        assert_eq!(counters.misses.get(), 2);
+    }
+
+    #[test]
+    fn invalidation_drops_matching_entries_and_values_loaded_before_it() {
+        let (cache, _) = cache(10);
+        let generation = cache.generation();
+        for key in 1..=4 {
+            cache.insert(key, "value", generation);
+        }
+
+        cache.remove_where(&|key, _| key % 2 == 0);
+        assert_eq!(cache.get(&1), Some("value"));
+        assert_eq!(cache.get(&2), None);
+
+        // Read before the invalidation above, so possibly from before the write behind it.
+        cache.insert(2, "stale", generation);
+        assert_eq!(cache.get(&2), None);
+
+        cache.insert(2, "fresh", cache.generation());
+        assert_eq!(cache.get(&2), Some("fresh"));
+
+        cache.clear();
+        assert_eq!(cache.get(&1), None);
+    }
+}
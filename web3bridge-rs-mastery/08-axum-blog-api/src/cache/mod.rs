@@ -0,0 +1,101 @@
+mod memory;
+
+use crate::config::CacheConfig;
+use crate::error::AppError;
+use crate::telemetry::Metrics;
+use prometheus_client::metrics::counter::Counter;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+
+pub use memory::InMemoryCache;
+
+/// Read-through cache for database rows. Writers invalidate what they change; values are
+/// never updated in place.
+pub trait Cache<K, V>: Debug + Send + Sync {
+    /// The value under `key` unless it is missing or expired, counted as a hit or a miss.
+    fn get(&self, key: &K) -> Option<V>;
+
+    /// Taken before loading a value, then handed to `insert`.
+    fn generation(&self) -> Generation;
+
+    /// Stores a value loaded since `generation`. It is dropped if anything was invalidated in
+    /// the meantime, since the load may have read rows from before that write.
+    fn insert(&self, key: K, value: V, generation: Generation);
+
+    fn remove(&self, key: &K);
+
+    /// Removes every entry `stale` returns true for.
+    fn remove_where(&self, stale: &dyn Fn(&K, &V) -> bool);
+
+    fn clear(&self);
+}
+
+/// Counts invalidations; see `Cache::insert`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Generation(pub u64);
+
+/// Hit and miss counters, registered with `Metrics` under the cache's name.
+#[derive(Debug, Clone, Default)]
+pub struct CacheCounters {
+    pub hits: Counter,
+    pub misses: Counter,
+}
+
+/// Caches nothing, so every read goes to the database.
+#[derive(Debug, Default)]
+pub struct NoCache;
+
+impl<K, V> Cache<K, V> for NoCache {
+    fn get(&self, _key: &K) -> Option<V> {
+        None
+    }
+
+    fn generation(&self) -> Generation {
+        Generation(0)
+    }
+
+    fn insert(&self, _key: K, _value: V, _generation: Generation) {}
+
+    fn remove(&self, _key: &K) {}
+
+    fn remove_where(&self, _stale: &dyn Fn(&K, &V) -> bool) {}
+
+    fn clear(&self) {}
+}
+
+/// The cache `name`, reported in `/metrics`, or `NoCache` when caching is switched off.
+pub fn build<K, V>(name: &str, config: &CacheConfig, metrics: &Metrics) -> Arc<dyn Cache<K, V>>
+where
+    K: Debug + Hash + Eq + Send + 'static,
+    V: Debug + Clone + Send + 'static,
+{
+    match NonZeroUsize::new(config.capacity) {
+        Some(capacity) if config.enabled => Arc::new(InMemoryCache::new(
+            capacity,
+            config.ttl,
+            metrics.cache_counters(name),
+        )),
+        _ => Arc::new(NoCache),
+    }
+}
+
+/// The cached value for `key`, or the result of `load`, which is cached when it succeeds.
+pub async fn get_or_load<K, V>(
+    cache: &dyn Cache<K, V>,
+    key: K,
+    load: impl Future<Output = Result<V, AppError>>,
+) -> Result<V, AppError>
+where
+    V: Clone,
+{
+    if let Some(value) = cache.get(&key) {
+        return Ok(value);
+    }
+
+    let generation = cache.generation();
+    let value = load.await?;
+    cache.insert(key, value.clone(), generation);
+    Ok(value)
+}
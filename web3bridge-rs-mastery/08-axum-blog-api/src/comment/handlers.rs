@@ -0,0 +1,509 @@
+use crate::app::state::SharedState;
+use crate::auth::AuthAuthor;
+use crate::comment::models::{
+    Comment, CommentListResponse, CommentResponse, CommentStatus, CreateCommentRequest,
+    ModerateCommentRequest,
+};
+use crate::comment::queries::{self, NewComment};
+use crate::error::{AppError, ErrorCode, ErrorResponse};
+use crate::extract::Path;
+use crate::post::{self, models::Post};
+use crate::response::MessageResponse;
+use crate::validation::{ValidJson, parse_uuid};
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[utoipa::path(
+    post,
+    path = "/posts/{post_id}/comments",
+    tag = "comments",
+    params(("post_id" = String, Path, description = "Post UUID")),
+    request_body = CreateCommentRequest,
+    security((), ("bearer" = [])),
+    responses(
+        (status = 201, description = "Comment created", body = CommentResponse),
+        (status = 404, description = "Post or parent comment not found", body = ErrorResponse),
+        (status = 422, description = "Invalid input", body = ErrorResponse),
+    )
+)]
+pub async fn create_comment(
+    State(state): State<SharedState>,
+    viewer: Option<AuthAuthor>,
+    Path(post_id): Path<String>,
+    ValidJson(payload): ValidJson<CreateCommentRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let viewer_id = viewer.map(|viewer| viewer.author_id);
+    let post = find_visible_post(&state, &post_id, viewer_id).await?;
+
+    let guest_name = match viewer_id {
+        Some(_) => None,
+        None => match payload.name.as_deref() {
+            Some(name) if !name.is_empty() => Some(name),
+            _ => {
+                return Err(AppError::invalid(
+                    "name",
+                    ErrorCode::ValidationRequired,
+                    "Guests must provide a name",
+                ));
+            }
+        },
+    };
+
+    let (parent_id, depth) = match payload.parent_id {
+        Some(parent_id) => {
+            let parent = queries::find_comment_by_id(&state, post.id, parent_id)
+                .await
+                .ok()
+                .filter(|parent| {
+                    parent.deleted_at.is_none() && parent.is_visible_to(viewer_id, post.author_id)
+                })
+                .ok_or_else(|| {
+                    AppError::invalid(
+                        "parent_id",
+                        ErrorCode::CommentParentNotFound,
+                        "Parent comment does not exist on this post",
+                    )
+                })?;
+
+            if parent.depth >= state.comment_max_depth {
+                return Err(AppError::invalid(
+                    "parent_id",
+                    ErrorCode::CommentTooDeep,
+                    format!(
+                        "Replies cannot be nested more than {} levels deep",
+                        state.comment_max_depth
+                    ),
+                ));
+            }
+            (Some(parent.id), parent.depth + 1)
+        }
+        None => (None, 0),
+    };
+
+    // The post's own author is trusted; everyone else waits for moderation.
+    let status = if viewer_id == Some(post.author_id) {
+        CommentStatus::Approved
+    } else {
+        CommentStatus::Pending
+    };
+
+    let comment = queries::insert_comment(
+        &state,
+        NewComment {
+            post_id: post.id,
+            parent_id,
+            author_id: viewer_id,
+            guest_name,
+            depth,
+            body: &payload.body,
+            status,
+        },
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(CommentResponse::from(comment))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/posts/{post_id}/comments",
+    tag = "comments",
+    params(("post_id" = String, Path, description = "Post UUID")),
+    security((), ("bearer" = [])),
+    responses(
+        (status = 200, description = "Threaded comments visible to the caller", body = CommentListResponse),
+        (status = 404, description = "Post not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_comments(
+    State(state): State<SharedState>,
+    viewer: Option<AuthAuthor>,
+    Path(post_id): Path<String>,
+) -> Result<Json<CommentListResponse>, AppError> {
+    let viewer_id = viewer.map(|viewer| viewer.author_id);
+    let post = find_visible_post(&state, &post_id, viewer_id).await?;
+
+    let comments: Vec<Comment> = queries::list_comments(&state, post.id)
+        .await?
+        .into_iter()
+        .filter(|comment| comment.is_visible_to(viewer_id, post.author_id))
+        .collect();
+
+    let total = comments.len();
+    let data = build_thread(comments);
+
+    Ok(Json(CommentListResponse { data, total }))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/posts/{post_id}/comments/{comment_id}",
+    tag = "comments",
+    params(
+        ("post_id" = String, Path, description = "Post UUID"),
+        ("comment_id" = String, Path, description = "Comment UUID"),
+    ),
+    request_body = ModerateCommentRequest,
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Moderated comment", body = CommentResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not the owner", body = ErrorResponse),
+        (status = 404, description = "Comment not found", body = ErrorResponse),
+        (status = 422, description = "Invalid input", body = ErrorResponse),
+    )
+)]
+pub async fn moderate_comment(
+    State(state): State<SharedState>,
+    auth: AuthAuthor,
+    Path((post_id, comment_id)): Path<(String, String)>,
+    ValidJson(payload): ValidJson<ModerateCommentRequest>,
+) -> Result<Json<CommentResponse>, AppError> {
+    let post = find_visible_post(&state, &post_id, Some(auth.author_id)).await?;
+    auth.ensure_owner(post.author_id)?;
+
+    let comment_id = parse_uuid("comment_id", &comment_id)?;
+    let comment =
+        queries::update_comment_status(&state, post.id, comment_id, payload.status).await?;
+
+    Ok(Json(CommentResponse::from(comment)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/posts/{post_id}/comments/{comment_id}",
+    tag = "comments",
+    params(
+        ("post_id" = String, Path, description = "Post UUID"),
+        ("comment_id" = String, Path, description = "Comment UUID"),
+    ),
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Comment deleted, or left as a tombstone for its replies", body = MessageResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not the owner", body = ErrorResponse),
+        (status = 404, description = "Comment not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_comment(
+    State(state): State<SharedState>,
+    auth: AuthAuthor,
+    Path((post_id, comment_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    let post = find_visible_post(&state, &post_id, Some(auth.author_id)).await?;
+    let comment_id = parse_uuid("comment_id", &comment_id)?;
+    let comment = queries::find_comment_by_id(&state, post.id, comment_id).await?;
+
+    // Commenters can remove their own comments; post authors can remove any on their post.
+    if comment.author_id != Some(auth.author_id) {
+        auth.ensure_owner(post.author_id)?;
+    }
+
+    // Only the post's author takes a whole subtree down; a commenter's own deletion leaves
+    // other people's replies in place.
+    if auth.author_id == post.author_id {
+        queries::delete_comment(&state, post.id, comment_id).await?;
+    } else {
+        queries::retract_comment(&state, post.id, comment_id).await?;
+    }
+
+    Ok((
+        StatusCode::OK,
+        Json(MessageResponse::ok("Comment deleted successfully")),
+    ))
+}
+
+async fn find_visible_post(
+    state: &SharedState,
+    post_id: &str,
+    viewer_id: Option<Uuid>,
+) -> Result<Post, AppError> {
+    let post_id = parse_uuid("post_id", post_id)?;
+    let post = post::find_post_by_id(state, post_id).await?;
+
+    if !post.is_visible_to(viewer_id) {
+        return Err(AppError::NotFound(
+            ErrorCode::PostNotFound,
+            format!("Post not found: {post_id}"),
+        ));
+    }
+
+    Ok(post)
+}
+
+/// Nests a flat, depth-ordered comment list into reply trees.
+///
+/// Replies whose parent was filtered out are dropped along with the hidden parent.
+fn build_thread(comments: Vec<Comment>) -> Vec<CommentResponse> {
+    let mut nodes: Vec<CommentResponse> = comments.into_iter().map(CommentResponse::from).collect();
+    let index: HashMap<String, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(position, node)| (node.id.clone(), position))
+        .collect();
+
+    // Parents always sit before their replies, so popping from the back only ever
+    // moves a node into a slot that is still in `nodes`.
+    let mut roots = Vec::new();
+    while let Some(node) = nodes.pop() {
+        match &node.parent_id {
+            None => roots.push(node),
+            Some(parent_id) => {
+                if let Some(parent) = index.get(parent_id).and_then(|&i| nodes.get_mut(i)) {
+                    parent.replies.push(node);
+                }
+            }
+        }
+    }
+
+    roots.reverse();
+    for root in &mut roots {
+        reverse_replies(root);
+    }
+    roots
+}
+
+fn reverse_replies(node: &mut CommentResponse) {
+    node.replies.reverse();
+    for reply in &mut node.replies {
+        reverse_replies(reply);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::state::AppState;
+    use crate::app::test_support::{current_etag, send, send_with_headers, signed_in_author};
+    use crate::db::DbPool;
+    use axum::http::{Method, header::IF_MATCH};
+    use chrono::{Duration, Utc};
+    use serde_json::json;
+
+    fn comment(id: Uuid, parent_id: Option<Uuid>, depth: i32, minutes: i64) -> Comment {
+        let at = Utc::now() + Duration::minutes(minutes);
+        Comment {
+            id,
+            post_id: Uuid::nil(),
+            parent_id,
+            author_id: None,
+            author_name: "guest".to_string(),
+            depth,
+            body: "hi".to_string(),
+            status: CommentStatus::Approved,
+            created_at: at,
+            updated_at: at,
+            deleted_at: None,
+        }
+    }
+
+    #[test]
+    fn thread_nests_replies_in_order() {
+        let (a, b, a1, a2, a1x) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+        let flat = vec![
+            comment(a, None, 0, 0),
+            comment(b, None, 0, 1),
+            comment(a1, Some(a), 1, 2),
+            comment(a2, Some(a), 1, 3),
+            comment(a1x, Some(a1), 2, 4),
+        ];
+
+        let thread = build_thread(flat);
+
+        assert_eq!(thread.len(), 2);
+        assert_eq!(thread[0].id, a.to_string());
+        assert_eq!(thread[1].id, b.to_string());
+        let replies: Vec<_> = thread[0].replies.iter().map(|r| r.id.clone()).collect();
+        assert_eq!(replies, vec![a1.to_string(), a2.to_string()]);
+        assert_eq!(thread[0].replies[0].replies[0].id, a1x.to_string());
+    }
+
+    #[test]
+    fn replies_to_hidden_comments_are_dropped() {
+        let hidden_parent = Uuid::new_v4();
+        let flat = vec![comment(Uuid::new_v4(), Some(hidden_parent), 1, 0)];
+
+        assert!(build_thread(flat).is_empty());
+    }
+
+    #[tokio::test]
+    async fn comment_bodies_are_rejected_per_field() {
+        let state = AppState::for_tests_in_memory();
+        let (_, token) = signed_in_author(&state, "writer@example.com").await;
+        let post = json!({ "title": "Hello", "content": "World" });
+        let (_, post) = send(&state, Method::POST, "/posts", Some(&token), Some(post)).await;
+        let uri = format!("/posts/{}/comments", post["id"].as_str().unwrap());
+
+        for (body, field, code) in [
+            (
+                json!({ "body": "  ", "name": "Guest" }),
+                "body",
+                "validation.required",
+            ),
+            (json!({ "body": "Hi" }), "name", "validation.required"),
+            (
+                json!({ "body": "Hi", "name": "Guest", "parent_id": "42" }),
+                "parent_id",
+                "validation.invalid_uuid",
+            ),
+        ] {
+            let (status, error) = send(&state, Method::POST, &uri, None, Some(body)).await;
+            assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+            assert_eq!(error["errors"][0]["field"], field);
+            assert_eq!(error["errors"][0]["code"], code);
+        }
+
+        let uri = format!("{uri}/{}", Uuid::new_v4());
+        let body = json!({ "status": "spam" });
+        let (status, error) = send(&state, Method::PATCH, &uri, Some(&token), Some(body)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error["errors"][0]["field"], "status");
+        assert_eq!(error["errors"][0]["code"], "request.invalid_body");
+    }
+
+    #[sqlx::test(migrator = "crate::db::MIGRATOR")]
+    #[cfg_attr(feature = "postgres", ignore = "needs a Postgres DATABASE_URL")]
+    async fn deleting_an_author_keeps_their_comments_and_the_replies_under_them(pool: DbPool) {
+        let state = AppState::for_tests_with_pool(pool);
+        let (_, owner_token) = signed_in_author(&state, "owner@example.com").await;
+        let (leaver_id, leaver_token) = signed_in_author(&state, "leaver@example.com").await;
+
+        let post = json!({ "title": "Hello", "content": "World" });
+        let (_, post) = send(
+            &state,
+            Method::POST,
+            "/posts",
+            Some(&owner_token),
+            Some(post),
+        )
+        .await;
+        let comments = format!("/posts/{}/comments", post["id"].as_str().unwrap());
+        let comment = json!({ "body": "First!" });
+        let (_, comment) = send(
+            &state,
+            Method::POST,
+            &comments,
+            Some(&leaver_token),
+            Some(comment),
+        )
+        .await;
+        let reply = json!({ "body": "Welcome", "parent_id": comment["id"] });
+        let (status, _) = send(
+            &state,
+            Method::POST,
+            &comments,
+            Some(&owner_token),
+            Some(reply),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let author = format!("/authors/{leaver_id}");
+        let etag = current_etag(&state, &author, None).await;
+        let (status, _, _) = send_with_headers(
+            &state,
+            Method::DELETE,
+            &author,
+            Some(&leaver_token),
+            &[(IF_MATCH, etag.as_str())],
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (_, thread) = send(&state, Method::GET, &comments, Some(&owner_token), None).await;
+        assert_eq!(thread["total"], 2);
+        let orphan = &thread["data"][0];
+        assert_eq!(orphan["author_name"], "[deleted author]");
+        assert!(orphan["author_id"].is_null());
+        assert_eq!(orphan["replies"][0]["body"], "Welcome");
+    }
+
+    #[sqlx::test(migrator = "crate::db::MIGRATOR")]
+    #[cfg_attr(feature = "postgres", ignore = "needs a Postgres DATABASE_URL")]
+    async fn deleting_your_own_comment_keeps_other_peoples_replies(pool: DbPool) {
+        let state = AppState::for_tests_with_pool(pool);
+        let (_, owner_token) = signed_in_author(&state, "owner@example.com").await;
+        let (_, a_token) = signed_in_author(&state, "a@example.com").await;
+        let (_, b_token) = signed_in_author(&state, "b@example.com").await;
+
+        let post = json!({ "title": "Hello", "content": "World" });
+        let (_, post) = send(
+            &state,
+            Method::POST,
+            "/posts",
+            Some(&owner_token),
+            Some(post),
+        )
+        .await;
+        let comments = format!("/posts/{}/comments", post["id"].as_str().unwrap());
+        let parent = json!({ "body": "First!" });
+        let (_, parent) = send(
+            &state,
+            Method::POST,
+            &comments,
+            Some(&a_token),
+            Some(parent),
+        )
+        .await;
+        let parent_uri = format!("{comments}/{}", parent["id"].as_str().unwrap());
+        let approve = json!({ "status": "approved" });
+        let (status, _) = send(
+            &state,
+            Method::PATCH,
+            &parent_uri,
+            Some(&owner_token),
+            Some(approve),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let reply = json!({ "body": "Second", "parent_id": parent["id"] });
+        let (status, _) = send(&state, Method::POST, &comments, Some(&b_token), Some(reply)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let leaf = json!({ "body": "Typo" });
+        let (_, leaf) = send(&state, Method::POST, &comments, Some(&a_token), Some(leaf)).await;
+
+        for comment in [&parent, &leaf] {
+            let uri = format!("{comments}/{}", comment["id"].as_str().unwrap());
+            let (status, _) = send(&state, Method::DELETE, &uri, Some(&a_token), None).await;
+            assert_eq!(status, StatusCode::OK);
+        }
+
+        // The unanswered comment is gone; the answered one is a tombstone over B's reply.
+        let (_, thread) = send(&state, Method::GET, &comments, Some(&owner_token), None).await;
+        assert_eq!(thread["total"], 2);
+        let tombstone = &thread["data"][0];
+        assert_eq!(tombstone["id"], parent["id"]);
+        assert_eq!(tombstone["deleted"], true);
+        assert_eq!(tombstone["body"], "[deleted]");
+        assert_eq!(tombstone["author_name"], "[deleted]");
+        assert!(tombstone["author_id"].is_null());
+        assert_eq!(tombstone["replies"][0]["body"], "Second");
+
+        let reply = json!({ "body": "Third", "parent_id": parent["id"] });
+        let (status, error) =
+            send(&state, Method::POST, &comments, Some(&b_token), Some(reply)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error["errors"][0]["code"], "comment.parent_not_found");
+
+        // The post's author still removes the whole subtree.
+        let (status, _) = send(
+            &state,
+            Method::DELETE,
+            &parent_uri,
+            Some(&owner_token),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (_, thread) = send(&state, Method::GET, &comments, Some(&owner_token), None).await;
+        assert_eq!(thread["total"], 0);
+    }
+}
@@ -0,0 +1,33 @@
+mod handlers;
+pub mod models;
+mod queries;
+
+use crate::app::state::SharedState;
+use axum::{
+    Router,
+    routing::{get, patch},
+};
+use utoipa::OpenApi;
+
+/// OpenAPI paths for the routes under `/posts/{post_id}/comments`.
+#[derive(OpenApi)]
+#[openapi(paths(
+    handlers::get_comments,
+    handlers::create_comment,
+    handlers::moderate_comment,
+    handlers::delete_comment,
+))]
+pub struct CommentApi;
+
+/// Routes nested under `/posts/{post_id}/comments`.
+pub fn comment_routes() -> Router<SharedState> {
+    Router::new()
+        .route(
+            "/",
+            get(handlers::get_comments).post(handlers::create_comment),
+        )
+        .route(
+            "/{comment_id}",
+            patch(handlers::moderate_comment).delete(handlers::delete_comment),
+        )
+}
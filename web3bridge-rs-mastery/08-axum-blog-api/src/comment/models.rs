@@ -0,0 +1,115 @@
+use crate::validation::{self, MAX_VARCHAR_LENGTH};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+const MAX_BODY_LENGTH: u64 = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum CommentStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Comment {
+    pub id: Uuid,
+    pub post_id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub author_id: Option<Uuid>,
+    pub author_name: String,
+    pub depth: i32,
+    pub body: String,
+    pub status: CommentStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Set when the commenter deleted it while it had replies; the body is then blank.
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl Comment {
+    /// Approved comments are public; the commenter and the post's author also see the rest.
+    pub fn is_visible_to(&self, viewer_id: Option<Uuid>, post_author_id: Uuid) -> bool {
+        self.status == CommentStatus::Approved
+            || viewer_id == Some(post_author_id)
+            || (viewer_id.is_some() && viewer_id == self.author_id)
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct CreateCommentRequest {
+    #[serde(deserialize_with = "validation::trim")]
+    #[validate(length(min = 1, max = MAX_BODY_LENGTH))]
+    pub body: String,
+    #[serde(default, deserialize_with = "validation::uuid_opt")]
+    pub parent_id: Option<Uuid>,
+    /// Display name for guests, who must give one; ignored when the request is authenticated.
+    #[serde(default, deserialize_with = "validation::trim_opt")]
+    #[validate(length(max = MAX_VARCHAR_LENGTH))]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct ModerateCommentRequest {
+    pub status: CommentStatus,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CommentResponse {
+    pub id: String,
+    pub post_id: String,
+    pub parent_id: Option<String>,
+    pub author_id: Option<String>,
+    pub author_name: String,
+    pub depth: i32,
+    pub body: String,
+    pub status: CommentStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// A deleted comment kept so its replies stay threaded; its author and body read
+    /// `[deleted]`.
+    pub deleted: bool,
+    #[schema(no_recursion)]
+    pub replies: Vec<CommentResponse>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CommentListResponse {
+    pub data: Vec<CommentResponse>,
+    pub total: usize,
+}
+
+impl From<Comment> for CommentResponse {
+    fn from(value: Comment) -> Self {
+        let deleted = value.deleted_at.is_some();
+        let (author_id, author_name, body) = if deleted {
+            (None, "[deleted]".to_string(), "[deleted]".to_string())
+        } else {
+            (
+                value.author_id.map(|id| id.to_string()),
+                value.author_name,
+                value.body,
+            )
+        };
+        Self {
+            id: value.id.to_string(),
+            post_id: value.post_id.to_string(),
+            parent_id: value.parent_id.map(|id| id.to_string()),
+            author_id,
+            author_name,
+            depth: value.depth,
+            body,
+            status: value.status,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+            deleted,
+            replies: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use crate::app::state::SharedState;
+use crate::comment::models::{Comment, CommentStatus};
+use crate::db::NOW;
+use crate::error::{AppError, ErrorCode};
+use uuid::Uuid;
+
+// Authenticated commenters show their current author name; guests keep the name they gave.
+// Comments outlive a deleted author, whose `author_id` is then cleared.
+const COMMENT_SELECT: &str = "SELECT c.id, c.post_id, c.parent_id, c.author_id, COALESCE(a.name, c.guest_name, '[deleted author]') AS author_name, c.depth, c.body, c.status, c.created_at, c.updated_at, c.deleted_at FROM comments c LEFT JOIN authors a ON a.id = c.author_id";
+
+pub struct NewComment<'a> {
+    pub post_id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub author_id: Option<Uuid>,
+    pub guest_name: Option<&'a str>,
+    pub depth: i32,
+    pub body: &'a str,
+    pub status: CommentStatus,
+}
+
+pub async fn insert_comment(state: &SharedState, new: NewComment<'_>) -> Result<Comment, AppError> {
+    let comment_id = Uuid::new_v4();
+
+    sqlx::query(
+        "INSERT INTO comments (id, post_id, parent_id, author_id, guest_name, depth, body, status) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+    )
+    .bind(comment_id)
+    .bind(new.post_id)
+    .bind(new.parent_id)
+    .bind(new.author_id)
+    .bind(new.guest_name)
+    .bind(new.depth)
+    .bind(new.body)
+    .bind(new.status)
+    .execute(&state.db_pool)
+    .await
+    .map_err(|err| AppError::InternalServerError(format!("Failed to create comment: {err}")))?;
+
+    find_comment_by_id(state, new.post_id, comment_id).await
+}
+
+/// Returns every comment on a post, oldest first, so parents precede their replies.
+pub async fn list_comments(state: &SharedState, post_id: Uuid) -> Result<Vec<Comment>, AppError> {
+    let comments = sqlx::query_as::<_, Comment>(&format!(
+        "{COMMENT_SELECT} WHERE c.post_id = $1 ORDER BY c.depth ASC, c.created_at ASC, c.id ASC"
+    ))
+    .bind(post_id)
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(|err| AppError::InternalServerError(format!("Failed to fetch comments: {err}")))?;
+
+    Ok(comments)
+}
+
+pub async fn find_comment_by_id(
+    state: &SharedState,
+    post_id: Uuid,
+    id: Uuid,
+) -> Result<Comment, AppError> {
+    let comment = sqlx::query_as::<_, Comment>(&format!(
+        "{COMMENT_SELECT} WHERE c.id = $1 AND c.post_id = $2"
+    ))
+    .bind(id)
+    .bind(post_id)
+    .fetch_optional(&state.db_pool)
+    .await
+    .map_err(|err| AppError::InternalServerError(format!("Failed to fetch comment: {err}")))?
+    .ok_or_else(|| {
+        AppError::NotFound(
+            ErrorCode::CommentNotFound,
+            format!("Comment not found: {id}"),
+        )
+    })?;
+
+    Ok(comment)
+}
+
+pub async fn update_comment_status(
+    state: &SharedState,
+    post_id: Uuid,
+    id: Uuid,
+    status: CommentStatus,
+) -> Result<Comment, AppError> {
+    let result = sqlx::query(&format!(
+        "UPDATE comments SET status = $3, updated_at = {NOW} WHERE id = $1 AND post_id = $2"
+    ))
+    .bind(id)
+    .bind(post_id)
+    .bind(status)
+    .execute(&state.db_pool)
+    .await
+    .map_err(|err| AppError::InternalServerError(format!("Failed to update comment: {err}")))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(
+            ErrorCode::CommentNotFound,
+            format!("Comment not found: {id}"),
+        ));
+    }
+
+    find_comment_by_id(state, post_id, id).await
+}
+
+/// Removes a commenter's own comment without taking anyone's replies with it.
+///
+/// A comment nobody has replied to is deleted outright; otherwise its body is blanked and
+/// it stays in the thread as a tombstone. Deleting the last reply under a tombstone does
+/// not remove the tombstone.
+pub async fn retract_comment(state: &SharedState, post_id: Uuid, id: Uuid) -> Result<(), AppError> {
+    let deleted = sqlx::query(
+        "DELETE FROM comments WHERE id = $1 AND post_id = $2 AND NOT EXISTS (SELECT 1 FROM comments r WHERE r.parent_id = $1)",
+    )
+    .bind(id)
+    .bind(post_id)
+    .execute(&state.db_pool)
+    .await
+    .map_err(|err| AppError::InternalServerError(format!("Failed to delete comment: {err}")))?;
+
+    if deleted.rows_affected() > 0 {
+        return Ok(());
+    }
+
+    let result = sqlx::query(&format!(
+        "UPDATE comments SET body = '', deleted_at = {NOW}, updated_at = {NOW} WHERE id = $1 AND post_id = $2"
+    ))
+    .bind(id)
+    .bind(post_id)
+    .execute(&state.db_pool)
+    .await
+    .map_err(|err| AppError::InternalServerError(format!("Failed to delete comment: {err}")))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(
+            ErrorCode::CommentNotFound,
+            format!("Comment not found: {id}"),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Deletes a comment; its replies go with it through `ON DELETE CASCADE`. Only the post's
+/// author moderates this way.
+pub async fn delete_comment(state: &SharedState, post_id: Uuid, id: Uuid) -> Result<(), AppError> {
+    let result = sqlx::query("DELETE FROM comments WHERE id = $1 AND post_id = $2")
+        .bind(id)
+        .bind(post_id)
+        .execute(&state.db_pool)
+        .await
+        .map_err(|err| AppError::InternalServerError(format!("Failed to delete comment: {err}")))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(
+            ErrorCode::CommentNotFound,
+            format!("Comment not found: {id}"),
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use crate::config::DatabaseConfig;
+use crate::db::{self, DbPool, MIGRATOR};
+use crate::error::AppError;
+use secrecy::ExposeSecret;
+use sqlx::pool::PoolOptions;
+
+pub async fn connect_db(config: &DatabaseConfig) -> Result<DbPool, AppError> {
+    tracing::info!(
+        max_connections = config.max_connections,
+        min_connections = config.min_connections,
+        "connecting to database"
+    );
+    let failed = |err: sqlx::Error| {
+        AppError::InternalServerError(format!("Failed to connect to the database: {err}"))
+    };
+    let options = db::connect_options(config.url.expose_secret()).map_err(failed)?;
+    let db_pool = PoolOptions::new()
+        .max_connections(config.max_connections)
+        .min_connections(config.min_connections)
+        .acquire_timeout(config.acquire_timeout)
+        .idle_timeout(config.idle_timeout)
+        .connect_with(options)
+        .await
+        .map_err(failed)?;
+    tracing::info!("connected to database");
+
+    MIGRATOR
+        .run(&db_pool)
+        .await
+        .map_err(|err| AppError::InternalServerError(err.to_string()))?;
+    tracing::info!("migrations applied");
+
+    Ok(db_pool)
+}
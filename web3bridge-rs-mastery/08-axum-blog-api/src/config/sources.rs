@@ -0,0 +1,220 @@
+use crate::config::{Budget, LogFormat, RouteBudget};
+use secrecy::SecretString;
+use serde::Deserialize;
+use std::fmt::Display;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+/// Shape of `config.toml`. Every key is optional; unknown keys are rejected so typos surface.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FileConfig {
+    pub server: ServerSection,
+    pub site: SiteSection,
+    pub database: DatabaseSection,
+    pub auth: AuthSection,
+    pub comments: CommentsSection,
+    pub payments: PaymentsSection,
+    pub admin: AdminSection,
+    pub cors: CorsSection,
+    pub log: LogSection,
+    pub features: FeaturesSection,
+    pub rate_limit: RateLimitSection,
+    pub cache: CacheSection,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerSection {
+    pub host: Option<IpAddr>,
+    pub port: Option<u16>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SiteSection {
+    pub url: Option<String>,
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseSection {
+    pub url: Option<String>,
+    pub max_connections: Option<u32>,
+    pub min_connections: Option<u32>,
+    pub acquire_timeout_seconds: Option<u64>,
+    pub idle_timeout_seconds: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthSection {
+    pub jwt_secret: Option<String>,
+    pub jwt_ttl_seconds: Option<i64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CommentsSection {
+    pub max_depth: Option<i32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PaymentsSection {
+    pub paystack_secret_key: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdminSection {
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CorsSection {
+    pub allowed_origins: Option<Vec<String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogSection {
+    pub level: Option<String>,
+    pub format: Option<LogFormat>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FeaturesSection {
+    pub docs: Option<bool>,
+    pub metrics: Option<bool>,
+    pub comments: Option<bool>,
+    pub webhooks: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitSection {
+    pub enabled: Option<bool>,
+    pub trust_forwarded_for: Option<bool>,
+    pub burst: Option<u32>,
+    pub per_minute: Option<u32>,
+    pub routes: Option<Vec<RouteBudget>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheSection {
+    pub enabled: Option<bool>,
+    pub capacity: Option<usize>,
+    pub ttl_seconds: Option<u64>,
+}
+
+/// One `[[rate_limit.routes]]` table, checked as it is read into a `RouteBudget`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RouteBudgetSection {
+    pub route: String,
+    pub burst: u32,
+    pub per_minute: u32,
+}
+
+impl TryFrom<RouteBudgetSection> for RouteBudget {
+    type Error = String;
+
+    fn try_from(section: RouteBudgetSection) -> Result<Self, Self::Error> {
+        let budget = Budget {
+            burst: section.burst,
+            per_minute: section.per_minute,
+        };
+        RouteBudget::new(&section.route, budget)
+    }
+}
+
+/// Layers environment variables over file values and records every problem it meets.
+pub struct Resolver<E> {
+    env: E,
+    problems: Vec<String>,
+}
+
+impl<E: Fn(&str) -> Option<String>> Resolver<E> {
+    pub fn new(env: E, problems: Vec<String>) -> Self {
+        Self { env, problems }
+    }
+
+    pub fn into_problems(self) -> Vec<String> {
+        self.problems
+    }
+
+    // Blank variables count as unset, so `FOO=` in a .env file does not override the file.
+    fn env(&self, env_key: &str) -> Option<String> {
+        (self.env)(env_key)
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+
+    /// The environment value parsed as `T`, else the value from the file.
+    pub fn value<T>(&mut self, file_key: &str, env_key: &str, file: Option<T>) -> Option<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let Some(raw) = self.env(env_key) else {
+            return file;
+        };
+        match raw.parse::<T>() {
+            Ok(value) => Some(value),
+            Err(err) => {
+                self.problems.push(format!(
+                    "{file_key} ({env_key}): invalid value '{raw}': {err}"
+                ));
+                None
+            }
+        }
+    }
+
+    /// Like `value` for strings, but wrapped so it never shows up in logs or `Debug` output.
+    pub fn secret(&self, env_key: &str, file: Option<String>) -> Option<SecretString> {
+        self.env(env_key).or(file).map(SecretString::from)
+    }
+
+    /// A list from a TOML array, or from a comma-separated environment variable.
+    pub fn list(&self, env_key: &str, file: Option<Vec<String>>) -> Option<Vec<String>> {
+        match self.env(env_key) {
+            Some(raw) => Some(
+                raw.split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            ),
+            None => file,
+        }
+    }
+
+    /// Like `list`, with every environment item parsed as `T`.
+    pub fn parsed_list<T>(
+        &mut self,
+        file_key: &str,
+        env_key: &str,
+        file: Option<Vec<T>>,
+    ) -> Option<Vec<T>>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let Some(raw) = self.list(env_key, None) else {
+            return file;
+        };
+        let mut items = Vec::with_capacity(raw.len());
+        for item in raw {
+            match item.parse::<T>() {
+                Ok(value) => items.push(value),
+                Err(err) => self.problems.push(format!("{file_key} ({env_key}): {err}")),
+            }
+        }
+        Some(items)
+    }
+}
@@ -0,0 +1,66 @@
+//! The database backend, chosen at build time: `postgres` (the default) or `sqlite`
+//! (`cargo run --no-default-features --features sqlite`).
+//!
+//! Queries are written against the aliases below. Where the two dialects disagree
+//! the query modules carry one statement per backend behind `cfg(feature = ...)`.
+
+#[cfg(all(feature = "postgres", feature = "sqlite"))]
+compile_error!(
+    "the `postgres` and `sqlite` features are mutually exclusive; add --no-default-features"
+);
+
+#[cfg(not(any(feature = "postgres", feature = "sqlite")))]
+compile_error!("enable a database backend: the `postgres` or the `sqlite` feature");
+
+#[cfg(feature = "postgres")]
+mod postgres;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+#[cfg(feature = "postgres")]
+pub use postgres::{
+    Db, MIGRATOR, NOW, URL_SCHEMES, connect_options, is_undefined_table, is_unique_violation,
+    timestamp,
+};
+#[cfg(feature = "sqlite")]
+pub use sqlite::{
+    Db, MIGRATOR, NOW, URL_SCHEMES, connect_options, is_undefined_table, is_unique_violation,
+    timestamp,
+};
+
+#[cfg(all(test, feature = "postgres"))]
+pub use postgres::unreachable_pool;
+#[cfg(all(test, feature = "sqlite"))]
+pub use sqlite::unreachable_pool;
+
+pub type DbPool = sqlx::Pool<Db>;
+pub type DbConnection = <Db as sqlx::Database>::Connection;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[sqlx::test(migrator = "crate::db::MIGRATOR")]
+    #[cfg_attr(feature = "postgres", ignore = "needs a Postgres DATABASE_URL")]
+    async fn backend_errors_are_recognised(pool: DbPool) {
+        let insert = || {
+            sqlx::query(
+                "INSERT INTO authors (id, name, email) VALUES ($1, 'Ada', 'ada@example.com')",
+            )
+            .bind(Uuid::new_v4())
+            .execute(&pool)
+        };
+        insert().await.unwrap();
+        let duplicate = insert().await.unwrap_err();
+        assert!(is_unique_violation(&duplicate), "{duplicate}");
+        assert!(!is_undefined_table(&duplicate));
+
+        let missing = sqlx::query("SELECT 1 FROM no_such_table")
+            .execute(&pool)
+            .await
+            .unwrap_err();
+        assert!(is_undefined_table(&missing), "{missing}");
+        assert!(!is_unique_violation(&missing));
+    }
+}
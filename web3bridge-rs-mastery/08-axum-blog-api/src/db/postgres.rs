@@ -0,0 +1,49 @@
+#[cfg(test)]
+use crate::db::DbPool;
+use chrono::{DateTime, Utc};
+use sqlx::migrate::Migrator;
+use sqlx::postgres::PgConnectOptions;
+
+pub type Db = sqlx::Postgres;
+
+/// Migrations embedded at compile time; also used by the readiness probe to spot pending ones.
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations/postgres");
+
+pub const URL_SCHEMES: &[&str] = &["postgres://", "postgresql://"];
+
+/// The current time as a SQL expression.
+pub const NOW: &str = "NOW()";
+
+/// `at` for binding where it is compared with stored values; Postgres compares
+/// `TIMESTAMPTZ` by value, so it is bound as is.
+pub fn timestamp(at: DateTime<Utc>) -> DateTime<Utc> {
+    at
+}
+
+pub fn connect_options(url: &str) -> Result<PgConnectOptions, sqlx::Error> {
+    url.parse()
+}
+
+/// `unique_violation`, raised by a duplicate key in a `UNIQUE` or primary key index.
+pub fn is_unique_violation(err: &sqlx::Error) -> bool {
+    has_code(err, "23505")
+}
+
+/// `undefined_table`.
+pub fn is_undefined_table(err: &sqlx::Error) -> bool {
+    has_code(err, "42P01")
+}
+
+fn has_code(err: &sqlx::Error, code: &str) -> bool {
+    matches!(err, sqlx::Error::Database(db_err) if db_err.code().as_deref() == Some(code))
+}
+
+/// A pool for tests that never reach the database: nothing listens on port 1, so
+/// any query fails fast instead of hanging.
+#[cfg(test)]
+pub fn unreachable_pool() -> DbPool {
+    sqlx::postgres::PgPoolOptions::new()
+        .acquire_timeout(std::time::Duration::from_millis(100))
+        .connect_lazy("postgres://localhost:1/unused")
+        .expect("lazy pools do not connect")
+}
@@ -0,0 +1,64 @@
+#[cfg(test)]
+use crate::db::DbPool;
+use chrono::{DateTime, SecondsFormat, Utc};
+use sqlx::migrate::Migrator;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode};
+use std::str::FromStr;
+
+pub type Db = sqlx::Sqlite;
+
+/// Migrations embedded at compile time; also used by the readiness probe to spot pending ones.
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations/sqlite");
+
+pub const URL_SCHEMES: &[&str] = &["sqlite:"];
+
+/// The current time as a SQL expression, in RFC 3339 with milliseconds; see `timestamp`.
+pub const NOW: &str = "strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')";
+
+/// `at` in the text form `NOW` stores, for binding where it is compared with stored values.
+///
+/// Timestamps are compared as text. A bound `DateTime` drops a zero fraction, and
+/// `…:00+00:00` sorts before the `…:00.000+00:00` of the same instant, so the fraction is
+/// always written: as milliseconds like `NOW`, or microseconds when `at` has them.
+pub fn timestamp(at: DateTime<Utc>) -> String {
+    let format = if at.timestamp_subsec_micros().is_multiple_of(1_000) {
+        SecondsFormat::Millis
+    } else {
+        SecondsFormat::Micros
+    };
+    at.to_rfc3339_opts(format, false)
+}
+
+/// Creates the database file on first run. WAL lets readers carry on while a write
+/// is in progress; writers still queue behind one another.
+pub fn connect_options(url: &str) -> Result<SqliteConnectOptions, sqlx::Error> {
+    Ok(SqliteConnectOptions::from_str(url)?
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal))
+}
+
+/// `SQLITE_CONSTRAINT_UNIQUE` or `SQLITE_CONSTRAINT_PRIMARYKEY`, as extended result codes.
+pub fn is_unique_violation(err: &sqlx::Error) -> bool {
+    matches!(
+        err,
+        sqlx::Error::Database(db_err) if matches!(db_err.code().as_deref(), Some("2067" | "1555"))
+    )
+}
+
+/// SQLite reports a missing table as a plain `SQLITE_ERROR`, so only the message tells.
+pub fn is_undefined_table(err: &sqlx::Error) -> bool {
+    matches!(
+        err,
+        sqlx::Error::Database(db_err) if db_err.message().starts_with("no such table")
+    )
+}
+
+/// A pool for tests that never reach the database: the file cannot be created, so
+/// any query fails fast.
+#[cfg(test)]
+pub fn unreachable_pool() -> DbPool {
+    sqlx::sqlite::SqlitePoolOptions::new()
+        .acquire_timeout(std::time::Duration::from_millis(100))
+        .connect_lazy("sqlite:///nonexistent/unused.db")
+        .expect("lazy pools do not connect")
+}
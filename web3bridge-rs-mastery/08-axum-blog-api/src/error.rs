@@ -1,52 +1,397 @@
+use crate::telemetry::current_request_id;
 use axum::{
     Json,
-    http::StatusCode,
+    http::{HeaderValue, StatusCode, header::CONTENT_TYPE},
     response::{IntoResponse, Response},
 };
-use serde_json::json;
+use serde::Serialize;
 use thiserror::Error;
+use utoipa::ToSchema;
+
+pub const PROBLEM_JSON: &str = "application/problem+json";
 
 #[derive(Debug, Error)]
 pub enum AppError {
-    #[error("Resource not found: {0}")]
-    NotFound(String),
+    #[error("Resource not found: {1}")]
+    NotFound(ErrorCode, String),
+    #[error("Unauthorized: {1}")]
+    Unauthorized(ErrorCode, String),
+    #[error("Forbidden: {1}")]
+    Forbidden(ErrorCode, String),
+    #[error("Method not allowed: {0}")]
+    MethodNotAllowed(String),
+    #[error("Precondition failed: {0}")]
+    PreconditionFailed(String),
+    #[error("Precondition required: {0}")]
+    PreconditionRequired(String),
+    #[error("Too many requests: {0}")]
+    TooManyRequests(String),
+    /// The message is logged but never sent to the client.
     #[error("Internal Server Error: {0}")]
     InternalServerError(String),
-    #[error("Invalid Input, cannot be processed: {field} - {message}")]
-    UnProcessableEntity { field: String, message: String },
-    #[error("Environment variable is missing: {0}")]
-    MissingEnvironmentVarible(String),
-    #[error("Failed to parse: {0}")]
-    ParsingError(String),
+    #[error("Invalid Input, cannot be processed: {}", describe(.0))]
+    UnProcessableEntity(Vec<FieldError>),
 }
 
-impl IntoResponse for AppError {
-    fn into_response(self) -> Response {
+/// Stable, machine-readable identifier for an error, sent as `code`.
+///
+/// Clients should branch on these rather than on `detail`, whose wording may change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+pub enum ErrorCode {
+    #[serde(rename = "auth.missing_token")]
+    AuthMissingToken,
+    #[serde(rename = "auth.invalid_token")]
+    AuthInvalidToken,
+    #[serde(rename = "auth.invalid_credentials")]
+    AuthInvalidCredentials,
+    #[serde(rename = "auth.sign_in_required")]
+    AuthSignInRequired,
+    #[serde(rename = "auth.not_owner")]
+    AuthNotOwner,
+    #[serde(rename = "auth.admin_disabled")]
+    AuthAdminDisabled,
+    #[serde(rename = "auth.invalid_admin_token")]
+    AuthInvalidAdminToken,
+    /// The password reset token is unknown, already used or expired.
+    #[serde(rename = "auth.invalid_reset_token")]
+    AuthInvalidResetToken,
+    #[serde(rename = "author.not_found")]
+    AuthorNotFound,
+    #[serde(rename = "author.email_taken")]
+    AuthorEmailTaken,
+    #[serde(rename = "post.not_found")]
+    PostNotFound,
+    #[serde(rename = "post.unknown_author")]
+    PostUnknownAuthor,
+    #[serde(rename = "post.invalid_schedule")]
+    PostInvalidSchedule,
+    #[serde(rename = "post.subscription_required")]
+    PostSubscriptionRequired,
+    #[serde(rename = "revision.not_found")]
+    RevisionNotFound,
+    #[serde(rename = "tag.not_found")]
+    TagNotFound,
+    #[serde(rename = "tag.too_many")]
+    TagTooMany,
+    #[serde(rename = "comment.not_found")]
+    CommentNotFound,
+    #[serde(rename = "comment.parent_not_found")]
+    CommentParentNotFound,
+    #[serde(rename = "comment.too_deep")]
+    CommentTooDeep,
+    #[serde(rename = "pagination.invalid_cursor")]
+    PaginationInvalidCursor,
+    #[serde(rename = "pagination.cursor_with_search")]
+    PaginationCursorWithSearch,
+    #[serde(rename = "bulk.line_too_long")]
+    BulkLineTooLong,
+    #[serde(rename = "bulk.unreadable_body")]
+    BulkUnreadableBody,
+    #[serde(rename = "bulk.invalid_record")]
+    BulkInvalidRecord,
+    #[serde(rename = "bulk.unknown_author")]
+    BulkUnknownAuthor,
+    #[serde(rename = "payment.invalid_signature")]
+    PaymentInvalidSignature,
+    #[serde(rename = "payment.invalid_payload")]
+    PaymentInvalidPayload,
+    /// A required field is missing or blank.
+    #[serde(rename = "validation.required")]
+    ValidationRequired,
+    #[serde(rename = "validation.invalid_email")]
+    ValidationInvalidEmail,
+    #[serde(rename = "validation.invalid_uuid")]
+    ValidationInvalidUuid,
+    #[serde(rename = "validation.invalid_number")]
+    ValidationInvalidNumber,
+    /// Too short or too long; `message` gives the bounds.
+    #[serde(rename = "validation.invalid_length")]
+    ValidationInvalidLength,
+    /// An update that would change nothing.
+    #[serde(rename = "validation.empty_update")]
+    ValidationEmptyUpdate,
+    /// Several fields failed at once; see `errors`.
+    #[serde(rename = "validation.failed")]
+    ValidationFailed,
+    /// The body is not JSON of the expected shape.
+    #[serde(rename = "request.invalid_body")]
+    RequestInvalidBody,
+    /// A path parameter could not be read.
+    #[serde(rename = "request.invalid_path")]
+    RequestInvalidPath,
+    /// A query parameter could not be read.
+    #[serde(rename = "request.invalid_query")]
+    RequestInvalidQuery,
+    /// No route matches the path.
+    #[serde(rename = "request.route_not_found")]
+    RequestRouteNotFound,
+    /// The route exists but not for this method; `Allow` lists the ones it takes.
+    #[serde(rename = "request.method_not_allowed")]
+    RequestMethodNotAllowed,
+    #[serde(rename = "request.precondition_failed")]
+    RequestPreconditionFailed,
+    #[serde(rename = "request.precondition_required")]
+    RequestPreconditionRequired,
+    #[serde(rename = "request.rate_limited")]
+    RequestRateLimited,
+    #[serde(rename = "internal")]
+    Internal,
+}
+
+/// One rejected input field of a `422` response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct FieldError {
+    pub field: String,
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+/// RFC 7807 problem details, sent as `application/problem+json` for every error.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorResponse {
+    /// Always `about:blank`; `code` identifies the problem.
+    #[serde(rename = "type")]
+    #[schema(example = "about:blank")]
+    pub problem_type: &'static str,
+    /// Reason phrase of `status`.
+    #[schema(example = "Not Found")]
+    pub title: &'static str,
+    #[schema(example = 404)]
+    pub status: u16,
+    /// Human-readable explanation; not meant to be parsed.
+    pub detail: String,
+    pub code: ErrorCode,
+    /// Every rejected field; only present on `422` responses.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+    /// Matches the `x-request-id` response header; quote it when reporting a problem.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+impl AppError {
+    /// A `422` for a single field.
+    pub fn invalid(field: impl Into<String>, code: ErrorCode, message: impl Into<String>) -> Self {
+        AppError::UnProcessableEntity(vec![FieldError {
+            field: field.into(),
+            code,
+            message: message.into(),
+        }])
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            AppError::NotFound(code, _)
+            | AppError::Unauthorized(code, _)
+            | AppError::Forbidden(code, _) => *code,
+            AppError::MethodNotAllowed(_) => ErrorCode::RequestMethodNotAllowed,
+            AppError::PreconditionFailed(_) => ErrorCode::RequestPreconditionFailed,
+            AppError::PreconditionRequired(_) => ErrorCode::RequestPreconditionRequired,
+            AppError::TooManyRequests(_) => ErrorCode::RequestRateLimited,
+            AppError::InternalServerError(_) => ErrorCode::Internal,
+            AppError::UnProcessableEntity(errors) => match errors.as_slice() {
+                [error] => error.code,
+                _ => ErrorCode::ValidationFailed,
+            },
+        }
+    }
+
+    fn status(&self) -> StatusCode {
         match self {
-            AppError::NotFound(message) => (
-                StatusCode::NOT_FOUND,
-                Json(json!({ "status": "error", "message": message })),
-            )
-                .into_response(),
-            AppError::InternalServerError(message) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "status": "error", "message": message })),
-            )
-                .into_response(),
-            AppError::UnProcessableEntity { field, message } => (
-                StatusCode::UNPROCESSABLE_ENTITY,
-                Json(json!({
-                    "status": "error",
-                    "field": field,
-                    "message": message
-                })),
-            )
-                .into_response(),
-            AppError::MissingEnvironmentVarible(message) | AppError::ParsingError(message) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "status": "error", "message": message })),
-            )
-                .into_response(),
+            AppError::NotFound(..) => StatusCode::NOT_FOUND,
+            AppError::Unauthorized(..) => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden(..) => StatusCode::FORBIDDEN,
+            AppError::MethodNotAllowed(_) => StatusCode::METHOD_NOT_ALLOWED,
+            AppError::PreconditionFailed(_) => StatusCode::PRECONDITION_FAILED,
+            AppError::PreconditionRequired(_) => StatusCode::PRECONDITION_REQUIRED,
+            AppError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+            AppError::InternalServerError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            AppError::UnProcessableEntity(_) => StatusCode::UNPROCESSABLE_ENTITY,
         }
     }
 }
+
+/// Collects every failed field check so one `422` can report them all.
+#[derive(Debug, Default)]
+pub struct FieldErrors(Vec<FieldError>);
+
+impl FieldErrors {
+    pub fn add(&mut self, field: impl Into<String>, code: ErrorCode, message: impl Into<String>) {
+        self.0.push(FieldError {
+            field: field.into(),
+            code,
+            message: message.into(),
+        });
+    }
+
+    /// Keeps the field errors of a failed check and returns `T::default()` in their place,
+    /// so later checks still run; `finish` rejects the request before the default is used.
+    ///
+    /// Any other error is passed through, since it is not about the input.
+    pub fn capture<T: Default>(&mut self, result: Result<T, AppError>) -> Result<T, AppError> {
+        match result {
+            Ok(value) => Ok(value),
+            Err(AppError::UnProcessableEntity(errors)) => {
+                self.0.extend(errors);
+                Ok(T::default())
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// `Ok` when every check passed.
+    pub fn finish(self) -> Result<(), AppError> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::UnProcessableEntity(self.0))
+        }
+    }
+}
+
+fn describe(errors: &[FieldError]) -> String {
+    errors
+        .iter()
+        .map(|error| format!("{} - {}", error.field, error.message))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        let code = self.code();
+        let (detail, errors) = match self {
+            AppError::NotFound(_, message)
+            | AppError::Unauthorized(_, message)
+            | AppError::Forbidden(_, message)
+            | AppError::MethodNotAllowed(message)
+            | AppError::PreconditionFailed(message)
+            | AppError::PreconditionRequired(message)
+            | AppError::TooManyRequests(message) => (message, Vec::new()),
+            AppError::InternalServerError(message) => {
+                // Runs inside the request span, so the log line carries the request id.
+                tracing::error!(error = %message, "request failed");
+                ("An unexpected error occurred".to_string(), Vec::new())
+            }
+            AppError::UnProcessableEntity(errors) => {
+                let detail = match errors.as_slice() {
+                    [error] => error.message.clone(),
+                    _ => format!("{} fields are invalid", errors.len()),
+                };
+                (detail, errors)
+            }
+        };
+
+        let body = ErrorResponse {
+            problem_type: "about:blank",
+            title: status.canonical_reason().unwrap_or_default(),
+            status: status.as_u16(),
+            detail,
+            code,
+            errors,
+            request_id: current_request_id(),
+        };
+        let mut response = (status, Json(body)).into_response();
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON));
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    async fn problem(error: AppError) -> (StatusCode, String, Value) {
+        let response = error.into_response();
+        let status = response.status();
+        let content_type = response.headers()[CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, content_type, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn errors_are_problem_details_with_a_code() {
+        let (status, content_type, body) = problem(AppError::NotFound(
+            ErrorCode::PostNotFound,
+            "Post not found".to_string(),
+        ))
+        .await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(content_type, PROBLEM_JSON);
+        assert_eq!(body["type"], "about:blank");
+        assert_eq!(body["title"], "Not Found");
+        assert_eq!(body["status"], 404);
+        assert_eq!(body["detail"], "Post not found");
+        assert_eq!(body["code"], "post.not_found");
+        assert!(body.get("errors").is_none());
+    }
+
+    #[tokio::test]
+    async fn internal_details_stay_out_of_the_body() {
+        let (status, _, body) = problem(AppError::InternalServerError(
+            "Failed to fetch post: pool timed out while waiting for an open connection".to_string(),
+        ))
+        .await;
+
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body["code"], "internal");
+        assert!(!body.to_string().contains("pool timed out"));
+    }
+
+    #[tokio::test]
+    async fn field_errors_are_reported_together() {
+        let mut errors = FieldErrors::default();
+        errors.add(
+            "name",
+            ErrorCode::ValidationRequired,
+            "Name cannot be empty",
+        );
+        let parsed = errors
+            .capture(Err::<Vec<String>, _>(AppError::invalid(
+                "email",
+                ErrorCode::ValidationInvalidEmail,
+                "Invalid email format",
+            )))
+            .unwrap();
+        assert!(parsed.is_empty());
+
+        let (status, _, body) = problem(errors.finish().unwrap_err()).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["code"], "validation.failed");
+        assert_eq!(body["errors"][0]["field"], "name");
+        assert_eq!(body["errors"][0]["code"], "validation.required");
+        assert_eq!(body["errors"][1]["field"], "email");
+        assert_eq!(body["errors"][1]["code"], "validation.invalid_email");
+    }
+
+    #[tokio::test]
+    async fn a_single_field_error_lends_its_code() {
+        let error = AppError::invalid("email", ErrorCode::AuthorEmailTaken, "Email already exists");
+        assert_eq!(error.code(), ErrorCode::AuthorEmailTaken);
+
+        let (_, _, body) = problem(error).await;
+        assert_eq!(body["detail"], "Email already exists");
+        assert_eq!(body["errors"][0]["field"], "email");
+    }
+
+    #[test]
+    fn captured_non_field_errors_pass_through() {
+        let mut errors = FieldErrors::default();
+        let result = errors.capture(Err::<(), _>(AppError::InternalServerError(
+            "boom".to_string(),
+        )));
+
+        assert!(matches!(result, Err(AppError::InternalServerError(_))));
+        assert!(errors.finish().is_ok());
+    }
+}
@@ -0,0 +1,183 @@
+use crate::error::AppError;
+use axum::http::{
+    HeaderMap, HeaderValue, StatusCode,
+    header::{ETAG, IF_MATCH, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+};
+use axum::response::{IntoResponse, IntoResponseParts, Response, ResponseParts};
+use chrono::{DateTime, Utc};
+use std::convert::Infallible;
+
+/// Strong entity tag for a stored row, built from its version and `updated_at`.
+///
+/// Added to responses as the `ETag` header. Clients send it back in `If-Match`
+/// to make a write conditional, or in `If-None-Match` to revalidate a cached GET.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ETag(String);
+
+impl ETag {
+    pub fn new(version: i64, updated_at: DateTime<Utc>) -> Self {
+        Self(format!("\"{version}-{}\"", updated_at.timestamp_micros()))
+    }
+
+    /// The tag of another representation of the same version, e.g. `"3-1700000000000000-html"`,
+    /// so a cached copy in one format is never revalidated as the other.
+    pub fn variant(self, name: &str) -> Self {
+        let tag = self.0.trim_end_matches('"');
+        Self(format!("{tag}-{name}\""))
+    }
+
+    /// Checks `If-Match` before a write: missing is a `428`, anything but a match is a `412`.
+    pub fn require_if_match(&self, headers: &HeaderMap) -> Result<(), AppError> {
+        let Some(value) = headers.get(IF_MATCH) else {
+            return Err(AppError::PreconditionRequired(
+                "Send If-Match with the ETag from your last read".to_string(),
+            ));
+        };
+
+        // Writes need the strong comparison, so weak tags never match.
+        let matches = value
+            .to_str()
+            .is_ok_and(|value| self.listed_in(value, |tag| tag == self.0));
+        if !matches {
+            return Err(AppError::PreconditionFailed(
+                "The resource has changed since it was read; fetch it again".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// `true` when `If-None-Match` already names this version, so a GET can answer `304`.
+    pub fn is_fresh(&self, headers: &HeaderMap) -> bool {
+        headers
+            .get(IF_NONE_MATCH)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| {
+                self.listed_in(value, |tag| tag.trim_start_matches("W/") == self.0)
+            })
+    }
+
+    /// The `304 Not Modified` answer to a conditional GET.
+    pub fn not_modified(self) -> Response {
+        (StatusCode::NOT_MODIFIED, self, ()).into_response()
+    }
+
+    fn listed_in(&self, header: &str, matches: impl Fn(&str) -> bool) -> bool {
+        header
+            .split(',')
+            .map(str::trim)
+            .any(|tag| tag == "*" || matches(tag))
+    }
+}
+
+impl IntoResponseParts for ETag {
+    type Error = Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        let value = HeaderValue::from_str(&self.0).expect("ETags are plain ASCII");
+        res.headers_mut().insert(ETAG, value);
+        Ok(res)
+    }
+}
+
+/// `Last-Modified` for responses built from many rows, such as feeds, where no single
+/// version identifies the content.
+///
+/// HTTP dates have whole-second precision, so the timestamp is truncated to match what
+/// clients send back in `If-Modified-Since`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LastModified(DateTime<Utc>);
+
+impl LastModified {
+    pub fn new(modified_at: DateTime<Utc>) -> Self {
+        let secs = modified_at.timestamp();
+        Self(DateTime::from_timestamp(secs, 0).unwrap_or(modified_at))
+    }
+
+    /// `true` when `If-Modified-Since` is no older than this, so a GET can answer `304`.
+    ///
+    /// Ignored when `If-None-Match` is present, as HTTP requires.
+    pub fn is_fresh(&self, headers: &HeaderMap) -> bool {
+        if headers.contains_key(IF_NONE_MATCH) {
+            return false;
+        }
+        headers
+            .get(IF_MODIFIED_SINCE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| DateTime::parse_from_rfc2822(value).ok())
+            .is_some_and(|since| self.0 <= since)
+    }
+
+    /// The `304 Not Modified` answer to a conditional GET.
+    pub fn not_modified(self) -> Response {
+        (StatusCode::NOT_MODIFIED, self, ()).into_response()
+    }
+
+    fn http_date(&self) -> String {
+        self.0.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+    }
+}
+
+impl IntoResponseParts for LastModified {
+    type Error = Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        let value = HeaderValue::from_str(&self.http_date()).expect("HTTP dates are plain ASCII");
+        res.headers_mut().insert(LAST_MODIFIED, value);
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(name: axum::http::HeaderName, value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn if_match_uses_strong_comparison() {
+        let etag = ETag::new(3, Utc::now());
+        let current = etag.0.clone();
+
+        assert!(etag.require_if_match(&headers(IF_MATCH, &current)).is_ok());
+        assert!(
+            etag.require_if_match(&headers(IF_MATCH, &format!("\"x\", {current}")))
+                .is_ok()
+        );
+        assert!(etag.require_if_match(&headers(IF_MATCH, "*")).is_ok());
+        assert!(matches!(
+            etag.require_if_match(&headers(IF_MATCH, &format!("W/{current}"))),
+            Err(AppError::PreconditionFailed(_))
+        ));
+        assert!(matches!(
+            etag.require_if_match(&HeaderMap::new()),
+            Err(AppError::PreconditionRequired(_))
+        ));
+    }
+
+    #[test]
+    fn if_none_match_uses_weak_comparison() {
+        let etag = ETag::new(1, Utc::now());
+
+        assert!(etag.is_fresh(&headers(IF_NONE_MATCH, &format!("W/{}", etag.0))));
+        assert!(!etag.is_fresh(&headers(IF_NONE_MATCH, "\"1-0\"")));
+        assert!(!etag.is_fresh(&HeaderMap::new()));
+    }
+
+    #[test]
+    fn if_modified_since_compares_whole_seconds() {
+        let modified = LastModified::new("2026-10-18T09:30:15.750Z".parse().unwrap());
+        assert_eq!(modified.http_date(), "Sun, 18 Oct 2026 09:30:15 GMT");
+
+        let since = |value| headers(IF_MODIFIED_SINCE, value);
+        assert!(modified.is_fresh(&since("Sun, 18 Oct 2026 09:30:15 GMT")));
+        assert!(modified.is_fresh(&since("Sun, 18 Oct 2026 10:00:00 GMT")));
+        assert!(!modified.is_fresh(&since("Sun, 18 Oct 2026 09:30:14 GMT")));
+        assert!(!modified.is_fresh(&since("yesterday")));
+        assert!(!modified.is_fresh(&HeaderMap::new()));
+    }
+}
@@ -0,0 +1,119 @@
+//! `Path` and `Query` that reject with `AppError`, so a malformed URL is answered with the
+//! same problem details as a malformed body (see `validation::ValidJson`).
+
+use crate::error::{AppError, ErrorCode};
+use axum::extract::{FromRequestParts, path::ErrorKind, rejection::PathRejection};
+use axum::http::request::Parts;
+use axum_extra::extract::QueryRejection;
+use serde::de::DeserializeOwned;
+use std::error::Error;
+
+/// `axum::extract::Path` with a `422` on the offending parameter.
+#[derive(Debug)]
+pub struct Path<T>(pub T);
+
+impl<S, T> FromRequestParts<S> for Path<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned + Send,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        match axum::extract::Path::<T>::from_request_parts(parts, state).await {
+            Ok(axum::extract::Path(value)) => Ok(Self(value)),
+            Err(rejection) => Err(path_rejected(rejection)),
+        }
+    }
+}
+
+fn path_rejected(rejection: PathRejection) -> AppError {
+    // A route whose parameters do not fit its handler is a bug, not bad input.
+    if rejection.status().is_server_error() {
+        return AppError::InternalServerError(rejection.body_text());
+    }
+    let PathRejection::FailedToDeserializePathParams(error) = &rejection else {
+        return AppError::InternalServerError(rejection.body_text());
+    };
+    let field = match error.kind() {
+        ErrorKind::ParseErrorAtKey { key, .. }
+        | ErrorKind::InvalidUtf8InPathParam { key }
+        | ErrorKind::DeserializeError { key, .. } => key.as_str(),
+        _ => "path",
+    };
+    AppError::invalid(
+        field,
+        ErrorCode::RequestInvalidPath,
+        error.kind().to_string(),
+    )
+}
+
+/// `axum_extra::extract::Query`, which reads repeated keys into a `Vec`, with a `422` on the
+/// offending parameter.
+#[derive(Debug)]
+pub struct Query<T>(pub T);
+
+impl<S, T> FromRequestParts<S> for Query<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        match axum_extra::extract::Query::<T>::from_request_parts(parts, state).await {
+            Ok(axum_extra::extract::Query(value)) => Ok(Self(value)),
+            Err(rejection) => Err(query_rejected(rejection)),
+        }
+    }
+}
+
+fn query_rejected(rejection: QueryRejection) -> AppError {
+    match rejected_field::<serde::de::value::Error>(&rejection) {
+        Some((field, error)) => {
+            AppError::invalid(field, ErrorCode::RequestInvalidQuery, error.to_string())
+        }
+        None => AppError::invalid(
+            "query",
+            ErrorCode::RequestInvalidQuery,
+            rejection.body_text(),
+        ),
+    }
+}
+
+/// The field that failed to deserialize, and why, for rejections of extractors that read
+/// through `serde_path_to_error` (axum's `Json` and axum-extra's `Query`).
+///
+/// `None` when the input was unreadable as a whole or the problem is not tied to one field,
+/// such as a missing one.
+pub fn rejected_field<E: Error + 'static>(rejection: &dyn Error) -> Option<(String, &E)> {
+    let error = rejection
+        .source()?
+        .source()?
+        .downcast_ref::<serde_path_to_error::Error<E>>()?;
+    let field = error.path().to_string();
+    (field != ".").then(|| (field, error.inner()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::state::AppState;
+    use crate::app::test_support::send;
+    use axum::http::{Method, StatusCode};
+
+    #[tokio::test]
+    async fn malformed_urls_are_rejected_on_the_offending_parameter() {
+        let state = AppState::for_tests_in_memory();
+
+        for (uri, field) in [("/posts?limit=abc", "limit"), ("/posts/%FF", "id")] {
+            let (status, error) = send(&state, Method::GET, uri, None, None).await;
+            assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{uri}");
+            assert_eq!(error["errors"][0]["field"], field, "{uri}");
+        }
+
+        let (_, error) = send(&state, Method::GET, "/authors?offset=-", None, None).await;
+        assert_eq!(error["code"], "request.invalid_query");
+        let (_, error) = send(&state, Method::GET, "/tags/%C0", None, None).await;
+        assert_eq!(error["code"], "request.invalid_path");
+    }
+}
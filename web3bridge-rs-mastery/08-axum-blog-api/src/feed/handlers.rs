@@ -0,0 +1,364 @@
+use crate::app::state::SharedState;
+use crate::error::{AppError, ErrorResponse};
+use crate::etag::LastModified;
+use crate::extract::Path;
+use crate::feed::render::{self, Feed, FeedEntry};
+use crate::post::models::{Post, PostCriteria};
+use crate::validation::parse_uuid;
+use axum::{
+    extract::State,
+    http::{HeaderMap, header::CONTENT_TYPE},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+const ATOM: &str = "application/atom+xml; charset=utf-8";
+const RSS: &str = "application/rss+xml; charset=utf-8";
+const XML: &str = "application/xml; charset=utf-8";
+
+/// Entries per feed, newest first.
+const FEED_SIZE: i64 = 20;
+const SITEMAP_PAGE_SIZE: i64 = 500;
+/// The most URLs one sitemap file may list.
+const MAX_SITEMAP_URLS: usize = 50_000;
+
+/// The newest published posts as an Atom feed.
+#[utoipa::path(
+    get,
+    path = "/feed.atom",
+    tag = "feeds",
+    params(("If-Modified-Since" = Option<String>, Header, description = "`Last-Modified` of a cached copy")),
+    responses(
+        (status = 200, description = "Atom 1.0 feed", body = String, content_type = "application/atom+xml", headers(("Last-Modified" = String))),
+        (status = 304, description = "No post has changed since `If-Modified-Since`"),
+    )
+)]
+pub async fn site_atom(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let modified = state.posts.last_modified(None).await?;
+    if let Some(unchanged) = not_modified(&headers, modified) {
+        return Ok(unchanged);
+    }
+    let feed = site_feed(&state, "/feed.atom").await?;
+    Ok(respond(modified, ATOM, feed.to_atom()))
+}
+
+/// The newest published posts as an RSS 2.0 feed.
+#[utoipa::path(
+    get,
+    path = "/feed.rss",
+    tag = "feeds",
+    params(("If-Modified-Since" = Option<String>, Header, description = "`Last-Modified` of a cached copy")),
+    responses(
+        (status = 200, description = "RSS 2.0 feed", body = String, content_type = "application/rss+xml", headers(("Last-Modified" = String))),
+        (status = 304, description = "No post has changed since `If-Modified-Since`"),
+    )
+)]
+pub async fn site_rss(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let modified = state.posts.last_modified(None).await?;
+    if let Some(unchanged) = not_modified(&headers, modified) {
+        return Ok(unchanged);
+    }
+    let feed = site_feed(&state, "/feed.rss").await?;
+    Ok(respond(modified, RSS, feed.to_rss()))
+}
+
+/// One author's newest published posts as an Atom feed.
+#[utoipa::path(
+    get,
+    path = "/authors/{id}/feed.atom",
+    tag = "feeds",
+    params(
+        ("id" = String, Path, description = "Author UUID"),
+        ("If-Modified-Since" = Option<String>, Header, description = "`Last-Modified` of a cached copy"),
+    ),
+    responses(
+        (status = 200, description = "Atom 1.0 feed", body = String, content_type = "application/atom+xml", headers(("Last-Modified" = String))),
+        (status = 304, description = "No post has changed since `If-Modified-Since`"),
+        (status = 404, description = "Author not found", body = ErrorResponse),
+        (status = 422, description = "Invalid input", body = ErrorResponse),
+    )
+)]
+pub async fn author_atom(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<Response, AppError> {
+    let author_id = parse_uuid("id", &id)?;
+    let author = state.authors.find_by_id(author_id).await?;
+
+    let modified = state.posts.last_modified(Some(author_id)).await?;
+    if let Some(unchanged) = not_modified(&headers, modified) {
+        return Ok(unchanged);
+    }
+    let posts = recent_posts(&state, Some(author_id)).await?;
+    let feed = Feed {
+        site: &state.site,
+        title: format!("{} - {}", author.name, state.site.title),
+        path: format!("/authors/{author_id}/feed.atom"),
+        entries: posts
+            .into_iter()
+            .map(|post| FeedEntry {
+                post,
+                author_name: author.name.clone(),
+            })
+            .collect(),
+        author_name: Some(author.name),
+    };
+    Ok(respond(modified, ATOM, feed.to_atom()))
+}
+
+/// Every published post, for search engines.
+#[utoipa::path(
+    get,
+    path = "/sitemap.xml",
+    tag = "feeds",
+    params(("If-Modified-Since" = Option<String>, Header, description = "`Last-Modified` of a cached copy")),
+    responses(
+        (status = 200, description = "Sitemap 0.9 document", body = String, content_type = "application/xml", headers(("Last-Modified" = String))),
+        (status = 304, description = "No post has changed since `If-Modified-Since`"),
+    )
+)]
+pub async fn sitemap(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let modified = state.posts.last_modified(None).await?;
+    if let Some(unchanged) = not_modified(&headers, modified) {
+        return Ok(unchanged);
+    }
+    let criteria = PostCriteria::default();
+    let mut posts = Vec::new();
+    let mut cursor = None;
+    loop {
+        let page = state
+            .posts
+            .list(&criteria, cursor, SITEMAP_PAGE_SIZE, 0)
+            .await?;
+        posts.extend(page.items);
+        cursor = page.next_cursor;
+        if !page.has_more || cursor.is_none() || posts.len() >= MAX_SITEMAP_URLS {
+            break;
+        }
+    }
+    posts.truncate(MAX_SITEMAP_URLS);
+
+    Ok(respond(modified, XML, render::sitemap(&state.site, &posts)))
+}
+
+async fn site_feed<'a>(state: &'a SharedState, path: &str) -> Result<Feed<'a>, AppError> {
+    let posts = recent_posts(state, None).await?;
+
+    // A feed holds a handful of authors at most, so one lookup each is fine.
+    let mut names: HashMap<Uuid, String> = HashMap::new();
+    let mut entries = Vec::with_capacity(posts.len());
+    for post in posts {
+        let author_name = match names.get(&post.author_id) {
+            Some(name) => name.clone(),
+            None => {
+                let name = state.authors.find_by_id(post.author_id).await?.name;
+                names.insert(post.author_id, name.clone());
+                name
+            }
+        };
+        entries.push(FeedEntry { post, author_name });
+    }
+
+    Ok(Feed {
+        site: &state.site,
+        title: state.site.title.clone(),
+        path: path.to_string(),
+        author_name: None,
+        entries,
+    })
+}
+
+// Published, not deleted, newest first: the same rows an anonymous `GET /posts` lists.
+async fn recent_posts(state: &SharedState, author_id: Option<Uuid>) -> Result<Vec<Post>, AppError> {
+    let criteria = PostCriteria {
+        author_id,
+        ..PostCriteria::default()
+    };
+    let page = state.posts.list(&criteria, None, FEED_SIZE, 0).await?;
+    Ok(page.items)
+}
+
+// Handlers read `modified` first and list second, so a write in between makes the next
+// request send the content again rather than answer `304` for a copy that missed it.
+// Checking it before listing means a revalidation costs a single query.
+fn not_modified(headers: &HeaderMap, modified: Option<DateTime<Utc>>) -> Option<Response> {
+    modified
+        .map(LastModified::new)
+        .filter(|last_modified| last_modified.is_fresh(headers))
+        .map(LastModified::not_modified)
+}
+
+fn respond(modified: Option<DateTime<Utc>>, content_type: &'static str, body: String) -> Response {
+    let last_modified = modified.map(LastModified::new);
+    ([(CONTENT_TYPE, content_type)], last_modified, body).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::router::create_router;
+    use crate::app::state::{AppState, SharedState};
+    use crate::app::test_support::{current_etag, send, send_with_headers, signed_in_author};
+    use axum::{
+        body::Body,
+        http::{
+            HeaderMap, Method, Request, StatusCode,
+            header::{CONTENT_TYPE, IF_MATCH, IF_MODIFIED_SINCE, LAST_MODIFIED},
+        },
+    };
+    use chrono::{DateTime, Utc};
+    use serde_json::json;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    async fn get(
+        state: &SharedState,
+        uri: &str,
+        since: Option<&str>,
+    ) -> (StatusCode, HeaderMap, String) {
+        let mut request = Request::get(uri);
+        if let Some(since) = since {
+            request = request.header(IF_MODIFIED_SINCE, since);
+        }
+        let response = create_router(state.clone())
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let headers = response.headers().clone();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, headers, String::from_utf8(bytes.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn feeds_list_published_posts_and_answer_conditional_gets() {
+        let state = AppState::for_tests_in_memory();
+        let (author_id, token) = signed_in_author(&state, "writer@example.com").await;
+        for body in [
+            json!({ "title": "Public post", "content": "Hello *feed*", "tags": ["rust"] }),
+            json!({ "title": "Secret draft", "content": "x", "status": "draft" }),
+            json!({ "title": "Paid post", "content": "members only", "is_premium": true }),
+        ] {
+            let (status, _) = send(&state, Method::POST, "/posts", Some(&token), Some(body)).await;
+            assert_eq!(status, StatusCode::CREATED);
+        }
+
+        let (status, headers, atom) = get(&state, "/feed.atom", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(
+            headers[CONTENT_TYPE]
+                .to_str()
+                .unwrap()
+                .starts_with("application/atom+xml")
+        );
+        assert!(atom.contains("<title>Public post</title>"), "{atom}");
+        assert!(atom.contains("&lt;em&gt;feed&lt;/em&gt;"), "{atom}");
+        assert!(atom.contains("<name>Test Author</name>"), "{atom}");
+        assert!(
+            atom.contains("Paid post") && !atom.contains("members only"),
+            "{atom}"
+        );
+        assert!(!atom.contains("Secret draft"), "{atom}");
+
+        let last_modified = headers[LAST_MODIFIED].to_str().unwrap().to_string();
+        let (status, _, body) = get(&state, "/feed.atom", Some(&last_modified)).await;
+        assert_eq!(status, StatusCode::NOT_MODIFIED);
+        assert!(body.is_empty());
+        let (status, _, _) = get(&state, "/feed.atom", Some("Thu, 01 Jan 1970 00:00:00 GMT")).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (_, headers, rss) = get(&state, "/feed.rss", None).await;
+        assert!(
+            headers[CONTENT_TYPE]
+                .to_str()
+                .unwrap()
+                .starts_with("application/rss+xml")
+        );
+        assert!(
+            rss.contains("<rss") && rss.contains("<category>rust</category>"),
+            "{rss}"
+        );
+
+        let (_, _, sitemap) = get(&state, "/sitemap.xml", None).await;
+        assert_eq!(sitemap.matches("<url>").count(), 2, "{sitemap}");
+        assert!(
+            sitemap.contains("<loc>http://127.0.0.1:3310/posts/"),
+            "{sitemap}"
+        );
+
+        let (status, _, author_feed) =
+            get(&state, &format!("/authors/{author_id}/feed.atom"), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(
+            author_feed.contains("<title>Test Author - Axum Blog</title>"),
+            "{author_feed}"
+        );
+
+        let (status, _, _) = get(
+            &state,
+            &format!("/authors/{}/feed.atom", Uuid::new_v4()),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn removing_a_post_moves_last_modified() {
+        let state = AppState::for_tests_in_memory();
+        let (author_id, token) = signed_in_author(&state, "writer@example.com").await;
+        let mut created = Vec::new();
+        for title in ["Older", "Newer"] {
+            let body = json!({ "title": title, "content": "x" });
+            let (_, post) = send(&state, Method::POST, "/posts", Some(&token), Some(body)).await;
+            created.push(post);
+        }
+        let newest: DateTime<Utc> =
+            serde_json::from_value(created[1]["updated_at"].clone()).unwrap();
+
+        let uri = format!("/posts/{}", created[1]["id"].as_str().unwrap());
+        let etag = current_etag(&state, &uri, Some(&token)).await;
+        let (status, _, _) = send_with_headers(
+            &state,
+            Method::DELETE,
+            &uri,
+            Some(&token),
+            &[(IF_MATCH, etag.as_str())],
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        // The remaining post is older, yet the feed changed after the newest one listed.
+        for author in [None, Some(author_id)] {
+            let modified = state.posts.last_modified(author).await.unwrap().unwrap();
+            assert!(modified > newest);
+        }
+        let (_, headers, atom) = get(&state, "/feed.atom", None).await;
+        assert!(!atom.contains("Newer"), "{atom}");
+        assert!(headers.contains_key(LAST_MODIFIED));
+    }
+
+    #[tokio::test]
+    async fn an_empty_feed_has_no_last_modified() {
+        let state = AppState::for_tests_in_memory();
+
+        let (status, headers, atom) = get(&state, "/feed.atom", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(!headers.contains_key(LAST_MODIFIED));
+        assert!(!atom.contains("<entry>"), "{atom}");
+    }
+}
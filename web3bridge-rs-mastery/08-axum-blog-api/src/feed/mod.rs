@@ -0,0 +1,25 @@
+mod handlers;
+mod render;
+
+use crate::app::state::SharedState;
+use axum::{Router, routing::get};
+use utoipa::OpenApi;
+
+/// OpenAPI paths for the feeds and the sitemap.
+#[derive(OpenApi)]
+#[openapi(paths(
+    handlers::site_atom,
+    handlers::site_rss,
+    handlers::author_atom,
+    handlers::sitemap,
+))]
+pub struct FeedApi;
+
+/// Atom and RSS feeds of published posts, plus `/sitemap.xml`. Merged at the root.
+pub fn feed_routes() -> Router<SharedState> {
+    Router::new()
+        .route("/feed.atom", get(handlers::site_atom))
+        .route("/feed.rss", get(handlers::site_rss))
+        .route("/authors/{id}/feed.atom", get(handlers::author_atom))
+        .route("/sitemap.xml", get(handlers::sitemap))
+}
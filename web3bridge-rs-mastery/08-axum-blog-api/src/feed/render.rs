@@ -0,0 +1,174 @@
+use crate::config::SiteConfig;
+use crate::post::models::Post;
+use atom_syndication as atom;
+use chrono::{DateTime, SecondsFormat, Utc};
+use quick_xml::escape::escape;
+use std::fmt::Write;
+
+/// Shown instead of the body of a premium post, which feeds never include.
+const PREMIUM_SUMMARY: &str = "This post is for subscribers.";
+
+/// A post with the name of its author, as it appears in a feed.
+pub struct FeedEntry {
+    pub post: Post,
+    pub author_name: String,
+}
+
+/// Everything a feed shows; rendered as Atom or RSS.
+pub struct Feed<'a> {
+    pub site: &'a SiteConfig,
+    pub title: String,
+    /// Path of the feed itself, e.g. `/feed.atom`.
+    pub path: String,
+    /// Set on per-author feeds, whose entries then need no author of their own.
+    pub author_name: Option<String>,
+    pub entries: Vec<FeedEntry>,
+}
+
+impl Feed<'_> {
+    /// Newest `updated_at` among the entries; `None` for an empty feed.
+    pub fn updated(&self) -> Option<DateTime<Utc>> {
+        self.entries.iter().map(|entry| entry.post.updated_at).max()
+    }
+
+    pub fn to_atom(&self) -> String {
+        let person = |name: &str| atom::Person {
+            name: name.to_string(),
+            ..atom::Person::default()
+        };
+        let link = |href: String, rel: &str| atom::Link {
+            href,
+            rel: rel.to_string(),
+            ..atom::Link::default()
+        };
+
+        let entries = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let post = &entry.post;
+                let (summary, content) = if post.is_premium {
+                    (Some(atom::Text::plain(PREMIUM_SUMMARY)), None)
+                } else {
+                    let content = atom::Content {
+                        value: Some(post.html().into_owned()),
+                        content_type: Some("html".to_string()),
+                        ..atom::Content::default()
+                    };
+                    (None, Some(content))
+                };
+                atom::Entry {
+                    id: format!("urn:uuid:{}", post.id),
+                    title: atom::Text::plain(post.title.as_str()),
+                    updated: post.updated_at.fixed_offset(),
+                    published: post.published_at.map(|at| at.fixed_offset()),
+                    authors: match &self.author_name {
+                        Some(_) => Vec::new(),
+                        None => vec![person(&entry.author_name)],
+                    },
+                    links: vec![link(post_url(self.site, post), "alternate")],
+                    categories: post
+                        .tags
+                        .iter()
+                        .map(|tag| atom::Category {
+                            term: tag.clone(),
+                            ..atom::Category::default()
+                        })
+                        .collect(),
+                    summary,
+                    content,
+                    ..atom::Entry::default()
+                }
+            })
+            .collect();
+
+        atom::Feed {
+            id: self.url(),
+            title: atom::Text::plain(self.title.as_str()),
+            updated: self.updated().unwrap_or_else(Utc::now).fixed_offset(),
+            authors: self
+                .author_name
+                .as_deref()
+                .map(person)
+                .into_iter()
+                .collect(),
+            links: vec![
+                link(self.url(), "self"),
+                link(self.site.url.clone(), "alternate"),
+            ],
+            entries,
+            ..atom::Feed::default()
+        }
+        .to_string()
+    }
+
+    pub fn to_rss(&self) -> String {
+        let items = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let post = &entry.post;
+                let description = if post.is_premium {
+                    PREMIUM_SUMMARY.to_string()
+                } else {
+                    post.html().into_owned()
+                };
+                rss::Item {
+                    title: Some(post.title.clone()),
+                    link: Some(post_url(self.site, post)),
+                    description: Some(description),
+                    guid: Some(rss::Guid {
+                        value: format!("urn:uuid:{}", post.id),
+                        permalink: false,
+                    }),
+                    pub_date: Some(post.published_at.unwrap_or(post.created_at).to_rfc2822()),
+                    categories: post
+                        .tags
+                        .iter()
+                        .map(|tag| rss::Category {
+                            name: tag.clone(),
+                            domain: None,
+                        })
+                        .collect(),
+                    ..rss::Item::default()
+                }
+            })
+            .collect();
+
+        rss::Channel {
+            title: self.title.clone(),
+            link: self.site.url.clone(),
+            description: format!("Latest posts from {}", self.title),
+            last_build_date: self.updated().map(|at| at.to_rfc2822()),
+            items,
+            ..rss::Channel::default()
+        }
+        .to_string()
+    }
+
+    fn url(&self) -> String {
+        format!("{}{}", self.site.url, self.path)
+    }
+}
+
+/// A sitemap listing every post in `posts`, with its last change as `lastmod`.
+pub fn sitemap(site: &SiteConfig, posts: &[Post]) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+    for post in posts {
+        // Writing to a String cannot fail.
+        let _ = writeln!(
+            xml,
+            "  <url><loc>{}</loc><lastmod>{}</lastmod></url>",
+            escape(post_url(site, post)),
+            post.updated_at.to_rfc3339_opts(SecondsFormat::Secs, true)
+        );
+    }
+    xml.push_str("</urlset>\n");
+    xml
+}
+
+fn post_url(site: &SiteConfig, post: &Post) -> String {
+    format!("{}/posts/{}", site.url, post.id)
+}
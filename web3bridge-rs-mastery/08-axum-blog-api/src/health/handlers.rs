@@ -0,0 +1,131 @@
+use crate::app::state::SharedState;
+use crate::health::models::{CheckStatus, Readiness, ReadinessResponse};
+use crate::health::queries;
+use crate::response::MessageResponse;
+use axum::{Json, extract::State, http::StatusCode};
+use std::time::Duration;
+
+/// Upper bound on the database checks, so a hung connection fails the probe instead of stalling it.
+const READINESS_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[utoipa::path(
+    get,
+    path = "/health/live",
+    tag = "meta",
+    responses((status = 200, description = "The process is up", body = MessageResponse))
+)]
+pub async fn live() -> (StatusCode, Json<MessageResponse>) {
+    (
+        StatusCode::OK,
+        Json(MessageResponse::ok("Server is running")),
+    )
+}
+
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    tag = "meta",
+    responses(
+        (status = 200, description = "Database reachable and fully migrated", body = ReadinessResponse),
+        (status = 503, description = "Database unreachable or migrations pending", body = ReadinessResponse),
+    )
+)]
+pub async fn ready(State(state): State<SharedState>) -> (StatusCode, Json<ReadinessResponse>) {
+    let checks = async {
+        queries::ping(&state).await?;
+        queries::pending_migrations(&state).await
+    };
+
+    let response = match tokio::time::timeout(READINESS_TIMEOUT, checks).await {
+        Ok(Ok(pending)) => ReadinessResponse {
+            status: if pending.is_empty() {
+                Readiness::Ready
+            } else {
+                Readiness::Unavailable
+            },
+            database: CheckStatus::Ok,
+            database_error: None,
+            pending_migrations: Some(pending),
+        },
+        Ok(Err(err)) => unavailable(err.to_string()),
+        Err(_) => unavailable(format!(
+            "Database check timed out after {}s",
+            READINESS_TIMEOUT.as_secs()
+        )),
+    };
+
+    let status = match response.status {
+        Readiness::Ready => StatusCode::OK,
+        Readiness::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+    };
+    if status != StatusCode::OK {
+        tracing::warn!(?response, "readiness check failed");
+    }
+
+    (status, Json(response))
+}
+
+fn unavailable(error: String) -> ReadinessResponse {
+    ReadinessResponse {
+        status: Readiness::Unavailable,
+        database: CheckStatus::Error,
+        database_error: Some(error),
+        pending_migrations: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::router::create_router;
+    use crate::app::state::AppState;
+    use crate::db::DbPool;
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    async fn probe(state: crate::app::state::SharedState, uri: &str) -> (StatusCode, Value) {
+        let request = Request::get(uri).body(Body::empty()).unwrap();
+        let response = create_router(state).oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn liveness_does_not_need_the_database() {
+        let (status, _) = probe(AppState::for_tests(), "/health/live").await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn readiness_fails_when_the_database_is_down() {
+        let (status, body) = probe(AppState::for_tests(), "/health/ready").await;
+
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["database"], "error");
+        assert!(body["pending_migrations"].is_null());
+    }
+
+    #[sqlx::test(migrator = "crate::db::MIGRATOR")]
+    #[cfg_attr(feature = "postgres", ignore = "needs a Postgres DATABASE_URL")]
+    async fn readiness_passes_on_a_migrated_database(pool: DbPool) {
+        let (status, body) =
+            probe(AppState::for_tests_with_pool(pool.clone()), "/health/ready").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["pending_migrations"], Value::Array(Vec::new()));
+
+        sqlx::query("DELETE FROM _sqlx_migrations WHERE version = (SELECT MAX(version) FROM _sqlx_migrations)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let (status, body) = probe(AppState::for_tests_with_pool(pool), "/health/ready").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["status"], "unavailable");
+        assert_eq!(body["pending_migrations"].as_array().unwrap().len(), 1);
+    }
+}
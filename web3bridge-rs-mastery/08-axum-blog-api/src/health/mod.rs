@@ -0,0 +1,19 @@
+mod handlers;
+pub mod models;
+mod queries;
+
+use crate::app::state::SharedState;
+use axum::{Router, routing::get};
+use utoipa::OpenApi;
+
+/// OpenAPI paths for the routes under `/health`.
+#[derive(OpenApi)]
+#[openapi(paths(handlers::live, handlers::ready))]
+pub struct HealthApi;
+
+/// Probes for orchestrators: `live` only proves the process answers, `ready` also checks the database.
+pub fn health_routes() -> Router<SharedState> {
+    Router::new()
+        .route("/live", get(handlers::live))
+        .route("/ready", get(handlers::ready))
+}
@@ -0,0 +1,32 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Readiness {
+    Ready,
+    Unavailable,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Error,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PendingMigration {
+    pub version: i64,
+    pub description: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReadinessResponse {
+    pub status: Readiness,
+    pub database: CheckStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub database_error: Option<String>,
+    /// Migrations shipped with this build but not yet applied; `null` when the database is unreachable.
+    pub pending_migrations: Option<Vec<PendingMigration>>,
+}
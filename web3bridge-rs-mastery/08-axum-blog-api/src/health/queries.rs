@@ -0,0 +1,44 @@
+use crate::app::state::SharedState;
+use crate::db::{self, MIGRATOR};
+use crate::error::AppError;
+use crate::health::models::PendingMigration;
+
+pub async fn ping(state: &SharedState) -> Result<(), AppError> {
+    sqlx::query("SELECT 1")
+        .execute(&state.db_pool)
+        .await
+        .map_err(|err| AppError::InternalServerError(format!("Database unavailable: {err}")))?;
+
+    Ok(())
+}
+
+/// Embedded migrations that have no successful row in `_sqlx_migrations`.
+pub async fn pending_migrations(state: &SharedState) -> Result<Vec<PendingMigration>, AppError> {
+    // A database that was never migrated has no bookkeeping table yet.
+    let applied: Vec<i64> =
+        sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success")
+            .fetch_all(&state.db_pool)
+            .await
+            .or_else(|err| {
+                if db::is_undefined_table(&err) {
+                    Ok(Vec::new())
+                } else {
+                    Err(err)
+                }
+            })
+            .map_err(|err| {
+                AppError::InternalServerError(format!("Failed to read migrations: {err}"))
+            })?;
+
+    let pending = MIGRATOR
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .filter(|migration| !applied.contains(&migration.version))
+        .map(|migration| PendingMigration {
+            version: migration.version,
+            description: migration.description.to_string(),
+        })
+        .collect();
+
+    Ok(pending)
+}
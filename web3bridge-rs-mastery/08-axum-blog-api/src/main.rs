@@ -1,16 +1,38 @@
 mod app;
 
+mod auth;
 mod author;
+mod bulk;
+mod cache;
+mod comment;
 mod config;
+mod db;
 mod error;
+mod etag;
+mod extract;
+mod feed;
+mod health;
+mod markdown;
+mod outbox;
+mod pagination;
+mod payments;
 mod post;
+mod rate_limit;
+mod response;
+mod revision;
+mod tag;
+mod telemetry;
+mod validation;
 
 
 use app::create_app;
+use config::AppConfig;
 use dotenvy::dotenv;
 
 #[tokio::main]
 async fn main() {
     dotenv().ok();
-    create_app().await;
+    let app_config = AppConfig::load().unwrap_or_else(|err| panic!("{err}"));
+    telemetry::init_tracing(&app_config.log);
+    create_app(app_config).await;
 }
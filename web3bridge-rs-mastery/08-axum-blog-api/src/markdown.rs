@@ -0,0 +1,68 @@
+use pulldown_cmark::{Event, Options, Parser, html};
+
+/// Reading speed behind `reading_time_minutes`.
+const WORDS_PER_MINUTE: usize = 200;
+
+fn parser(markdown: &str) -> Parser<'_> {
+    Parser::new_ext(
+        markdown,
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH,
+    )
+}
+
+/// Renders post Markdown to HTML that is safe to embed in a page.
+///
+/// Raw HTML in the source survives only where ammonia's allow-list permits it, so
+/// scripts, styles, `on*` attributes and `javascript:` links are dropped, and links
+/// get `rel="noopener noreferrer"`.
+pub fn render(markdown: &str) -> String {
+    let mut unsafe_html = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(&mut unsafe_html, parser(markdown));
+    ammonia::clean(&unsafe_html)
+}
+
+/// Words of visible text, so Markdown syntax and embedded HTML are not counted.
+pub fn word_count(markdown: &str) -> usize {
+    parser(markdown)
+        .map(|event| match event {
+            Event::Text(text) | Event::Code(text) => text.split_whitespace().count(),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Whole minutes, rounded up; zero only for an empty body.
+pub fn reading_time_minutes(word_count: usize) -> usize {
+    word_count.div_ceil(WORDS_PER_MINUTE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_markdown_and_strips_active_content() {
+        let html = render(
+            "# Title\n\n**bold** [link](https://example.com) [bad](javascript:alert(1))\n\n\
+             <script>alert(1)</script><img src=\"x.png\" onerror=\"alert(1)\">",
+        );
+
+        assert!(html.contains("<h1>Title</h1>"), "{html}");
+        assert!(html.contains("<strong>bold</strong>"), "{html}");
+        assert!(html.contains(r#"href="https://example.com""#), "{html}");
+        assert!(html.contains(r#"<img src="x.png">"#), "{html}");
+        assert!(!html.contains("script"), "{html}");
+        assert!(!html.contains("onerror"), "{html}");
+        assert!(!html.contains("javascript"), "{html}");
+    }
+
+    #[test]
+    fn counts_words_but_not_syntax() {
+        let words = word_count("## Two words\n\n- *one* `two` [three](https://x.y)\n\n<br>");
+        assert_eq!(words, 5);
+
+        assert_eq!(reading_time_minutes(0), 0);
+        assert_eq!(reading_time_minutes(1), 1);
+        assert_eq!(reading_time_minutes(401), 3);
+    }
+}
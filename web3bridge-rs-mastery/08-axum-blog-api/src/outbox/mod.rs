@@ -0,0 +1,12 @@
+pub mod models;
+mod queries;
+mod worker;
+
+pub use queries::enqueue;
+pub use worker::spawn_outbox_worker;
+
+pub const POST_CREATED: &str = "post.created";
+pub const POST_UPDATED: &str = "post.updated";
+/// A scheduled post went live; edits that publish a post directly are `post.updated`.
+pub const POST_PUBLISHED: &str = "post.published";
+pub const POST_DELETED: &str = "post.deleted";
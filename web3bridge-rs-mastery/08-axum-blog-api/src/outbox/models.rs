@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum OutboxStatus {
+    Pending,
+    Done,
+    /// Gave up after too many failed attempts; kept for inspection and manual replay.
+    Dead,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct OutboxEvent {
+    pub id: Uuid,
+    pub event_type: String,
+    pub aggregate_id: Uuid,
+    pub payload: Value,
+    /// Includes the attempt currently in flight.
+    pub attempts: i32,
+}
@@ -0,0 +1,98 @@
+use crate::app::state::SharedState;
+use crate::db::{DbConnection, NOW};
+use crate::error::AppError;
+use crate::outbox::models::{OutboxEvent, OutboxStatus};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Queues an event on the caller's connection, so it commits or rolls back with the change itself.
+pub async fn enqueue(
+    conn: &mut DbConnection,
+    event_type: &str,
+    aggregate_id: Uuid,
+    payload: Value,
+) -> Result<(), AppError> {
+    sqlx::query(
+        "INSERT INTO outbox (id, event_type, aggregate_id, payload) VALUES ($1, $2, $3, $4)",
+    )
+    .bind(Uuid::new_v4())
+    .bind(event_type)
+    .bind(aggregate_id)
+    .bind(payload)
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| AppError::InternalServerError(format!("Failed to queue event: {err}")))?;
+
+    Ok(())
+}
+
+/// Takes up to `limit` due events, oldest first, and counts the attempt.
+///
+/// Claimed rows are pushed `lease` into the future, so an event whose worker dies
+/// mid-delivery is picked up again once the lease runs out. On Postgres `SKIP LOCKED`
+/// lets several workers drain the table without handing out the same row twice.
+pub async fn claim_due(
+    state: &SharedState,
+    limit: i64,
+    lease: Duration,
+) -> Result<Vec<OutboxEvent>, AppError> {
+    let events = sqlx::query_as::<_, OutboxEvent>(CLAIM_DUE)
+        .bind(limit)
+        .bind(lease.as_secs_f64())
+        .fetch_all(&state.db_pool)
+        .await
+        .map_err(|err| {
+            AppError::InternalServerError(format!("Failed to claim outbox events: {err}"))
+        })?;
+
+    Ok(events)
+}
+
+#[cfg(feature = "postgres")]
+const CLAIM_DUE: &str = "UPDATE outbox SET attempts = attempts + 1, next_attempt_at = NOW() + make_interval(secs => $2) WHERE id IN (SELECT id FROM outbox WHERE status = 'pending' AND next_attempt_at <= NOW() ORDER BY next_attempt_at, created_at LIMIT $1 FOR UPDATE SKIP LOCKED) RETURNING id, event_type, aggregate_id, payload, attempts";
+
+// SQLite runs one write at a time, so two workers can never claim the same row.
+#[cfg(feature = "sqlite")]
+const CLAIM_DUE: &str = "UPDATE outbox SET attempts = attempts + 1, next_attempt_at = strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now', printf('%+.3f seconds', $2)) WHERE id IN (SELECT id FROM outbox WHERE status = 'pending' AND next_attempt_at <= strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now') ORDER BY next_attempt_at, created_at LIMIT $1) RETURNING id, event_type, aggregate_id, payload, attempts";
+
+pub async fn mark_done(state: &SharedState, id: Uuid) -> Result<(), AppError> {
+    sqlx::query(&format!(
+        "UPDATE outbox SET status = 'done', processed_at = {NOW}, last_error = NULL WHERE id = $1"
+    ))
+    .bind(id)
+    .execute(&state.db_pool)
+    .await
+    .map_err(|err| {
+        AppError::InternalServerError(format!("Failed to update outbox event: {err}"))
+    })?;
+
+    Ok(())
+}
+
+/// Records a failed attempt; `retry_at` of `None` moves the event to the dead-letter state.
+pub async fn mark_failed(
+    state: &SharedState,
+    id: Uuid,
+    error: &str,
+    retry_at: Option<DateTime<Utc>>,
+) -> Result<OutboxStatus, AppError> {
+    let status = match retry_at {
+        Some(_) => OutboxStatus::Pending,
+        None => OutboxStatus::Dead,
+    };
+
+    sqlx::query(
+        &format!("UPDATE outbox SET status = $2, last_error = $3, next_attempt_at = COALESCE($4, next_attempt_at), processed_at = CASE WHEN $2 = 'dead' THEN {NOW} END WHERE id = $1"),
+    )
+    .bind(id)
+    .bind(status)
+    .bind(error)
+    .bind(retry_at)
+    .execute(&state.db_pool)
+    .await
+    .map_err(|err| AppError::InternalServerError(format!("Failed to update outbox event: {err}")))?;
+
+    Ok(status)
+}
@@ -0,0 +1,203 @@
+use crate::app::state::SharedState;
+use crate::error::AppError;
+use crate::outbox::models::{OutboxEvent, OutboxStatus};
+use crate::outbox::{POST_CREATED, POST_DELETED, POST_PUBLISHED, POST_UPDATED, queries};
+use chrono::{TimeDelta, Utc};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+const BATCH_SIZE: i64 = 50;
+/// How long a claimed event is hidden from other workers while it is being delivered.
+const CLAIM_LEASE: Duration = Duration::from_secs(5 * 60);
+const MAX_ATTEMPTS: i32 = 8;
+const BASE_BACKOFF: TimeDelta = TimeDelta::seconds(10);
+const MAX_BACKOFF: TimeDelta = TimeDelta::hours(1);
+
+/// Spawns the background task that delivers queued side-effect events.
+pub fn spawn_outbox_worker(state: SharedState) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            // Keep draining while full batches come back, then wait for the next tick.
+            loop {
+                match drain_once(&state).await {
+                    Ok(count) if count as i64 == BATCH_SIZE => continue,
+                    Ok(_) => break,
+                    Err(err) => {
+                        tracing::error!(error = %err, "outbox drain failed");
+                        break;
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Claims one batch of due events and tries to deliver each of them once.
+///
+/// Returns how many events were claimed.
+pub async fn drain_once(state: &SharedState) -> Result<usize, AppError> {
+    let events = queries::claim_due(state, BATCH_SIZE, CLAIM_LEASE).await?;
+
+    for event in &events {
+        match deliver(state, event).await {
+            Ok(()) => queries::mark_done(state, event.id).await?,
+            Err(err) => {
+                let retry_at =
+                    (event.attempts < MAX_ATTEMPTS).then(|| Utc::now() + backoff(event.attempts));
+                let status = queries::mark_failed(state, event.id, &err, retry_at).await?;
+
+                if status == OutboxStatus::Dead {
+                    tracing::error!(
+                        event_id = %event.id,
+                        event_type = %event.event_type,
+                        attempts = event.attempts,
+                        error = %err,
+                        "outbox event dead-lettered"
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(events.len())
+}
+
+/// Delay before the next try after `attempts` failures: doubles each time, capped at `MAX_BACKOFF`.
+pub fn backoff(attempts: i32) -> TimeDelta {
+    let exponent = attempts.saturating_sub(1).clamp(0, 16) as u32;
+    (BASE_BACKOFF * 2_i32.pow(exponent)).min(MAX_BACKOFF)
+}
+
+// Downstream consumers (webhooks, search reindexing, notification emails) hook in here.
+// Returning an error schedules a retry; unknown event types end up dead-lettered.
+async fn deliver(_state: &SharedState, event: &OutboxEvent) -> Result<(), String> {
+    match event.event_type.as_str() {
+        POST_CREATED | POST_UPDATED | POST_PUBLISHED | POST_DELETED => {
+            tracing::info!(
+                event_type = %event.event_type,
+                aggregate_id = %event.aggregate_id,
+                payload = %event.payload,
+                "outbox event delivered"
+            );
+            Ok(())
+        }
+        other => Err(format!("No consumer for event type '{other}'")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::state::AppState;
+    use crate::db::{DbPool, NOW};
+    use crate::post::{NewPost, models::PostStatus};
+    use serde_json::json;
+    use uuid::Uuid;
+
+    #[test]
+    fn backoff_doubles_and_is_capped() {
+        assert_eq!(backoff(1), TimeDelta::seconds(10));
+        assert_eq!(backoff(2), TimeDelta::seconds(20));
+        assert_eq!(backoff(4), TimeDelta::seconds(80));
+        assert_eq!(backoff(20), MAX_BACKOFF);
+        assert_eq!(backoff(i32::MAX), MAX_BACKOFF);
+    }
+
+    #[sqlx::test(migrator = "crate::db::MIGRATOR")]
+    #[cfg_attr(feature = "postgres", ignore = "needs a Postgres DATABASE_URL")]
+    async fn failing_events_retry_then_dead_letter(pool: DbPool) {
+        let state = AppState::for_tests_with_pool(pool);
+
+        let mut conn = state.db_pool.acquire().await.unwrap();
+        queries::enqueue(&mut conn, POST_CREATED, Uuid::new_v4(), json!({}))
+            .await
+            .unwrap();
+        queries::enqueue(&mut conn, "post.unknown", Uuid::new_v4(), json!({}))
+            .await
+            .unwrap();
+
+        let status_of = |event_type: &'static str| {
+            sqlx::query_as::<_, (OutboxStatus, i32)>(
+                "SELECT status, attempts FROM outbox WHERE event_type = $1",
+            )
+            .bind(event_type)
+            .fetch_one(&state.db_pool)
+        };
+
+        assert_eq!(drain_once(&state).await.unwrap(), 2);
+        assert_eq!(
+            status_of(POST_CREATED).await.unwrap(),
+            (OutboxStatus::Done, 1)
+        );
+        assert_eq!(
+            status_of("post.unknown").await.unwrap(),
+            (OutboxStatus::Pending, 1)
+        );
+
+        // Backed-off events are not retried early.
+        assert_eq!(drain_once(&state).await.unwrap(), 0);
+
+        for _ in 1..MAX_ATTEMPTS {
+            sqlx::query(&format!(
+                "UPDATE outbox SET next_attempt_at = {NOW} WHERE status = 'pending'"
+            ))
+            .execute(&state.db_pool)
+            .await
+            .unwrap();
+            drain_once(&state).await.unwrap();
+        }
+        assert_eq!(
+            status_of("post.unknown").await.unwrap(),
+            (OutboxStatus::Dead, MAX_ATTEMPTS)
+        );
+    }
+
+    #[sqlx::test(migrator = "crate::db::MIGRATOR")]
+    #[cfg_attr(feature = "postgres", ignore = "needs a Postgres DATABASE_URL")]
+    async fn every_post_write_is_queued_and_delivered(pool: DbPool) {
+        let state = AppState::for_tests_with_pool(pool);
+        let author = state
+            .authors
+            .insert("Ada", "ada@example.com", "hash")
+            .await
+            .unwrap();
+        let post = state
+            .posts
+            .insert(NewPost {
+                title: "Later",
+                content: "x",
+                content_html: "<p>x</p>",
+                author_id: author.id,
+                status: PostStatus::Scheduled,
+                published_at: Some(Utc::now() - TimeDelta::minutes(1)),
+                is_premium: false,
+                tags: &[],
+            })
+            .await
+            .unwrap();
+        assert_eq!(state.posts.publish_due().await.unwrap(), 1);
+        state.posts.delete(post.id, post.version + 1).await.unwrap();
+
+        let queued = sqlx::query_scalar::<_, String>(
+            "SELECT event_type FROM outbox WHERE aggregate_id = $1 ORDER BY created_at, id",
+        )
+        .bind(post.id)
+        .fetch_all(&state.db_pool)
+        .await
+        .unwrap();
+        assert_eq!(queued, [POST_CREATED, POST_PUBLISHED, POST_DELETED]);
+
+        assert_eq!(drain_once(&state).await.unwrap(), 3);
+        let done =
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM outbox WHERE status = 'done'")
+                .fetch_one(&state.db_pool)
+                .await
+                .unwrap();
+        assert_eq!(done, 3);
+    }
+}
@@ -0,0 +1,138 @@
+use crate::db::{self, Db};
+use crate::error::{AppError, ErrorCode};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Utc};
+use sqlx::QueryBuilder;
+use uuid::Uuid;
+
+/// Position of the last row on a page, used for keyset pagination.
+///
+/// Rows are ordered by `(created_at DESC, id DESC)`, so the next page starts
+/// strictly after this pair. Clients only ever see the encoded form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cursor {
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl Cursor {
+    pub fn new(created_at: DateTime<Utc>, id: Uuid) -> Self {
+        Self { created_at, id }
+    }
+
+    pub fn encode(&self) -> String {
+        let raw = format!("{}:{}", self.created_at.timestamp_micros(), self.id);
+        URL_SAFE_NO_PAD.encode(raw)
+    }
+
+    pub fn decode(value: &str) -> Result<Self, AppError> {
+        let invalid = || {
+            AppError::invalid(
+                "cursor",
+                ErrorCode::PaginationInvalidCursor,
+                "Invalid cursor",
+            )
+        };
+
+        let bytes = URL_SAFE_NO_PAD.decode(value).map_err(|_| invalid())?;
+        let raw = String::from_utf8(bytes).map_err(|_| invalid())?;
+        let (micros, id) = raw.split_once(':').ok_or_else(invalid)?;
+
+        let micros = micros.parse::<i64>().map_err(|_| invalid())?;
+        let created_at = DateTime::from_timestamp_micros(micros).ok_or_else(invalid)?;
+        let id = Uuid::parse_str(id).map_err(|_| invalid())?;
+
+        Ok(Self { created_at, id })
+    }
+}
+
+/// Pushes ` AND` the rows after `cursor`, if any.
+pub fn push_after(builder: &mut QueryBuilder<'_, Db>, cursor: Option<Cursor>) {
+    if let Some(cursor) = cursor {
+        builder
+            .push(" AND (created_at, id) < (")
+            .push_bind(db::timestamp(cursor.created_at))
+            .push(", ")
+            .push_bind(cursor.id)
+            .push(")");
+    }
+}
+
+/// Pushes the tail of an `ORDER BY` plus the `LIMIT` and `OFFSET` of a page.
+///
+/// One extra row is fetched for `Page::from_rows`. A cursor already marks where the page
+/// starts, so `offset` only applies without one.
+pub fn push_page_bounds(
+    builder: &mut QueryBuilder<'_, Db>,
+    cursor: Option<Cursor>,
+    limit: i64,
+    offset: i64,
+) {
+    builder
+        .push("created_at DESC, id DESC LIMIT ")
+        .push_bind(limit + 1);
+    if cursor.is_none() {
+        builder.push(" OFFSET ").push_bind(offset);
+    }
+}
+
+/// One page of rows plus the cursor for the page that follows it, if any.
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub has_more: bool,
+    pub next_cursor: Option<Cursor>,
+}
+
+impl<T> Page<T> {
+    /// Builds a page from rows fetched with `LIMIT limit + 1`; the extra row
+    /// only signals that another page exists and is dropped.
+    pub fn from_rows(mut rows: Vec<T>, limit: i64, key: impl Fn(&T) -> Cursor) -> Self {
+        let limit = usize::try_from(limit).unwrap_or(0);
+        let has_more = rows.len() > limit;
+        rows.truncate(limit);
+
+        let next_cursor = if has_more { rows.last().map(key) } else { None };
+        Self {
+            items: rows,
+            has_more,
+            next_cursor,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_round_trips() {
+        let created_at = DateTime::from_timestamp_micros(1_771_800_000_123_456).unwrap();
+        let cursor = Cursor::new(created_at, Uuid::new_v4());
+
+        let decoded = Cursor::decode(&cursor.encode()).unwrap();
+        assert_eq!(decoded, cursor);
+    }
+
+    #[test]
+    fn page_only_sets_cursor_when_more_rows_exist() {
+        let now = Utc::now();
+        let rows: Vec<Cursor> = (0..3).map(|_| Cursor::new(now, Uuid::new_v4())).collect();
+
+        let page = Page::from_rows(rows.clone(), 2, |row| *row);
+        assert_eq!(page.items.len(), 2);
+        assert!(page.has_more);
+        assert_eq!(page.next_cursor, Some(rows[1]));
+
+        let page = Page::from_rows(rows, 3, |row| *row);
+        assert_eq!(page.items.len(), 3);
+        assert!(!page.has_more);
+        assert_eq!(page.next_cursor, None);
+    }
+
+    #[test]
+    fn cursor_rejects_garbage() {
+        assert!(Cursor::decode("not-a-cursor").is_err());
+        assert!(Cursor::decode(&URL_SAFE_NO_PAD.encode("123:not-a-uuid")).is_err());
+    }
+}
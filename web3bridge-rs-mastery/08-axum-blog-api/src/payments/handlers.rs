@@ -0,0 +1,320 @@
+use crate::app::state::SharedState;
+use crate::error::{AppError, ErrorCode, ErrorResponse};
+use crate::payments::models::PaystackEvent;
+use crate::payments::queries;
+use crate::payments::signature::{self, SIGNATURE_HEADER};
+use crate::response::MessageResponse;
+use axum::{
+    Json,
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+};
+use secrecy::ExposeSecret;
+use serde_json::Value;
+
+const PROVIDER: &str = "paystack";
+
+/// Receives Paystack webhooks.
+///
+/// The signature is checked against the raw body before anything is parsed. Each
+/// event is stored once; a redelivery is acknowledged without being applied again.
+/// A `charge.success` whose checkout set `metadata.author_id` links its customer to that
+/// author, which is what entitles the author to the customer's subscriptions.
+#[utoipa::path(
+    post,
+    path = "/webhooks/paystack",
+    tag = "webhooks",
+    request_body(content = Object, description = "Raw Paystack event"),
+    params(("x-paystack-signature" = String, Header, description = "Hex HMAC-SHA512 of the body")),
+    responses(
+        (status = 200, description = "Event stored (or already seen)", body = MessageResponse),
+        (status = 401, description = "Bad signature", body = ErrorResponse),
+        (status = 422, description = "Body is not a Paystack event", body = ErrorResponse),
+    )
+)]
+pub async fn paystack_webhook(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<impl IntoResponse, AppError> {
+    // The route is only mounted when a secret is configured.
+    let secret = state.paystack_secret.as_ref().ok_or_else(|| {
+        AppError::InternalServerError("Payment webhooks are not configured".to_string())
+    })?;
+
+    let signature = headers
+        .get(SIGNATURE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("");
+    if !signature::verify(secret.expose_secret().as_bytes(), &body, signature) {
+        return Err(AppError::Unauthorized(
+            ErrorCode::PaymentInvalidSignature,
+            "Invalid webhook signature".to_string(),
+        ));
+    }
+
+    let invalid = |err: serde_json::Error| {
+        AppError::invalid(
+            "body",
+            ErrorCode::PaymentInvalidPayload,
+            format!("Invalid webhook payload: {err}"),
+        )
+    };
+    let payload: Value = serde_json::from_slice(&body).map_err(invalid)?;
+    let event: PaystackEvent = serde_json::from_value(payload.clone()).map_err(invalid)?;
+    let event_id = event.event_id(&body);
+
+    let mut tx = state.db_pool.begin().await.map_err(|err| {
+        AppError::InternalServerError(format!("Failed to start transaction: {err}"))
+    })?;
+
+    let is_new =
+        queries::record_event(&mut tx, PROVIDER, &event_id, &event.event, &payload).await?;
+    if !is_new {
+        tracing::info!(event_id = %event_id, "ignoring duplicate payment event");
+        return Ok((
+            StatusCode::OK,
+            Json(MessageResponse::ok("Event already processed")),
+        ));
+    }
+
+    if let Some(link) = event.customer_link() {
+        queries::link_customer(&mut tx, PROVIDER, &link).await?;
+    }
+    if let Some(update) = event.subscription_update() {
+        queries::upsert_subscription(&mut tx, PROVIDER, &update).await?;
+    }
+    queries::mark_event_processed(&mut tx, PROVIDER, &event_id).await?;
+
+    tx.commit().await.map_err(|err| {
+        AppError::InternalServerError(format!("Failed to store payment event: {err}"))
+    })?;
+
+    tracing::info!(event_id = %event_id, event_type = %event.event, "processed payment event");
+    Ok((StatusCode::OK, Json(MessageResponse::ok("Event processed"))))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::router::create_router;
+    use crate::app::state::{AppState, SharedState};
+    use crate::auth::JwtKeys;
+    use crate::config::{FeatureToggles, RateLimitConfig};
+    use crate::db::DbPool;
+    use crate::payments::{self, signature};
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use secrecy::SecretString;
+    use serde_json::json;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    const SECRET: &str = "sk_test_webhook";
+
+    fn state(pool: DbPool) -> SharedState {
+        let jwt = JwtKeys::new(&SecretString::from("test-secret"), 60);
+        SharedState::new(AppState::new(
+            pool,
+            jwt,
+            5,
+            Some(SecretString::from(SECRET)),
+            None,
+            FeatureToggles::default(),
+            RateLimitConfig::default(),
+        ))
+    }
+
+    fn webhook(body: &str, signature: &str) -> Request<Body> {
+        Request::post("/webhooks/paystack")
+            .header("content-type", "application/json")
+            .header(signature::SIGNATURE_HEADER, signature)
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn unsigned_webhook_is_rejected_before_touching_the_database() {
+        let app = create_router(state(crate::db::unreachable_pool()));
+        let body = r#"{"event":"subscription.create","data":{}}"#;
+
+        let response = app
+            .clone()
+            .oneshot(webhook(
+                body,
+                &signature::sign(b"wrong-secret", body.as_bytes()),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app.oneshot(webhook(body, "")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn webhooks_are_not_mounted_without_a_secret() {
+        let app = create_router(AppState::for_tests());
+        let body = r#"{"event":"subscription.create","data":{}}"#;
+
+        let response = app
+            .oneshot(webhook(
+                body,
+                &signature::sign(SECRET.as_bytes(), body.as_bytes()),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    async fn reader(pool: &DbPool) -> Uuid {
+        let author_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO authors (id, name, email) VALUES ($1, 'Reader', 'reader@example.com')",
+        )
+        .bind(author_id)
+        .execute(pool)
+        .await
+        .unwrap();
+        author_id
+    }
+
+    fn subscription_event(event: &str, updated_at: &str) -> serde_json::Value {
+        json!({
+            "event": event,
+            "data": {
+                "id": 1,
+                "subscription_code": "SUB_test",
+                "customer": { "email": "reader@example.com", "customer_code": "CUS_test" },
+                "plan": { "plan_code": "PLN_test" },
+                "updatedAt": updated_at
+            }
+        })
+    }
+
+    fn checkout_charge(author_id: Uuid) -> serde_json::Value {
+        json!({
+            "event": "charge.success",
+            "data": {
+                "id": 7,
+                "reference": "ref_1",
+                "customer": { "email": "payer@example.com", "customer_code": "CUS_test" },
+                "metadata": { "author_id": author_id.to_string() }
+            }
+        })
+    }
+
+    #[sqlx::test(migrator = "crate::db::MIGRATOR")]
+    #[cfg_attr(feature = "postgres", ignore = "needs a Postgres DATABASE_URL")]
+    async fn signed_events_drive_subscription_state(pool: DbPool) {
+        let author_id = reader(&pool).await;
+        let state = state(pool);
+        let app = create_router(state.clone());
+        let send = |value: serde_json::Value| {
+            let body = value.to_string();
+            let signature = signature::sign(SECRET.as_bytes(), body.as_bytes());
+            app.clone().oneshot(webhook(&body, &signature))
+        };
+
+        let created = subscription_event("subscription.create", "2026-10-01T00:00:00Z");
+        assert_eq!(
+            send(created.clone()).await.unwrap().status(),
+            StatusCode::OK
+        );
+        // A matching email alone grants nothing: anyone can sign up with any address.
+        assert!(
+            !payments::has_active_subscription(&state, author_id)
+                .await
+                .unwrap()
+        );
+
+        assert_eq!(
+            send(checkout_charge(author_id)).await.unwrap().status(),
+            StatusCode::OK
+        );
+        assert!(
+            payments::has_active_subscription(&state, author_id)
+                .await
+                .unwrap()
+        );
+
+        // A redelivery is acknowledged but stored only once.
+        assert_eq!(send(created).await.unwrap().status(), StatusCode::OK);
+        let stored: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM payment_events")
+            .fetch_one(&state.db_pool)
+            .await
+            .unwrap();
+        assert_eq!(stored, 2);
+
+        // The first link stands; a later charge cannot hand the customer to someone else.
+        let other = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO authors (id, name, email) VALUES ($1, 'Other', 'other@example.com')",
+        )
+        .bind(other)
+        .execute(&state.db_pool)
+        .await
+        .unwrap();
+        let mut charge = checkout_charge(other);
+        charge["data"]["reference"] = json!("ref_2");
+        assert_eq!(send(charge).await.unwrap().status(), StatusCode::OK);
+        assert!(
+            !payments::has_active_subscription(&state, other)
+                .await
+                .unwrap()
+        );
+
+        let disabled = subscription_event("subscription.disable", "2026-10-02T00:00:00Z");
+        assert_eq!(send(disabled).await.unwrap().status(), StatusCode::OK);
+        assert!(
+            !payments::has_active_subscription(&state, author_id)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[sqlx::test(migrator = "crate::db::MIGRATOR")]
+    #[cfg_attr(feature = "postgres", ignore = "needs a Postgres DATABASE_URL")]
+    async fn a_subscription_can_be_disabled_again_after_a_re_enable(pool: DbPool) {
+        let author_id = reader(&pool).await;
+        let state = state(pool);
+        let app = create_router(state.clone());
+        let send = |value: serde_json::Value| {
+            let body = value.to_string();
+            let signature = signature::sign(SECRET.as_bytes(), body.as_bytes());
+            app.clone().oneshot(webhook(&body, &signature))
+        };
+        send(checkout_charge(author_id)).await.unwrap();
+
+        // Same subscription, same `data.id`; only the event and its time differ.
+        for (event, at, active) in [
+            ("subscription.create", "2026-10-01T00:00:00Z", true),
+            ("subscription.disable", "2026-10-02T00:00:00Z", false),
+            ("subscription.enable", "2026-10-03T00:00:00Z", true),
+            ("subscription.disable", "2026-10-04T00:00:00Z", false),
+        ] {
+            let response = send(subscription_event(event, at)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let entitled = payments::has_active_subscription(&state, author_id)
+                .await
+                .unwrap();
+            assert_eq!(entitled, active, "after {event} at {at}");
+        }
+
+        // A retry of the last delivery is still recognised.
+        send(subscription_event(
+            "subscription.disable",
+            "2026-10-04T00:00:00Z",
+        ))
+        .await
+        .unwrap();
+        let stored: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM payment_events")
+            .fetch_one(&state.db_pool)
+            .await
+            .unwrap();
+        assert_eq!(stored, 5);
+    }
+}
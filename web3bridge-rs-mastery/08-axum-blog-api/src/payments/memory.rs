@@ -0,0 +1,26 @@
+use crate::error::AppError;
+use crate::payments::repository::SubscriptionRepository;
+use async_trait::async_trait;
+use std::collections::HashSet;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// Keeps the entitled authors in a set so handler tests run without a database.
+#[derive(Debug, Default)]
+pub struct InMemorySubscriptionRepository {
+    subscribers: Mutex<HashSet<Uuid>>,
+}
+
+impl InMemorySubscriptionRepository {
+    /// Entitles the author, as a linked customer's active subscription would.
+    pub fn subscribe(&self, author_id: Uuid) {
+        self.subscribers.lock().unwrap().insert(author_id);
+    }
+}
+
+#[async_trait]
+impl SubscriptionRepository for InMemorySubscriptionRepository {
+    async fn has_active_subscription(&self, author_id: Uuid) -> Result<bool, AppError> {
+        Ok(self.subscribers.lock().unwrap().contains(&author_id))
+    }
+}
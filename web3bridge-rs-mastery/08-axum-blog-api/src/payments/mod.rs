@@ -0,0 +1,36 @@
+mod handlers;
+#[cfg(test)]
+mod memory;
+pub mod models;
+mod queries;
+mod repository;
+pub mod signature;
+
+use crate::app::state::SharedState;
+use crate::error::AppError;
+use axum::{Router, routing::post};
+use utoipa::OpenApi;
+use uuid::Uuid;
+
+#[cfg(test)]
+pub use memory::InMemorySubscriptionRepository;
+pub use repository::{SqlSubscriptionRepository, SubscriptionRepository};
+
+/// OpenAPI paths for the routes under `/webhooks`.
+#[derive(OpenApi)]
+#[openapi(paths(handlers::paystack_webhook))]
+pub struct PaymentApi;
+
+/// Routes nested under `/webhooks`.
+pub fn payment_routes() -> Router<SharedState> {
+    Router::new().route("/paystack", post(handlers::paystack_webhook))
+}
+
+/// Whether a customer linked to the author has a subscription that currently grants premium
+/// access.
+pub async fn has_active_subscription(
+    state: &SharedState,
+    author_id: Uuid,
+) -> Result<bool, AppError> {
+    state.subscriptions.has_active_subscription(author_id).await
+}
@@ -0,0 +1,187 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha512};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "kebab-case")]
+#[sqlx(type_name = "TEXT", rename_all = "kebab-case")]
+pub enum SubscriptionStatus {
+    Active,
+    NonRenewing,
+    Attention,
+    Cancelled,
+}
+
+/// Envelope shared by every Paystack webhook.
+#[derive(Debug, Deserialize)]
+pub struct PaystackEvent {
+    pub event: String,
+    #[serde(default)]
+    pub data: Value,
+}
+
+/// The subscription row an event asks us to write.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriptionUpdate {
+    pub subscription_code: String,
+    /// Links the subscription to an author through `payment_customers`.
+    pub customer_code: String,
+    /// Stored for support lookups only; authors' emails are unverified, so it grants nothing.
+    pub customer_email: String,
+    pub plan_code: Option<String>,
+    pub status: SubscriptionStatus,
+    pub next_payment_date: Option<DateTime<Utc>>,
+}
+
+/// The author a customer pays for, from a successful charge whose checkout set
+/// `metadata.author_id`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomerLink {
+    pub customer_code: String,
+    pub author_id: Uuid,
+}
+
+impl PaystackEvent {
+    /// Idempotency key for this delivery: a digest of the raw signed body.
+    ///
+    /// Paystack has no envelope id, and `data.id` names the subscription or charge rather
+    /// than the event, so keying on it would drop a second `subscription.disable` after a
+    /// re-enable. Retries resend the same bytes and so get the same key.
+    pub fn event_id(&self, raw_body: &[u8]) -> String {
+        format!("{}:{}", self.event, hex::encode(Sha512::digest(raw_body)))
+    }
+
+    /// Only charges carry the metadata our checkout sets, and only a signed webhook reaches this.
+    pub fn customer_link(&self) -> Option<CustomerLink> {
+        if self.event != "charge.success" {
+            return None;
+        }
+        let customer_code = self.data["customer"]["customer_code"].as_str()?.to_string();
+        let author_id = Uuid::parse_str(self.data["metadata"]["author_id"].as_str()?).ok()?;
+        Some(CustomerLink {
+            customer_code,
+            author_id,
+        })
+    }
+
+    /// Maps subscription lifecycle events onto a subscription write; other events only get stored.
+    pub fn subscription_update(&self) -> Option<SubscriptionUpdate> {
+        let data = &self.data;
+        let (subscription, status) = match self.event.as_str() {
+            "subscription.create" | "subscription.enable" => (data, SubscriptionStatus::Active),
+            "subscription.not_renew" => (data, SubscriptionStatus::NonRenewing),
+            "subscription.disable" => (data, SubscriptionStatus::Cancelled),
+            "invoice.payment_failed" => (&data["subscription"], SubscriptionStatus::Attention),
+            "invoice.update" if data["paid"].as_bool() == Some(true) => {
+                (&data["subscription"], SubscriptionStatus::Active)
+            }
+            _ => return None,
+        };
+
+        let subscription_code = subscription["subscription_code"].as_str()?.to_string();
+        let customer_code = data["customer"]["customer_code"].as_str()?.to_string();
+        let customer_email = data["customer"]["email"].as_str()?.trim().to_lowercase();
+        let plan_code = subscription["plan"]["plan_code"]
+            .as_str()
+            .or_else(|| data["plan"]["plan_code"].as_str())
+            .map(str::to_string);
+        let next_payment_date = subscription["next_payment_date"]
+            .as_str()
+            .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+            .map(|value| value.with_timezone(&Utc));
+
+        Some(SubscriptionUpdate {
+            subscription_code,
+            customer_code,
+            customer_email,
+            plan_code,
+            status,
+            next_payment_date,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn event(value: Value) -> PaystackEvent {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn subscription_create_activates() {
+        let event = event(json!({
+            "event": "subscription.create",
+            "data": {
+                "id": 42,
+                "subscription_code": "SUB_abc",
+                "next_payment_date": "2026-11-18T00:00:00.000Z",
+                "customer": { "email": "Reader@Example.com", "customer_code": "CUS_abc" },
+                "plan": { "plan_code": "PLN_monthly" }
+            }
+        }));
+
+        let update = event.subscription_update().unwrap();
+        assert_eq!(update.subscription_code, "SUB_abc");
+        assert_eq!(update.customer_code, "CUS_abc");
+        assert_eq!(update.customer_email, "reader@example.com");
+        assert_eq!(update.plan_code.as_deref(), Some("PLN_monthly"));
+        assert_eq!(update.status, SubscriptionStatus::Active);
+        assert!(update.next_payment_date.is_some());
+    }
+
+    #[test]
+    fn failed_invoice_reads_nested_subscription() {
+        let event = event(json!({
+            "event": "invoice.payment_failed",
+            "data": {
+                "subscription": { "subscription_code": "SUB_abc" },
+                "customer": { "email": "reader@example.com", "customer_code": "CUS_abc" }
+            }
+        }));
+
+        let update = event.subscription_update().unwrap();
+        assert_eq!(update.subscription_code, "SUB_abc");
+        assert_eq!(update.status, SubscriptionStatus::Attention);
+    }
+
+    #[test]
+    fn unrelated_events_are_stored_only() {
+        let event = event(json!({ "event": "charge.success", "data": { "reference": "r1" } }));
+
+        assert_eq!(event.subscription_update(), None);
+        assert_eq!(event.customer_link(), None);
+    }
+
+    #[test]
+    fn tagged_charges_link_the_customer_to_an_author() {
+        let author_id = Uuid::new_v4();
+        let charge = |event_type: &str| {
+            event(json!({
+                "event": event_type,
+                "data": {
+                    "customer": { "email": "someone@example.com", "customer_code": "CUS_abc" },
+                    "metadata": { "author_id": author_id.to_string() }
+                }
+            }))
+        };
+
+        let link = charge("charge.success").customer_link().unwrap();
+        assert_eq!(link.customer_code, "CUS_abc");
+        assert_eq!(link.author_id, author_id);
+        assert_eq!(charge("charge.failed").customer_link(), None);
+    }
+
+    #[test]
+    fn deliveries_are_keyed_by_their_body() {
+        let event = event(json!({ "event": "subscription.disable", "data": { "id": 42 } }));
+
+        assert_eq!(event.event_id(b"body"), event.event_id(b"body"));
+        assert_ne!(event.event_id(b"body"), event.event_id(b"other body"));
+        assert!(event.event_id(b"body").starts_with("subscription.disable:"));
+    }
+}
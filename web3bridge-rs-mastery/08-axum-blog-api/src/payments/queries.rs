@@ -0,0 +1,104 @@
+use crate::db::{DbConnection, DbPool, NOW};
+use crate::error::AppError;
+use crate::payments::models::{CustomerLink, SubscriptionUpdate};
+use serde_json::Value;
+use uuid::Uuid;
+
+/// Stores a raw event; returns `false` when it was already recorded.
+pub async fn record_event(
+    conn: &mut DbConnection,
+    provider: &str,
+    event_id: &str,
+    event_type: &str,
+    payload: &Value,
+) -> Result<bool, AppError> {
+    let result = sqlx::query(
+        "INSERT INTO payment_events (id, provider, event_id, event_type, payload) VALUES ($1, $2, $3, $4, $5) ON CONFLICT (provider, event_id) DO NOTHING",
+    )
+    .bind(Uuid::new_v4())
+    .bind(provider)
+    .bind(event_id)
+    .bind(event_type)
+    .bind(payload)
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| AppError::InternalServerError(format!("Failed to record payment event: {err}")))?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+pub async fn mark_event_processed(
+    conn: &mut DbConnection,
+    provider: &str,
+    event_id: &str,
+) -> Result<(), AppError> {
+    sqlx::query(&format!(
+        "UPDATE payment_events SET processed_at = {NOW} WHERE provider = $1 AND event_id = $2"
+    ))
+    .bind(provider)
+    .bind(event_id)
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| {
+        AppError::InternalServerError(format!("Failed to update payment event: {err}"))
+    })?;
+
+    Ok(())
+}
+
+/// Records which author a customer pays for. The first link wins, so a later charge cannot
+/// move someone's subscription to another author; links to unknown authors are dropped.
+pub async fn link_customer(
+    conn: &mut DbConnection,
+    provider: &str,
+    link: &CustomerLink,
+) -> Result<(), AppError> {
+    sqlx::query(
+        "INSERT INTO payment_customers (provider, customer_code, author_id) SELECT $1, $2, id FROM authors WHERE id = $3 ON CONFLICT (provider, customer_code) DO NOTHING",
+    )
+    .bind(provider)
+    .bind(&link.customer_code)
+    .bind(link.author_id)
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| AppError::InternalServerError(format!("Failed to link payment customer: {err}")))?;
+
+    Ok(())
+}
+
+pub async fn upsert_subscription(
+    conn: &mut DbConnection,
+    provider: &str,
+    update: &SubscriptionUpdate,
+) -> Result<(), AppError> {
+    sqlx::query(
+        &format!("INSERT INTO subscriptions (id, provider, subscription_code, customer_email, plan_code, status, next_payment_date, customer_code) VALUES ($1, $2, $3, $4, $5, $6, $7, $8) ON CONFLICT (subscription_code) DO UPDATE SET status = EXCLUDED.status, customer_code = EXCLUDED.customer_code, plan_code = COALESCE(EXCLUDED.plan_code, subscriptions.plan_code), next_payment_date = COALESCE(EXCLUDED.next_payment_date, subscriptions.next_payment_date), updated_at = {NOW}"),
+    )
+    .bind(Uuid::new_v4())
+    .bind(provider)
+    .bind(&update.subscription_code)
+    .bind(&update.customer_email)
+    .bind(&update.plan_code)
+    .bind(update.status)
+    .bind(update.next_payment_date)
+    .bind(&update.customer_code)
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| AppError::InternalServerError(format!("Failed to update subscription: {err}")))?;
+
+    Ok(())
+}
+
+/// Non-renewing subscriptions stay entitled until the period they already paid for ends.
+/// Subscriptions count only through a `payment_customers` link, never by matching emails.
+pub async fn has_active_subscription(pool: &DbPool, author_id: Uuid) -> Result<bool, AppError> {
+    let active = sqlx::query_scalar::<_, bool>(
+        &format!("SELECT EXISTS (SELECT 1 FROM subscriptions s JOIN payment_customers c ON c.provider = s.provider AND c.customer_code = s.customer_code WHERE c.author_id = $1 AND (s.status = 'active' OR (s.status = 'non-renewing' AND s.next_payment_date > {NOW})))"),
+    )
+    .bind(author_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|err| AppError::InternalServerError(format!("Failed to check subscription: {err}")))?;
+
+    Ok(active)
+}
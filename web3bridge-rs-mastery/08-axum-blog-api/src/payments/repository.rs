@@ -0,0 +1,34 @@
+use crate::db::DbPool;
+use crate::error::AppError;
+use crate::payments::queries;
+use async_trait::async_trait;
+use std::fmt::Debug;
+use uuid::Uuid;
+
+/// What the rest of the app asks about payments, so post handlers do not depend on a
+/// particular database. The webhook writes through `payments::queries` directly.
+#[async_trait]
+pub trait SubscriptionRepository: Debug + Send + Sync {
+    /// Whether a customer linked to the author has a subscription that currently grants
+    /// premium access.
+    async fn has_active_subscription(&self, author_id: Uuid) -> Result<bool, AppError>;
+}
+
+/// Database-backed subscriptions, as recorded by the Paystack webhook.
+#[derive(Debug, Clone)]
+pub struct SqlSubscriptionRepository {
+    pool: DbPool,
+}
+
+impl SqlSubscriptionRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl SubscriptionRepository for SqlSubscriptionRepository {
+    async fn has_active_subscription(&self, author_id: Uuid) -> Result<bool, AppError> {
+        queries::has_active_subscription(&self.pool, author_id).await
+    }
+}
@@ -0,0 +1,94 @@
+use crate::cache::{self, Cache};
+use crate::config::CacheConfig;
+use crate::pagination::{Cursor, Page};
+use crate::post::models::{Post, PostCriteria};
+use crate::telemetry::Metrics;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// The caches behind `post::queries`: single posts, listing pages and listing totals.
+///
+/// A listing can only change when a post it may contain changes, so a write by one author
+/// keeps every listing filtered to another author.
+#[derive(Debug, Clone)]
+pub struct PostCache {
+    pub(super) by_id: Arc<dyn Cache<Uuid, Post>>,
+    pub(super) lists: Arc<dyn Cache<ListKey, Page<Post>>>,
+    pub(super) counts: Arc<dyn Cache<PostCriteria, i64>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(super) struct ListKey {
+    pub criteria: PostCriteria,
+    pub cursor: Option<Cursor>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl PostCache {
+    pub fn new(config: &CacheConfig, metrics: &Metrics) -> Self {
+        Self {
+            by_id: cache::build("posts", config, metrics),
+            lists: cache::build("post_lists", config, metrics),
+            counts: cache::build("post_counts", config, metrics),
+        }
+    }
+
+    /// After a post by `author_id` is written.
+    pub fn invalidate_post(&self, id: Uuid, author_id: Uuid) {
+        self.by_id.remove(&id);
+        self.invalidate_listings(author_id);
+    }
+
+    /// After the author is deleted, which cascades to all of their posts.
+    pub fn invalidate_author(&self, author_id: Uuid) {
+        self.by_id
+            .remove_where(&|_, post| post.author_id == author_id);
+        self.invalidate_listings(author_id);
+    }
+
+    pub fn clear(&self) {
+        self.by_id.clear();
+        self.lists.clear();
+        self.counts.clear();
+    }
+
+    fn invalidate_listings(&self, author_id: Uuid) {
+        self.lists
+            .remove_where(&|key, _| may_include(&key.criteria, author_id));
+        self.counts
+            .remove_where(&|criteria, _| may_include(criteria, author_id));
+    }
+}
+
+fn may_include(criteria: &PostCriteria, author_id: Uuid) -> bool {
+    criteria.author_id.is_none_or(|filter| filter == author_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn criteria(author_id: Option<Uuid>) -> PostCriteria {
+        PostCriteria {
+            author_id,
+            ..PostCriteria::default()
+        }
+    }
+
+    #[test]
+    fn writes_keep_listings_filtered_to_other_authors() {
+        let cache = PostCache::new(&CacheConfig::default(), &Metrics::new());
+        let (ada, grace) = (Uuid::new_v4(), Uuid::new_v4());
+        let generation = cache.counts.generation();
+        for author_id in [None, Some(ada), Some(grace)] {
+            cache.counts.insert(criteria(author_id), 1, generation);
+        }
+
+        cache.invalidate_post(Uuid::new_v4(), ada);
+
+        assert_eq!(cache.counts.get(&criteria(None)), None);
+        assert_eq!(cache.counts.get(&criteria(Some(ada))), None);
+        assert_eq!(cache.counts.get(&criteria(Some(grace))), Some(1));
+    }
+}
@@ -0,0 +1,308 @@
+use crate::post::models::{Post, PostStatus};
+use crate::post::queries::event_payload;
+use futures_util::{Stream, StreamExt, stream};
+use serde_json::{Value, json};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast::{self, error::RecvError};
+use uuid::Uuid;
+
+/// Events kept for `Last-Event-ID` resumes; also how far a subscriber may fall behind.
+const REPLAY_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostEventKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+impl PostEventKind {
+    /// The SSE `event` name.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Updated => "updated",
+            Self::Deleted => "deleted",
+        }
+    }
+}
+
+/// A committed post write, numbered in publish order from 1.
+#[derive(Debug, Clone)]
+pub struct PostEvent {
+    pub id: u64,
+    pub kind: PostEventKind,
+    pub author_id: Uuid,
+    /// The status when written; a deleted post keeps the status it had.
+    pub status: PostStatus,
+    /// Whether the post was published before this write.
+    pub was_public: bool,
+    /// `event_payload` of the post as written.
+    pub data: Value,
+}
+
+impl PostEvent {
+    /// What `viewer_id` is sent for this event, if anything.
+    ///
+    /// Anyone may see events for published posts, only the author those for drafts and
+    /// scheduled posts. A published post taken back to draft or scheduled has gone from
+    /// everyone else's view, so they are told it was deleted, without its new details.
+    pub fn seen_by(&self, viewer_id: Option<Uuid>) -> Option<(PostEventKind, Value)> {
+        if self.status == PostStatus::Published || viewer_id == Some(self.author_id) {
+            Some((self.kind, self.data.clone()))
+        } else if self.was_public {
+            let gone = json!({ "id": self.data["id"], "author_id": self.author_id });
+            Some((PostEventKind::Deleted, gone))
+        } else {
+            None
+        }
+    }
+}
+
+/// What a subscriber receives: the next event, or notice that some were missed and the
+/// client should refetch what it shows.
+#[derive(Debug, Clone)]
+pub enum Delivery {
+    Event(PostEvent),
+    Missed,
+}
+
+/// Fans committed post writes out to `GET /posts/stream` subscribers through a broadcast
+/// channel, keeping the latest events so a reconnecting client can resume.
+#[derive(Debug, Clone)]
+pub struct PostEvents {
+    log: Arc<Mutex<EventLog>>,
+}
+
+#[derive(Debug)]
+struct EventLog {
+    // `None` once closed, which ends every subscription.
+    sender: Option<broadcast::Sender<Delivery>>,
+    recent: VecDeque<PostEvent>,
+    next_id: u64,
+}
+
+impl Default for PostEvents {
+    fn default() -> Self {
+        Self {
+            log: Arc::new(Mutex::new(EventLog {
+                sender: Some(broadcast::channel(REPLAY_CAPACITY).0),
+                recent: VecDeque::with_capacity(REPLAY_CAPACITY),
+                next_id: 1,
+            })),
+        }
+    }
+}
+
+impl PostEvents {
+    fn lock(&self) -> std::sync::MutexGuard<'_, EventLog> {
+        self.log.lock().expect("post event log poisoned")
+    }
+
+    /// Publishes a write that left the post's status as it was, or created or deleted it.
+    pub fn publish(&self, kind: PostEventKind, post: &Post) {
+        let was_public = kind != PostEventKind::Created && post.status == PostStatus::Published;
+        self.push(kind, post, was_public);
+    }
+
+    /// Publishes an update of a post whose status was `previous` before it.
+    pub fn publish_update(&self, previous: PostStatus, post: &Post) {
+        self.push(
+            PostEventKind::Updated,
+            post,
+            previous == PostStatus::Published,
+        );
+    }
+
+    fn push(&self, kind: PostEventKind, post: &Post, was_public: bool) {
+        let mut log = self.lock();
+        let Some(sender) = log.sender.clone() else {
+            return;
+        };
+
+        let event = PostEvent {
+            id: log.next_id,
+            kind,
+            author_id: post.author_id,
+            status: post.status,
+            was_public,
+            data: event_payload(post),
+        };
+        log.next_id += 1;
+        if log.recent.len() == REPLAY_CAPACITY {
+            log.recent.pop_front();
+        }
+        log.recent.push_back(event.clone());
+        // Sent under the lock, so a subscriber sees each event either in its backlog or live.
+        // Having no subscribers is not an error.
+        let _ = sender.send(Delivery::Event(event));
+    }
+
+    /// Tells every subscriber to refetch what it shows, for a change too large to send
+    /// post by post. Clients resuming from before it are told the same.
+    pub fn publish_reset(&self) {
+        let mut log = self.lock();
+        let Some(sender) = log.sender.clone() else {
+            return;
+        };
+
+        // With the backlog emptied and an id skipped, no earlier id can be resumed from.
+        log.recent.clear();
+        log.next_id += 1;
+        let _ = sender.send(Delivery::Missed);
+    }
+
+    /// Events after `last_event_id`, then live ones. Starts with `Delivery::Missed` when the
+    /// buffer no longer reaches back that far, or the id is from before a restart.
+    pub fn subscribe(&self, last_event_id: Option<u64>) -> impl Stream<Item = Delivery> + use<> {
+        let log = self.lock();
+        let receiver = log.sender.as_ref().map(broadcast::Sender::subscribe);
+
+        let mut backlog = Vec::new();
+        if let Some(last) = last_event_id {
+            let oldest = log.recent.front().map_or(log.next_id, |event| event.id);
+            if last >= log.next_id || last.saturating_add(1) < oldest {
+                backlog.push(Delivery::Missed);
+            }
+            backlog.extend(
+                log.recent
+                    .iter()
+                    .filter(|event| event.id > last)
+                    .cloned()
+                    .map(Delivery::Event),
+            );
+        }
+        drop(log);
+
+        let live = stream::unfold(receiver, |receiver| async move {
+            let mut receiver = receiver?;
+            let delivery = match receiver.recv().await {
+                Ok(delivery) => delivery,
+                Err(RecvError::Lagged(_)) => Delivery::Missed,
+                Err(RecvError::Closed) => return None,
+            };
+            Some((delivery, Some(receiver)))
+        });
+        stream::iter(backlog).chain(live)
+    }
+
+    /// Ends every subscription, so open streams do not hold up a graceful shutdown.
+    pub fn close(&self) {
+        self.lock().sender = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn post(author_id: Uuid) -> Post {
+        let now = Utc::now();
+        Post {
+            id: Uuid::new_v4(),
+            title: "Hello".to_string(),
+            content: "hi".to_string(),
+            content_html: None,
+            author_id,
+            status: PostStatus::Published,
+            published_at: Some(now),
+            is_premium: false,
+            deleted_at: None,
+            version: 1,
+            created_at: now,
+            updated_at: now,
+            snippet: None,
+            tags: Vec::new(),
+        }
+    }
+
+    fn ids(deliveries: &[Delivery]) -> Vec<Option<u64>> {
+        deliveries
+            .iter()
+            .map(|delivery| match delivery {
+                Delivery::Event(event) => Some(event.id),
+                Delivery::Missed => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn resumes_after_the_last_event_id_while_it_is_buffered() {
+        let events = PostEvents::default();
+        let author_id = Uuid::new_v4();
+        for _ in 0..REPLAY_CAPACITY + 2 {
+            events.publish(PostEventKind::Created, &post(author_id));
+        }
+        events.close();
+
+        let last = REPLAY_CAPACITY as u64;
+        let resumed: Vec<_> = events.subscribe(Some(last)).collect().await;
+        assert_eq!(ids(&resumed), [Some(last + 1), Some(last + 2)]);
+
+        // Event 1 has been dropped from the buffer, and 999 was never sent by this process.
+        for last in [1, 999] {
+            let resumed: Vec<_> = events.subscribe(Some(last)).collect().await;
+            assert_eq!(ids(&resumed)[0], None);
+        }
+    }
+
+    #[tokio::test]
+    async fn live_events_follow_the_backlog_until_closed() {
+        let events = PostEvents::default();
+        let author_id = Uuid::new_v4();
+        events.publish(PostEventKind::Created, &post(author_id));
+
+        let subscription = events.subscribe(Some(0));
+        events.publish(PostEventKind::Updated, &post(author_id));
+        events.close();
+
+        let received: Vec<_> = subscription.collect().await;
+        assert_eq!(ids(&received), [Some(1), Some(2)]);
+    }
+
+    #[tokio::test]
+    async fn a_reset_reaches_live_subscribers_and_every_resume_from_before_it() {
+        let events = PostEvents::default();
+        let author_id = Uuid::new_v4();
+        events.publish(PostEventKind::Created, &post(author_id));
+
+        let subscription = events.subscribe(Some(1));
+        events.publish_reset();
+        events.publish(PostEventKind::Created, &post(author_id));
+        let caught_up = events.subscribe(Some(1));
+        let after_reset = events.subscribe(Some(3));
+        events.close();
+
+        assert_eq!(ids(&subscription.collect::<Vec<_>>().await), [None, Some(3)]);
+        assert_eq!(ids(&caught_up.collect::<Vec<_>>().await), [None, Some(3)]);
+        assert!(after_reset.collect::<Vec<_>>().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn unpublishing_reads_as_a_delete_to_everyone_but_the_author() {
+        let events = PostEvents::default();
+        let author_id = Uuid::new_v4();
+        let mut draft = post(author_id);
+        draft.status = PostStatus::Draft;
+        events.publish_update(PostStatus::Published, &draft);
+        events.publish_update(PostStatus::Draft, &draft);
+        events.close();
+
+        let received: Vec<_> = events.subscribe(Some(0)).collect().await;
+        let [Delivery::Event(unpublished), Delivery::Event(edited)] = &received[..] else {
+            panic!("expected two events, got {received:?}");
+        };
+
+        let (kind, data) = unpublished.seen_by(None).unwrap();
+        assert_eq!(kind, PostEventKind::Deleted);
+        assert_eq!(data["id"], draft.id.to_string());
+        assert!(data.get("title").is_none());
+        assert_eq!(
+            unpublished.seen_by(Some(author_id)).unwrap().0,
+            PostEventKind::Updated
+        );
+        assert!(edited.seen_by(None).is_none());
+    }
+}
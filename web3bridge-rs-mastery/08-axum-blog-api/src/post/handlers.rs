@@ -1,127 +1,1011 @@
 use crate::app::state::SharedState;
-use crate::error::AppError;
-use crate::post::models::{CreatePostRequest, PostFilter, PostListResponse, PostResponse, UpdatePostRequest};
-use crate::post::queries;
+use crate::auth::AuthAuthor;
+use crate::error::{AppError, ErrorCode, ErrorResponse, FieldErrors};
+use crate::extract::{Path, Query};
+use crate::markdown;
+use crate::pagination::Cursor;
+use crate::payments;
+use crate::post::events::Delivery;
+use crate::post::models::{
+    CreatePostRequest, Post, PostCriteria, PostFilter, PostFormatQuery, PostListResponse,
+    PostResponse, PostStatus, PostStreamFilter, UpdatePostRequest,
+};
+use crate::post::repository::{NewPost, PostChanges};
+use crate::response::MessageResponse;
+use crate::tag::normalize_tags;
+use crate::validation::{ValidJson, parse_uuid};
 use axum::{
     Json,
-    extract::{Path, Query, State},
-    http::StatusCode,
-    response::IntoResponse,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
 };
-use serde_json::json;
+use chrono::Utc;
+use futures_util::{Stream, StreamExt};
+use std::convert::Infallible;
 use uuid::Uuid;
 
+const LAST_EVENT_ID: &str = "last-event-id";
+
+#[utoipa::path(
+    post,
+    path = "/posts",
+    tag = "posts",
+    request_body = CreatePostRequest,
+    security(("bearer" = [])),
+    responses(
+        (status = 201, description = "Post created", body = PostResponse, headers(("ETag" = String))),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not the owner", body = ErrorResponse),
+        (status = 422, description = "Invalid input", body = ErrorResponse),
+    )
+)]
 pub async fn create_post(
     State(state): State<SharedState>,
-    Json(payload): Json<CreatePostRequest>,
+    auth: AuthAuthor,
+    ValidJson(payload): ValidJson<CreatePostRequest>,
 ) -> Result<impl IntoResponse, AppError> {
-    let title = payload.title.trim();
-    if title.is_empty() {
-        return Err(AppError::UnProcessableEntity {
-            field: "title".to_string(),
-            message: "Title cannot be empty".to_string(),
-        });
-    }
+    let author_id = payload.author_id.unwrap_or(auth.author_id);
 
-    let content = payload.content.trim();
-    if content.is_empty() {
-        return Err(AppError::UnProcessableEntity {
-            field: "content".to_string(),
-            message: "Content cannot be empty".to_string(),
-        });
-    }
+    let mut errors = FieldErrors::default();
+    // Clients that predate post statuses expect new posts to go live immediately.
+    let status = payload.status.unwrap_or(PostStatus::Published);
+    let published_at =
+        errors.capture(status.published_at(payload.published_at, None, Utc::now()))?;
+    let tags = errors.capture(normalize_tags(&payload.tags))?;
+    errors.finish()?;
 
-    let author_id = parse_uuid_field("author_id", &payload.author_id)?;
+    // Posts are always written as the authenticated author; an explicit author_id must agree.
+    auth.ensure_owner(author_id)?;
+    let content_html = markdown::render(&payload.content);
 
-    let post = queries::insert_post(&state, title, content, author_id).await?;
-    Ok((StatusCode::CREATED, Json(PostResponse::from(post))))
+    if !state.authors.exists(author_id).await? {
+        return Err(AppError::invalid(
+            "author_id",
+            ErrorCode::PostUnknownAuthor,
+            "Author does not exist",
+        ));
+    }
+
+    let post = state
+        .posts
+        .insert(NewPost {
+            title: &payload.title,
+            content: &payload.content,
+            content_html: &content_html,
+            author_id,
+            status,
+            published_at,
+            is_premium: payload.is_premium.unwrap_or(false),
+            tags: &tags,
+        })
+        .await?;
+    Ok((
+        StatusCode::CREATED,
+        post.etag(),
+        Json(PostResponse::from(post)),
+    ))
 }
 
+#[utoipa::path(
+    get,
+    path = "/posts",
+    tag = "posts",
+    params(PostFilter),
+    security((), ("bearer" = [])),
+    responses(
+        (status = 200, description = "One page of posts; premium bodies are empty unless entitled", body = PostListResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 422, description = "Invalid input", body = ErrorResponse),
+    )
+)]
 pub async fn get_posts(
     State(state): State<SharedState>,
+    viewer: Option<AuthAuthor>,
     Query(filter): Query<PostFilter>,
 ) -> Result<Json<PostListResponse>, AppError> {
     let limit = filter.limit.unwrap_or(20).clamp(1, 100);
     let offset = filter.offset.unwrap_or(0).max(0);
 
     let author_id = match filter.author_id {
-        Some(raw) => Some(parse_uuid_field("author_id", &raw)?),
+        Some(raw) => Some(parse_uuid("author_id", &raw)?),
         None => None,
     };
 
-    let posts = queries::list_posts(&state, author_id, limit, offset).await?;
-    let total = posts.len();
-    let data = posts.into_iter().map(PostResponse::from).collect();
+    let search = filter
+        .q
+        .as_deref()
+        .map(str::trim)
+        .filter(|q| !q.is_empty())
+        .map(str::to_string);
+
+    let cursor = filter.cursor.as_deref().map(Cursor::decode).transpose()?;
+    if cursor.is_some() && search.is_some() {
+        return Err(AppError::invalid(
+            "cursor",
+            ErrorCode::PaginationCursorWithSearch,
+            "Cursor cannot be combined with q; use offset instead",
+        ));
+    }
+
+    let include_drafts = filter.include_drafts.unwrap_or(false);
+    let include_deleted = filter.include_deleted.unwrap_or(false);
+    if (include_drafts || include_deleted) && viewer.is_none() {
+        return Err(AppError::Unauthorized(
+            ErrorCode::AuthSignInRequired,
+            "Sign in to list drafts or deleted posts".to_string(),
+        ));
+    }
+
+    let viewer_id = viewer.map(|viewer| viewer.author_id);
+    let criteria = PostCriteria {
+        author_id,
+        search,
+        include_drafts,
+        include_deleted,
+        viewer_id,
+        tags: normalize_tags(&filter.tag)?,
+        tag_match: filter.tag_match.unwrap_or_default(),
+    };
+
+    let mut page = state.posts.list(&criteria, cursor, limit, offset).await?;
+
+    // Premium posts stay listed so readers can discover them, but without their body.
+    let locked = |post: &Post| post.is_premium && viewer_id != Some(post.author_id);
+    if page.items.iter().any(locked) && !is_subscriber(&state, viewer_id).await? {
+        page.items.iter_mut().filter(|post| locked(post)).for_each(Post::redact);
+    }
+
+    let total = if filter.include_total.unwrap_or(true) {
+        Some(state.posts.count(&criteria).await?)
+    } else {
+        None
+    };
+    let data = page.items.into_iter().map(PostResponse::from).collect();
+    let next_cursor = page.next_cursor.map(|cursor| cursor.encode());
 
-    Ok(Json(PostListResponse { data, total }))
+    Ok(Json(PostListResponse {
+        data,
+        total,
+        limit,
+        offset: if cursor.is_some() { 0 } else { offset },
+        has_more: page.has_more,
+        next_cursor,
+    }))
 }
 
+#[utoipa::path(
+    get,
+    path = "/posts/{id}",
+    tag = "posts",
+    params(
+        ("id" = String, Path, description = "Post UUID"),
+        PostFormatQuery,
+        ("If-None-Match" = Option<String>, Header, description = "ETag of a cached copy"),
+    ),
+    security((), ("bearer" = [])),
+    responses(
+        (status = 200, description = "The post", body = PostResponse, headers(("ETag" = String))),
+        (status = 304, description = "The cached copy is current"),
+        (status = 403, description = "Premium post without an active subscription", body = ErrorResponse),
+        (status = 404, description = "Post not found", body = ErrorResponse),
+        (status = 422, description = "Invalid input", body = ErrorResponse),
+    )
+)]
 pub async fn get_post_by_id(
     State(state): State<SharedState>,
+    viewer: Option<AuthAuthor>,
+    headers: HeaderMap,
     Path(id): Path<String>,
-) -> Result<Json<PostResponse>, AppError> {
-    let post_id = parse_uuid_field("id", &id)?;
-    let post = queries::find_post_by_id(&state, post_id).await?;
-    Ok(Json(PostResponse::from(post)))
+    Query(query): Query<PostFormatQuery>,
+) -> Result<Response, AppError> {
+    let post_id = parse_uuid("id", &id)?;
+    let post = state.posts.find_by_id(post_id).await?;
+    let viewer_id = viewer.map(|viewer| viewer.author_id);
+
+    if !post.is_visible_to(viewer_id) {
+        return Err(AppError::NotFound(
+            ErrorCode::PostNotFound,
+            format!("Post not found: {post_id}"),
+        ));
+    }
+
+    if post.is_premium
+        && viewer_id != Some(post.author_id)
+        && !is_subscriber(&state, viewer_id).await?
+    {
+        return Err(AppError::Forbidden(
+            ErrorCode::PostSubscriptionRequired,
+            "This post requires an active subscription".to_string(),
+        ));
+    }
+
+    // Checked after the access rules, so a 304 never confirms a post the viewer cannot read.
+    let format = query.format.unwrap_or_default();
+    let etag = post.etag_for(format);
+    if etag.is_fresh(&headers) {
+        return Ok(etag.not_modified());
+    }
+    Ok((etag, Json(PostResponse::with_format(post, format))).into_response())
 }
 
+#[utoipa::path(
+    patch,
+    path = "/posts/{id}",
+    tag = "posts",
+    params(
+        ("id" = String, Path, description = "Post UUID"),
+        ("If-Match" = String, Header, description = "ETag from the last read"),
+    ),
+    request_body = UpdatePostRequest,
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Updated post", body = PostResponse, headers(("ETag" = String))),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not the owner", body = ErrorResponse),
+        (status = 404, description = "Post not found", body = ErrorResponse),
+        (status = 412, description = "Changed since the given ETag", body = ErrorResponse),
+        (status = 422, description = "Invalid input", body = ErrorResponse),
+        (status = 428, description = "If-Match is missing", body = ErrorResponse),
+    )
+)]
 pub async fn patch_post(
     State(state): State<SharedState>,
+    auth: AuthAuthor,
+    headers: HeaderMap,
     Path(id): Path<String>,
-    Json(payload): Json<UpdatePostRequest>,
-) -> Result<Json<PostResponse>, AppError> {
-    let post_id = parse_uuid_field("id", &id)?;
-
-    let title = payload.title.as_deref().map(str::trim);
-    if let Some(title) = title {
-        if title.is_empty() {
-            return Err(AppError::UnProcessableEntity {
-                field: "title".to_string(),
-                message: "Title cannot be empty".to_string(),
-            });
-        }
-    }
+    ValidJson(payload): ValidJson<UpdatePostRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let post_id = parse_uuid("id", &id)?;
+    let current = ensure_post_owner(&state, auth, post_id).await?;
+    current.etag().require_if_match(&headers)?;
 
-    let content = payload.content.as_deref().map(str::trim);
-    if let Some(content) = content {
-        if content.is_empty() {
-            return Err(AppError::UnProcessableEntity {
-                field: "content".to_string(),
-                message: "Content cannot be empty".to_string(),
-            });
-        }
+    let mut errors = FieldErrors::default();
+    let title = payload.title.as_deref();
+    let content = payload.content.as_deref();
+    if title.is_none()
+        && content.is_none()
+        && payload.status.is_none()
+        && payload.published_at.is_none()
+        && payload.tags.is_none()
+        && payload.is_premium.is_none()
+    {
+        errors.add(
+            "body",
+            ErrorCode::ValidationEmptyUpdate,
+            "At least one of title, content, status, published_at, tags or is_premium must be provided",
+        );
     }
 
-    if title.is_none() && content.is_none() {
-        return Err(AppError::UnProcessableEntity {
-            field: "body".to_string(),
-            message: "At least one of title or content must be provided".to_string(),
-        });
-    }
+    let tags = errors.capture(payload.tags.as_deref().map(normalize_tags).transpose())?;
+    let status = payload.status.unwrap_or(current.status);
+    // Only a change to the schedule is checked against the clock, so editing a scheduled
+    // post that is due but not yet picked up by the publisher still works.
+    let published_at = if payload.status.is_some() || payload.published_at.is_some() {
+        errors.capture(status.published_at(
+            payload.published_at,
+            current.published_at,
+            Utc::now(),
+        ))?
+    } else {
+        current.published_at
+    };
+    errors.finish()?;
+
+    let content_html = content.map(markdown::render);
 
-    let post = queries::update_post(&state, post_id, title, content).await?;
-    Ok(Json(PostResponse::from(post)))
+    let post = state
+        .posts
+        .update(
+            post_id,
+            current.version,
+            PostChanges {
+                title,
+                content,
+                content_html: content_html.as_deref(),
+                status,
+                published_at,
+                is_premium: payload.is_premium,
+                tags: tags.as_deref(),
+                restored_from: None,
+            },
+        )
+        .await?;
+    Ok((post.etag(), Json(PostResponse::from(post))))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/posts/{id}",
+    tag = "posts",
+    params(
+        ("id" = String, Path, description = "Post UUID"),
+        ("If-Match" = String, Header, description = "ETag from the last read"),
+    ),
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Post soft-deleted", body = MessageResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not the owner", body = ErrorResponse),
+        (status = 404, description = "Post not found", body = ErrorResponse),
+        (status = 412, description = "Changed since the given ETag", body = ErrorResponse),
+        (status = 428, description = "If-Match is missing", body = ErrorResponse),
+    )
+)]
 pub async fn delete_post(
     State(state): State<SharedState>,
+    auth: AuthAuthor,
+    headers: HeaderMap,
     Path(id): Path<String>,
 ) -> Result<impl IntoResponse, AppError> {
-    let post_id = parse_uuid_field("id", &id)?;
-    queries::delete_post(&state, post_id).await?;
+    let post_id = parse_uuid("id", &id)?;
+    let current = ensure_post_owner(&state, auth, post_id).await?;
+    current.etag().require_if_match(&headers)?;
+    state.posts.delete(post_id, current.version).await?;
 
     Ok((
         StatusCode::OK,
-        Json(json!({
-            "status": "ok",
-            "message": "Post deleted successfully"
-        })),
+        Json(MessageResponse::ok("Post deleted successfully")),
     ))
 }
 
-fn parse_uuid_field(field: &str, value: &str) -> Result<Uuid, AppError> {
-    Uuid::parse_str(value).map_err(|_| AppError::UnProcessableEntity {
-        field: field.to_string(),
-        message: "Invalid UUID".to_string(),
-    })
+#[utoipa::path(
+    get,
+    path = "/posts/stream",
+    tag = "posts",
+    params(
+        PostStreamFilter,
+        ("Last-Event-ID" = Option<String>, Header, description = "Id of the last event received, to resume after it"),
+    ),
+    security((), ("bearer" = [])),
+    responses(
+        (status = 200, description = "Server-sent `created`, `updated` and `deleted` events carrying the post's outbox payload; `reset` when some were missed", content_type = "text/event-stream", body = String),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 422, description = "Invalid input", body = ErrorResponse),
+    )
+)]
+pub async fn stream_posts(
+    State(state): State<SharedState>,
+    viewer: Option<AuthAuthor>,
+    headers: HeaderMap,
+    Query(filter): Query<PostStreamFilter>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    let author_id = match filter.author_id {
+        Some(raw) => Some(parse_uuid("author_id", &raw)?),
+        None => None,
+    };
+    let last_event_id = match headers.get(LAST_EVENT_ID) {
+        Some(value) => Some(
+            value
+                .to_str()
+                .ok()
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .ok_or_else(|| {
+                    AppError::invalid(
+                        "Last-Event-ID",
+                        ErrorCode::ValidationInvalidNumber,
+                        "Last-Event-ID must be an event id from this stream",
+                    )
+                })?,
+        ),
+        None => None,
+    };
+
+    let viewer_id = viewer.map(|viewer| viewer.author_id);
+    let stream =
+        state
+            .post_events
+            .subscribe(last_event_id)
+            .filter_map(move |delivery| async move {
+                let event = match delivery {
+                    Delivery::Event(event)
+                        if author_id.is_none_or(|author_id| author_id == event.author_id) =>
+                    {
+                        let (kind, data) = event.seen_by(viewer_id)?;
+                        Event::default()
+                            .id(event.id.to_string())
+                            .event(kind.as_str())
+                            .data(data.to_string())
+                    }
+                    Delivery::Event(_) => return None,
+                    // Clients should refetch whatever they show, then carry on from here.
+                    Delivery::Missed => Event::default().event("reset").data("{}"),
+                };
+                Some(Ok(event))
+            });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+async fn ensure_post_owner(
+    state: &SharedState,
+    auth: AuthAuthor,
+    post_id: Uuid,
+) -> Result<Post, AppError> {
+    let post = state.posts.find_by_id(post_id).await?;
+
+    // Someone else's unpublished post should look exactly like a missing one.
+    if !post.is_visible_to(Some(auth.author_id)) {
+        return Err(AppError::NotFound(
+            ErrorCode::PostNotFound,
+            format!("Post not found: {post_id}"),
+        ));
+    }
+
+    auth.ensure_owner(post.author_id)?;
+    Ok(post)
+}
+
+async fn is_subscriber(state: &SharedState, viewer_id: Option<Uuid>) -> Result<bool, AppError> {
+    match viewer_id {
+        Some(author_id) => payments::has_active_subscription(state, author_id).await,
+        None => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::router::create_router;
+    use crate::app::state::{AppState, SharedState};
+    use crate::app::test_support::{current_etag, send, send_with_headers, signed_in_author};
+    use crate::payments::InMemorySubscriptionRepository;
+    use axum::{
+        body::Body,
+        http::{
+            Method, Request, StatusCode,
+            header::{ETAG, IF_MATCH, IF_NONE_MATCH},
+        },
+    };
+    use chrono::{Duration, Utc};
+    use serde_json::{Value, json};
+    use std::sync::Arc;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    fn titles(listing: &Value) -> Vec<&str> {
+        listing["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|post| post["title"].as_str().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn created_post_is_public_once_published() {
+        let state = AppState::for_tests_in_memory();
+        let (author_id, token) = signed_in_author(&state, "writer@example.com").await;
+        let body = json!({ "title": " Hello ", "content": "World", "tags": ["Rust", "axum"] });
+
+        let (status, created) =
+            send(&state, Method::POST, "/posts", Some(&token), Some(body)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(created["title"], "Hello");
+        assert_eq!(created["author_id"], author_id.to_string());
+        assert_eq!(created["status"], "published");
+        assert_eq!(created["tags"], json!(["axum", "rust"]));
+
+        let uri = format!("/posts/{}", created["id"].as_str().unwrap());
+        let (status, fetched) = send(&state, Method::GET, &uri, None, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(fetched["id"], created["id"]);
+    }
+
+    #[tokio::test]
+    async fn markdown_is_served_as_source_or_sanitized_html() {
+        let state = AppState::for_tests_in_memory();
+        let (_, token) = signed_in_author(&state, "writer@example.com").await;
+        let content = "# Hi\n\nSome **bold** text <img src=\"x.png\" onerror=\"alert(1)\">";
+        let body = json!({ "title": "Hello", "content": content });
+
+        let (_, created) = send(&state, Method::POST, "/posts", Some(&token), Some(body)).await;
+        assert_eq!(created["content"], content);
+        assert_eq!(created["content_format"], "markdown");
+        assert_eq!(created["word_count"], 4);
+        assert_eq!(created["reading_time_minutes"], 1);
+
+        let uri = format!("/posts/{}?format=html", created["id"].as_str().unwrap());
+        let (status, html) = send(&state, Method::GET, &uri, None, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(html["content_format"], "html");
+        assert_eq!(
+            html["content"],
+            "<h1>Hi</h1>\n<p>Some <strong>bold</strong> text <img src=\"x.png\"></p>\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn premium_bodies_are_kept_for_the_author_and_subscribers() {
+        let subscriptions = Arc::new(InMemorySubscriptionRepository::default());
+        let mut state = Arc::into_inner(AppState::for_tests_in_memory()).unwrap();
+        state.subscriptions = subscriptions.clone();
+        let state = SharedState::new(state);
+        let (_, token) = signed_in_author(&state, "writer@example.com").await;
+        let (reader_id, reader_token) = signed_in_author(&state, "reader@example.com").await;
+        let body = json!({ "title": "Paid", "content": "members only", "is_premium": true });
+
+        let (_, created) = send(&state, Method::POST, "/posts", Some(&token), Some(body)).await;
+        let uri = format!("/posts/{}", created["id"].as_str().unwrap());
+        let (status, error) = send(&state, Method::GET, &uri, Some(&reader_token), None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(error["code"], "post.subscription_required");
+        let (_, listing) = send(&state, Method::GET, "/posts", None, None).await;
+        assert_eq!(listing["data"][0]["content"], "");
+
+        let (status, _) = send(&state, Method::GET, &uri, Some(&token), None).await;
+        assert_eq!(status, StatusCode::OK);
+
+        subscriptions.subscribe(reader_id);
+        let (status, post) = send(&state, Method::GET, &uri, Some(&reader_token), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(post["content"], "members only");
+        let (_, listing) = send(&state, Method::GET, "/posts", Some(&reader_token), None).await;
+        assert_eq!(listing["data"][0]["content"], "members only");
+    }
+
+    #[tokio::test]
+    async fn a_due_scheduled_post_can_be_edited_before_it_is_published() {
+        let state = AppState::for_tests_in_memory();
+        let (_, token) = signed_in_author(&state, "writer@example.com").await;
+        let at = Utc::now() + Duration::milliseconds(300);
+        let body =
+            json!({ "title": "Soon", "content": "Hi", "status": "scheduled", "published_at": at });
+
+        let (status, created) =
+            send(&state, Method::POST, "/posts", Some(&token), Some(body)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        // Due now, but the publisher has not run.
+        tokio::time::sleep(std::time::Duration::from_millis(400)).await;
+
+        let uri = format!("/posts/{}", created["id"].as_str().unwrap());
+        let etag = current_etag(&state, &uri, Some(&token)).await;
+        let (status, _, edited) = send_with_headers(
+            &state,
+            Method::PATCH,
+            &uri,
+            Some(&token),
+            &[(IF_MATCH, &etag)],
+            Some(json!({ "title": "Soon, edited" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(edited["status"], "scheduled");
+        assert_eq!(edited["published_at"], created["published_at"]);
+
+        // Naming the status re-checks the schedule, which has passed.
+        let etag = current_etag(&state, &uri, Some(&token)).await;
+        let (status, _, error) = send_with_headers(
+            &state,
+            Method::PATCH,
+            &uri,
+            Some(&token),
+            &[(IF_MATCH, &etag)],
+            Some(json!({ "status": "scheduled" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error["code"], "post.invalid_schedule");
+    }
+
+    #[tokio::test]
+    async fn posting_needs_a_token_and_an_existing_author() {
+        let state = AppState::for_tests_in_memory();
+        let body = json!({ "title": "Hello", "content": "World" });
+
+        let (status, error) = send(&state, Method::POST, "/posts", None, Some(body.clone())).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(error["code"], "auth.missing_token");
+
+        // A token for an account that has since been deleted.
+        let token = state.jwt.issue(Uuid::new_v4()).unwrap();
+        let (status, error) = send(&state, Method::POST, "/posts", Some(&token), Some(body)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error["code"], "post.unknown_author");
+        assert_eq!(error["errors"][0]["field"], "author_id");
+    }
+
+    #[tokio::test]
+    async fn titles_are_trimmed_but_markdown_is_kept_as_written() {
+        let state = AppState::for_tests_in_memory();
+        let (_, token) = signed_in_author(&state, "writer@example.com").await;
+
+        let body = json!({ "title": "Hello", "content": "  \n ", "author_id": "42" });
+        let (status, error) = send(&state, Method::POST, "/posts", Some(&token), Some(body)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error["errors"][0]["field"], "author_id");
+        assert_eq!(error["errors"][0]["code"], "validation.invalid_uuid");
+
+        let body = json!({ "title": "Hello", "content": "  \n " });
+        let (status, error) = send(&state, Method::POST, "/posts", Some(&token), Some(body)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error["errors"][0]["field"], "content");
+        assert_eq!(error["errors"][0]["code"], "validation.required");
+
+        let content = "    fn main() {}\n";
+        let body = json!({ "title": " Hello ", "content": content });
+        let (status, created) =
+            send(&state, Method::POST, "/posts", Some(&token), Some(body)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(created["title"], "Hello");
+        assert_eq!(created["content"], content);
+    }
+
+    #[tokio::test]
+    async fn drafts_are_hidden_from_everyone_but_their_author() {
+        let state = AppState::for_tests_in_memory();
+        let (_, token) = signed_in_author(&state, "writer@example.com").await;
+        let (_, reader_token) = signed_in_author(&state, "reader@example.com").await;
+        let draft = json!({ "title": "Draft", "content": "WIP", "status": "draft" });
+        let (_, draft) = send(&state, Method::POST, "/posts", Some(&token), Some(draft)).await;
+        let public = json!({ "title": "Public", "content": "Done" });
+        send(&state, Method::POST, "/posts", Some(&token), Some(public)).await;
+
+        let uri = format!("/posts/{}", draft["id"].as_str().unwrap());
+        let (status, _) = send(&state, Method::GET, &uri, Some(&reader_token), None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = send(&state, Method::GET, &uri, Some(&token), None).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (_, listing) = send(&state, Method::GET, "/posts", None, None).await;
+        assert_eq!(titles(&listing), ["Public"]);
+        assert_eq!(listing["total"], 1);
+
+        let (status, _) = send(
+            &state,
+            Method::GET,
+            "/posts?include_drafts=true",
+            None,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (_, listing) = send(
+            &state,
+            Method::GET,
+            "/posts?include_drafts=true",
+            Some(&token),
+            None,
+        )
+        .await;
+        assert_eq!(titles(&listing), ["Public", "Draft"]);
+    }
+
+    #[tokio::test]
+    async fn listing_filters_by_tag_and_search() {
+        let state = AppState::for_tests_in_memory();
+        let (_, token) = signed_in_author(&state, "writer@example.com").await;
+        for (title, tags) in [
+            ("Async Rust", vec!["rust", "async"]),
+            ("Plain Rust", vec!["rust"]),
+            ("Go", vec!["go"]),
+        ] {
+            let body = json!({ "title": title, "content": "Body", "tags": tags });
+            send(&state, Method::POST, "/posts", Some(&token), Some(body)).await;
+        }
+
+        let (_, listing) = send(
+            &state,
+            Method::GET,
+            "/posts?tag=rust&tag=async&tag_match=all",
+            None,
+            None,
+        )
+        .await;
+        assert_eq!(titles(&listing), ["Async Rust"]);
+
+        let (_, listing) = send(&state, Method::GET, "/posts?tag=go&tag=async", None, None).await;
+        assert_eq!(titles(&listing), ["Go", "Async Rust"]);
+
+        let (_, listing) = send(&state, Method::GET, "/posts?q=plain", None, None).await;
+        assert_eq!(titles(&listing), ["Plain Rust"]);
+    }
+
+    #[tokio::test]
+    async fn only_the_owner_can_edit_and_deleted_posts_disappear() {
+        let state = AppState::for_tests_in_memory();
+        let (_, token) = signed_in_author(&state, "writer@example.com").await;
+        let (_, other_token) = signed_in_author(&state, "other@example.com").await;
+        let body = json!({ "title": "Hello", "content": "World" });
+        let (_, created) = send(&state, Method::POST, "/posts", Some(&token), Some(body)).await;
+        let uri = format!("/posts/{}", created["id"].as_str().unwrap());
+
+        let change = json!({ "title": "Edited", "status": "draft" });
+        let (status, _) = send(
+            &state,
+            Method::PATCH,
+            &uri,
+            Some(&other_token),
+            Some(change.clone()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let etag = current_etag(&state, &uri, None).await;
+        let if_match = [(IF_MATCH, etag.as_str())];
+        let (status, _, _) = send_with_headers(
+            &state,
+            Method::PATCH,
+            &uri,
+            Some(&token),
+            &if_match,
+            Some(json!({})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        let (status, headers, updated) = send_with_headers(
+            &state,
+            Method::PATCH,
+            &uri,
+            Some(&token),
+            &if_match,
+            Some(change),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(updated["title"], "Edited");
+        assert!(updated["published_at"].is_null());
+
+        let etag = headers[ETAG].to_str().unwrap();
+        let (status, _, _) = send_with_headers(
+            &state,
+            Method::DELETE,
+            &uri,
+            Some(&token),
+            &[(IF_MATCH, etag)],
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = send(&state, Method::GET, &uri, Some(&token), None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (_, listing) = send(
+            &state,
+            Method::GET,
+            "/posts?include_deleted=true&include_drafts=true",
+            Some(&token),
+            None,
+        )
+        .await;
+        assert_eq!(titles(&listing), ["Edited"]);
+    }
+
+    #[tokio::test]
+    async fn stale_writes_are_refused_and_fresh_reads_are_not_resent() {
+        let state = AppState::for_tests_in_memory();
+        let (_, token) = signed_in_author(&state, "writer@example.com").await;
+        let body = json!({ "title": "Hello", "content": "World" });
+        let (_, headers, created) = send_with_headers(
+            &state,
+            Method::POST,
+            "/posts",
+            Some(&token),
+            &[],
+            Some(body),
+        )
+        .await;
+        let uri = format!("/posts/{}", created["id"].as_str().unwrap());
+        let etag = headers[ETAG].to_str().unwrap().to_string();
+        assert_eq!(current_etag(&state, &uri, None).await, etag);
+
+        let if_none_match = [(IF_NONE_MATCH, etag.as_str())];
+        let (status, _, body) =
+            send_with_headers(&state, Method::GET, &uri, None, &if_none_match, None).await;
+        assert_eq!(status, StatusCode::NOT_MODIFIED);
+        assert!(body.is_null());
+
+        let (status, _) = send(&state, Method::DELETE, &uri, Some(&token), None).await;
+        assert_eq!(status, StatusCode::PRECONDITION_REQUIRED);
+
+        // Two editors read the same version; only the first write lands.
+        let if_match = [(IF_MATCH, etag.as_str())];
+        let first = json!({ "title": "First" });
+        let (status, _, _) = send_with_headers(
+            &state,
+            Method::PATCH,
+            &uri,
+            Some(&token),
+            &if_match,
+            Some(first),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let second = json!({ "title": "Second" });
+        let (status, _, error) = send_with_headers(
+            &state,
+            Method::PATCH,
+            &uri,
+            Some(&token),
+            &if_match,
+            Some(second),
+        )
+        .await;
+        assert_eq!(status, StatusCode::PRECONDITION_FAILED);
+        assert_eq!(error["code"], "request.precondition_failed");
+
+        let (status, _, fetched) =
+            send_with_headers(&state, Method::GET, &uri, None, &if_none_match, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(fetched["title"], "First");
+    }
+
+    #[tokio::test]
+    async fn each_format_is_revalidated_with_its_own_etag() {
+        let state = AppState::for_tests_in_memory();
+        let (_, token) = signed_in_author(&state, "writer@example.com").await;
+        let body = json!({ "title": "Hello", "content": "**World**" });
+        let (_, created) = send(&state, Method::POST, "/posts", Some(&token), Some(body)).await;
+        let uri = format!("/posts/{}", created["id"].as_str().unwrap());
+        let html_uri = format!("{uri}?format=html");
+        let markdown_tag = current_etag(&state, &uri, None).await;
+        let html_tag = current_etag(&state, &html_uri, None).await;
+        assert_ne!(markdown_tag, html_tag);
+
+        for (uri, tag) in [(&html_uri, &markdown_tag), (&uri, &html_tag)] {
+            let if_none_match = [(IF_NONE_MATCH, tag.as_str())];
+            let (status, _, fetched) =
+                send_with_headers(&state, Method::GET, uri, None, &if_none_match, None).await;
+            assert_eq!(status, StatusCode::OK, "{uri}");
+            assert_eq!(fetched["id"], created["id"]);
+        }
+
+        let if_none_match = [(IF_NONE_MATCH, html_tag.as_str())];
+        let (status, _, _) =
+            send_with_headers(&state, Method::GET, &html_uri, None, &if_none_match, None).await;
+        assert_eq!(status, StatusCode::NOT_MODIFIED);
+    }
+
+    // Reads `/posts/stream` to the end, so `post_events` must be closed first. Each event
+    // comes back as its id, name and data.
+    async fn read_stream(
+        state: &SharedState,
+        uri: &str,
+        token: Option<&str>,
+        last_event_id: Option<&str>,
+    ) -> (StatusCode, Vec<(Option<u64>, String, Value)>) {
+        let mut request = Request::builder().uri(uri);
+        if let Some(token) = token {
+            request = request.header("authorization", format!("Bearer {token}"));
+        }
+        if let Some(last_event_id) = last_event_id {
+            request = request.header("last-event-id", last_event_id);
+        }
+        let request = request.body(Body::empty()).unwrap();
+
+        let response = create_router(state.clone()).oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let text = String::from_utf8(bytes.to_vec()).unwrap();
+        if status != StatusCode::OK {
+            return (status, Vec::new());
+        }
+
+        let events = text
+            .split("\n\n")
+            .filter(|frame| !frame.trim().is_empty())
+            .map(|frame| {
+                let field = |name: &str| {
+                    frame
+                        .lines()
+                        .find_map(|line| line.strip_prefix(name)?.strip_prefix(": "))
+                };
+                (
+                    field("id").map(|id| id.parse().unwrap()),
+                    field("event").unwrap().to_string(),
+                    serde_json::from_str(field("data").unwrap()).unwrap(),
+                )
+            })
+            .collect();
+        (status, events)
+    }
+
+    #[tokio::test]
+    async fn committed_writes_are_streamed_and_can_be_resumed() {
+        let state = AppState::for_tests_in_memory();
+        let (author_id, token) = signed_in_author(&state, "writer@example.com").await;
+        let (_, other_token) = signed_in_author(&state, "other@example.com").await;
+
+        let public = json!({ "title": "Public", "content": "Done" });
+        let (_, public) = send(&state, Method::POST, "/posts", Some(&token), Some(public)).await;
+        let draft = json!({ "title": "Draft", "content": "WIP", "status": "draft" });
+        send(&state, Method::POST, "/posts", Some(&token), Some(draft)).await;
+        let elsewhere = json!({ "title": "Elsewhere", "content": "Hi" });
+        send(
+            &state,
+            Method::POST,
+            "/posts",
+            Some(&other_token),
+            Some(elsewhere),
+        )
+        .await;
+        let uri = format!("/posts/{}", public["id"].as_str().unwrap());
+        let etag = current_etag(&state, &uri, None).await;
+        let (status, _, _) = send_with_headers(
+            &state,
+            Method::DELETE,
+            &uri,
+            Some(&token),
+            &[(IF_MATCH, &etag)],
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let shelved = json!({ "title": "Shelved", "content": "Soon" });
+        let (_, shelved) = send(&state, Method::POST, "/posts", Some(&token), Some(shelved)).await;
+        let uri = format!("/posts/{}", shelved["id"].as_str().unwrap());
+        let etag = current_etag(&state, &uri, None).await;
+        let (status, _, _) = send_with_headers(
+            &state,
+            Method::PATCH,
+            &uri,
+            Some(&token),
+            &[(IF_MATCH, &etag)],
+            Some(json!({ "status": "draft" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        state.post_events.close();
+
+        let summary = |events: &[(Option<u64>, String, Value)]| -> Vec<(String, String)> {
+            events
+                .iter()
+                .map(|(_, name, data)| {
+                    let title = data["title"].as_str().unwrap_or_default();
+                    (name.clone(), title.to_string())
+                })
+                .collect()
+        };
+        let mine = format!("/posts/stream?author_id={author_id}");
+
+        // Anonymous readers do not see the draft, and see the unpublished post go away
+        // without learning anything about it as a draft.
+        let (status, events) = read_stream(&state, &mine, None, Some("0")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            summary(&events),
+            [
+                ("created".to_string(), "Public".to_string()),
+                ("deleted".to_string(), "Public".to_string()),
+                ("created".to_string(), "Shelved".to_string()),
+                ("deleted".to_string(), String::new()),
+            ]
+        );
+        assert_eq!(events[0].2["author_id"], author_id.to_string());
+        assert_eq!(events[3].2["id"], shelved["id"]);
+
+        let (_, events) = read_stream(&state, &mine, Some(&token), Some("0")).await;
+        assert_eq!(
+            events.iter().map(|(id, _, _)| *id).collect::<Vec<_>>(),
+            [Some(1), Some(2), Some(4), Some(5), Some(6)]
+        );
+        let (_, events) = read_stream(&state, &mine, Some(&token), Some("2")).await;
+        assert_eq!(
+            summary(&events),
+            [
+                ("deleted".to_string(), "Public".to_string()),
+                ("created".to_string(), "Shelved".to_string()),
+                ("updated".to_string(), "Shelved".to_string()),
+            ]
+        );
+
+        // An id this process never sent, as after a restart.
+        let (_, events) = read_stream(&state, "/posts/stream", None, Some("99")).await;
+        assert_eq!(events.len(), 1);
+        assert_eq!((events[0].0, events[0].1.as_str()), (None, "reset"));
+
+        // Without Last-Event-ID only new events are sent.
+        let (_, events) = read_stream(&state, "/posts/stream", None, None).await;
+        assert!(events.is_empty());
+
+        let (status, _) = read_stream(&state, "/posts/stream", None, Some("latest")).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let (status, _) = read_stream(&state, "/posts/stream?author_id=42", None, None).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    }
 }
@@ -0,0 +1,292 @@
+use crate::error::{AppError, ErrorCode};
+use crate::pagination::{Cursor, Page};
+use crate::post::events::{PostEventKind, PostEvents};
+use crate::post::models::{Post, PostCriteria, PostStatus};
+use crate::post::repository::{NewPost, PostChanges, PostRepository};
+use crate::revision::models::PostRevision;
+use crate::tag::models::TagMatch;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// Keeps posts in a `Vec` so handler tests run without a database.
+///
+/// Search is a case-insensitive substring match on title and content, without
+/// ranking or snippets; outbox events are not recorded, but writes are published
+/// to `events` like the SQL repository's.
+#[derive(Debug, Default)]
+pub struct InMemoryPostRepository {
+    posts: Mutex<Vec<Post>>,
+    revisions: Mutex<Vec<PostRevision>>,
+    events: PostEvents,
+}
+
+impl InMemoryPostRepository {
+    pub fn new(events: PostEvents) -> Self {
+        Self {
+            events,
+            ..Self::default()
+        }
+    }
+
+    fn record_revision(&self, post: &Post, restored_from: Option<i32>) {
+        let mut revisions = self.revisions.lock().unwrap();
+        let latest = revisions
+            .iter()
+            .filter(|revision| revision.post_id == post.id)
+            .map(|revision| revision.revision)
+            .max()
+            .unwrap_or(0);
+
+        revisions.push(PostRevision {
+            post_id: post.id,
+            revision: latest + 1,
+            title: post.title.clone(),
+            content: post.content.clone(),
+            restored_from,
+            created_at: post.updated_at,
+        });
+    }
+}
+
+// Mirrors `push_criteria` in the SQL queries.
+fn matches(post: &Post, criteria: &PostCriteria) -> bool {
+    let owned = criteria.viewer_id.is_some() && criteria.viewer_id == Some(post.author_id);
+
+    let status_ok = post.status == PostStatus::Published || (criteria.include_drafts && owned);
+    let deleted_ok = post.deleted_at.is_none() || (criteria.include_deleted && owned);
+    let author_ok = criteria.author_id.is_none_or(|id| id == post.author_id);
+
+    let matched_tags = criteria
+        .tags
+        .iter()
+        .filter(|tag| post.tags.contains(tag))
+        .count();
+    let tags_ok = criteria.tags.is_empty()
+        || match criteria.tag_match {
+            TagMatch::Any => matched_tags >= 1,
+            TagMatch::All => matched_tags == criteria.tags.len(),
+        };
+
+    let search_ok = criteria.search.as_deref().is_none_or(|search| {
+        let search = search.to_lowercase();
+        post.title.to_lowercase().contains(&search) || post.content.to_lowercase().contains(&search)
+    });
+
+    status_ok && deleted_ok && author_ok && tags_ok && search_ok
+}
+
+fn sorted_tags(tags: &[String]) -> Vec<String> {
+    let mut tags = tags.to_vec();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+// The writable copy of a non-deleted post, provided nobody changed it since `expected_version`.
+fn live_post(posts: &mut [Post], id: Uuid, expected_version: i64) -> Result<&mut Post, AppError> {
+    let post = posts
+        .iter_mut()
+        .find(|post| post.id == id && post.deleted_at.is_none())
+        .ok_or_else(|| {
+            AppError::NotFound(ErrorCode::PostNotFound, format!("Post not found: {id}"))
+        })?;
+
+    if post.version != expected_version {
+        return Err(AppError::PreconditionFailed(format!(
+            "Post {id} was modified by another request"
+        )));
+    }
+    Ok(post)
+}
+
+#[async_trait]
+impl PostRepository for InMemoryPostRepository {
+    async fn insert(&self, new: NewPost<'_>) -> Result<Post, AppError> {
+        let now = Utc::now();
+        let post = Post {
+            id: Uuid::new_v4(),
+            title: new.title.to_string(),
+            content: new.content.to_string(),
+            content_html: Some(new.content_html.to_string()),
+            author_id: new.author_id,
+            status: new.status,
+            published_at: new.published_at,
+            is_premium: new.is_premium,
+            deleted_at: None,
+            version: 1,
+            created_at: now,
+            updated_at: now,
+            snippet: None,
+            tags: sorted_tags(new.tags),
+        };
+        self.posts.lock().unwrap().push(post.clone());
+        self.record_revision(&post, None);
+        self.events.publish(PostEventKind::Created, &post);
+        Ok(post)
+    }
+
+    async fn list(
+        &self,
+        criteria: &PostCriteria,
+        cursor: Option<Cursor>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Page<Post>, AppError> {
+        let mut rows: Vec<Post> = self
+            .posts
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|post| matches(post, criteria))
+            .cloned()
+            .collect();
+        rows.sort_by_key(|row| std::cmp::Reverse((row.created_at, row.id)));
+
+        let rows: Vec<Post> = match cursor {
+            Some(cursor) => rows
+                .into_iter()
+                .filter(|post| (post.created_at, post.id) < (cursor.created_at, cursor.id))
+                .collect(),
+            None => rows
+                .into_iter()
+                .skip(usize::try_from(offset).unwrap_or(0))
+                .collect(),
+        };
+        let rows = rows
+            .into_iter()
+            .take(usize::try_from(limit + 1).unwrap_or(0))
+            .collect();
+
+        let mut page = Page::from_rows(rows, limit, |post| Cursor::new(post.created_at, post.id));
+        if criteria.search.is_some() {
+            page.next_cursor = None;
+        }
+        Ok(page)
+    }
+
+    async fn count(&self, criteria: &PostCriteria) -> Result<i64, AppError> {
+        let posts = self.posts.lock().unwrap();
+        Ok(posts.iter().filter(|post| matches(post, criteria)).count() as i64)
+    }
+
+    async fn last_modified(
+        &self,
+        author_id: Option<Uuid>,
+    ) -> Result<Option<DateTime<Utc>>, AppError> {
+        let posts = self.posts.lock().unwrap();
+        Ok(posts
+            .iter()
+            .filter(|post| author_id.is_none_or(|author_id| post.author_id == author_id))
+            .map(|post| post.updated_at)
+            .max())
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Post, AppError> {
+        self.posts
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|post| post.id == id && post.deleted_at.is_none())
+            .cloned()
+            .ok_or_else(|| {
+                AppError::NotFound(ErrorCode::PostNotFound, format!("Post not found: {id}"))
+            })
+    }
+
+    async fn update(
+        &self,
+        id: Uuid,
+        expected_version: i64,
+        changes: PostChanges<'_>,
+    ) -> Result<Post, AppError> {
+        let mut posts = self.posts.lock().unwrap();
+        let post = live_post(&mut posts, id, expected_version)?;
+        let previous = post.status;
+
+        if let Some(title) = changes.title {
+            post.title = title.to_string();
+        }
+        if let Some(content) = changes.content {
+            post.content = content.to_string();
+        }
+        if let Some(content_html) = changes.content_html {
+            post.content_html = Some(content_html.to_string());
+        }
+        post.status = changes.status;
+        post.published_at = changes.published_at;
+        if let Some(is_premium) = changes.is_premium {
+            post.is_premium = is_premium;
+        }
+        if let Some(tags) = changes.tags {
+            post.tags = sorted_tags(tags);
+        }
+        post.version += 1;
+        post.updated_at = Utc::now();
+
+        let post = post.clone();
+        drop(posts);
+        self.record_revision(&post, changes.restored_from);
+        self.events.publish_update(previous, &post);
+        Ok(post)
+    }
+
+    async fn delete(&self, id: Uuid, expected_version: i64) -> Result<(), AppError> {
+        let mut posts = self.posts.lock().unwrap();
+        let post = live_post(&mut posts, id, expected_version)?;
+
+        let now = Utc::now();
+        post.deleted_at = Some(now);
+        post.version += 1;
+        post.updated_at = now;
+        self.events.publish(PostEventKind::Deleted, post);
+        Ok(())
+    }
+
+    async fn publish_due(&self) -> Result<u64, AppError> {
+        let now = Utc::now();
+        let mut published = 0;
+        for post in self.posts.lock().unwrap().iter_mut() {
+            if post.status == PostStatus::Scheduled
+                && post.deleted_at.is_none()
+                && post.published_at.is_some_and(|at| at <= now)
+            {
+                post.status = PostStatus::Published;
+                post.version += 1;
+                post.updated_at = now;
+                self.events.publish_update(PostStatus::Scheduled, post);
+                published += 1;
+            }
+        }
+        Ok(published)
+    }
+
+    async fn revisions(&self, post_id: Uuid) -> Result<Vec<PostRevision>, AppError> {
+        let mut revisions: Vec<PostRevision> = self
+            .revisions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|revision| revision.post_id == post_id)
+            .cloned()
+            .collect();
+        revisions.sort_by_key(|revision| std::cmp::Reverse(revision.revision));
+        Ok(revisions)
+    }
+
+    async fn revision(&self, post_id: Uuid, revision: i32) -> Result<PostRevision, AppError> {
+        self.revisions
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|stored| stored.post_id == post_id && stored.revision == revision)
+            .cloned()
+            .ok_or_else(|| {
+                AppError::NotFound(
+                    ErrorCode::RevisionNotFound,
+                    format!("Revision not found: {revision}"),
+                )
+            })
+    }
+}
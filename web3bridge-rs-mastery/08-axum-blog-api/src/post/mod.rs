@@ -1,16 +1,54 @@
+mod cache;
+mod events;
 mod handlers;
+#[cfg(test)]
+mod memory;
 pub mod models;
 mod queries;
+mod repository;
+mod scheduler;
 
 use crate::app::state::SharedState;
+use crate::error::AppError;
+use crate::post::models::Post;
+use crate::tag::models::TagMatch;
 use axum::{
     Router,
     routing::{get, post},
 };
+use utoipa::OpenApi;
+use uuid::Uuid;
+
+pub use cache::PostCache;
+#[cfg(test)]
+pub use events::Delivery;
+pub use events::{PostEventKind, PostEvents};
+#[cfg(test)]
+pub use memory::InMemoryPostRepository;
+pub use queries::event_payload;
+pub use repository::{NewPost, PostChanges, PostRepository, SqlPostRepository};
+pub use scheduler::spawn_publisher;
+
+/// OpenAPI paths for the routes under `/posts`.
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        handlers::create_post,
+        handlers::get_posts,
+        handlers::stream_posts,
+        handlers::get_post_by_id,
+        handlers::patch_post,
+        handlers::delete_post,
+    ),
+    // Only referenced from the `tag_match` query parameter, which utoipa does not collect.
+    components(schemas(TagMatch))
+)]
+pub struct PostApi;
 
 pub fn post_routes() -> Router<SharedState> {
     Router::new()
         .route("/", post(handlers::create_post).get(handlers::get_posts))
+        .route("/stream", get(handlers::stream_posts))
         .route(
             "/{id}",
             get(handlers::get_post_by_id)
@@ -18,3 +56,7 @@ pub fn post_routes() -> Router<SharedState> {
                 .delete(handlers::delete_post),
         )
 }
+
+pub async fn find_post_by_id(state: &SharedState, id: Uuid) -> Result<Post, AppError> {
+    state.posts.find_by_id(id).await
+}
@@ -1,63 +1,316 @@
+use crate::error::{AppError, ErrorCode};
+use crate::etag::ETag;
+use crate::markdown;
+use crate::tag::models::TagMatch;
+use crate::validation::{self, MAX_VARCHAR_LENGTH};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use std::borrow::Cow;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum PostStatus {
+    Draft,
+    Scheduled,
+    Published,
+    Archived,
+}
+
+impl PostStatus {
+    /// Works out the `published_at` a post should carry after moving to this status.
+    ///
+    /// `requested` comes from the request body and `current` from the stored row.
+    pub fn published_at(
+        self,
+        requested: Option<DateTime<Utc>>,
+        current: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> Result<Option<DateTime<Utc>>, AppError> {
+        match self {
+            PostStatus::Draft => Ok(None),
+            PostStatus::Scheduled => match requested.or(current) {
+                Some(at) if at > now => Ok(Some(at)),
+                _ => Err(AppError::invalid(
+                    "published_at",
+                    ErrorCode::PostInvalidSchedule,
+                    "Scheduled posts need a published_at in the future",
+                )),
+            },
+            PostStatus::Published => match requested {
+                Some(at) if at > now => Err(AppError::invalid(
+                    "published_at",
+                    ErrorCode::PostInvalidSchedule,
+                    "Use status 'scheduled' to publish in the future",
+                )),
+                Some(at) => Ok(Some(at)),
+                None => Ok(Some(current.filter(|at| *at <= now).unwrap_or(now))),
+            },
+            PostStatus::Archived => Ok(current),
+        }
+    }
+}
 
 #[derive(Debug, Clone, FromRow)]
 pub struct Post {
     pub id: Uuid,
     pub title: String,
+    /// Markdown source.
     pub content: String,
+    /// `content` rendered by `markdown::render`; `None` on rows written before it was stored.
+    pub content_html: Option<String>,
     pub author_id: Uuid,
+    pub status: PostStatus,
+    pub published_at: Option<DateTime<Utc>>,
+    pub is_premium: bool,
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Incremented on every write; see `crate::etag`.
+    pub version: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    #[sqlx(default)]
+    pub snippet: Option<String>,
+    // Loaded separately from `post_tags`, never selected with the row.
+    #[sqlx(skip)]
+    pub tags: Vec<String>,
+}
+
+impl Post {
+    /// Tag of the default, Markdown representation, which `If-Match` is checked against.
+    pub fn etag(&self) -> ETag {
+        ETag::new(self.version, self.updated_at)
+    }
+
+    /// Tag of the representation served for `format`.
+    pub fn etag_for(&self, format: ContentFormat) -> ETag {
+        match format {
+            ContentFormat::Markdown => self.etag(),
+            ContentFormat::Html => self.etag().variant("html"),
+        }
+    }
+
+    /// Published, live posts are public; anything else is only visible to its author.
+    pub fn is_visible_to(&self, viewer_id: Option<Uuid>) -> bool {
+        let is_public = self.status == PostStatus::Published && self.deleted_at.is_none();
+        is_public || viewer_id == Some(self.author_id)
+    }
+
+    /// Sanitized HTML for the body, rendered now if the row predates stored HTML.
+    pub fn html(&self) -> Cow<'_, str> {
+        match &self.content_html {
+            Some(html) => Cow::Borrowed(html),
+            None => Cow::Owned(markdown::render(&self.content)),
+        }
+    }
+
+    /// Strips the body of a premium post for readers who are not entitled to it.
+    pub fn redact(&mut self) {
+        self.content.clear();
+        self.content_html = Some(String::new());
+        self.snippet = None;
+    }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema, Validate)]
 pub struct CreatePostRequest {
+    #[serde(deserialize_with = "validation::trim")]
+    #[validate(length(min = 1, max = MAX_VARCHAR_LENGTH))]
     pub title: String,
+    /// Markdown source, stored as written.
+    #[validate(custom(function = "validation::not_blank"))]
     pub content: String,
-    pub author_id: String,
+    /// Must be the authenticated author when given.
+    #[serde(default, deserialize_with = "validation::uuid_opt")]
+    pub author_id: Option<Uuid>,
+    pub status: Option<PostStatus>,
+    pub published_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub is_premium: Option<bool>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema, Validate)]
 pub struct UpdatePostRequest {
+    #[serde(default, deserialize_with = "validation::trim_opt")]
+    #[validate(length(min = 1, max = MAX_VARCHAR_LENGTH))]
     pub title: Option<String>,
+    #[validate(custom(function = "validation::not_blank"))]
     pub content: Option<String>,
+    pub status: Option<PostStatus>,
+    pub published_at: Option<DateTime<Utc>>,
+    /// Replaces the full tag set when present; an empty list clears it.
+    pub tags: Option<Vec<String>>,
+    pub is_premium: Option<bool>,
+}
+
+/// What `content` holds in a `PostResponse`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentFormat {
+    /// The source as written.
+    #[default]
+    Markdown,
+    /// Rendered and sanitized: no scripts, styles or event-handler attributes.
+    Html,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PostFormatQuery {
+    /// Defaults to `markdown`.
+    pub format: Option<ContentFormat>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PostStreamFilter {
+    /// Only events for this author's posts.
+    pub author_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct PostFilter {
     pub author_id: Option<String>,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
+    pub cursor: Option<String>,
+    pub include_total: Option<bool>,
+    pub q: Option<String>,
+    pub include_drafts: Option<bool>,
+    pub include_deleted: Option<bool>,
+    #[serde(default)]
+    pub tag: Vec<String>,
+    pub tag_match: Option<TagMatch>,
 }
 
-#[derive(Debug, Serialize)]
+/// Filters applied to both the post listing and its total count.
+///
+/// Drafts and deleted rows are only ever included for posts owned by `viewer_id`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PostCriteria {
+    pub author_id: Option<Uuid>,
+    pub search: Option<String>,
+    pub include_drafts: bool,
+    pub include_deleted: bool,
+    pub viewer_id: Option<Uuid>,
+    pub tags: Vec<String>,
+    pub tag_match: TagMatch,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct PostResponse {
     pub id: String,
     pub title: String,
+    /// Markdown, or HTML when requested with `format=html`; see `content_format`.
     pub content: String,
+    pub content_format: ContentFormat,
+    /// Words of visible text, ignoring Markdown syntax; `0` when the body is withheld.
+    pub word_count: usize,
+    /// At 200 words a minute, rounded up.
+    pub reading_time_minutes: usize,
     pub author_id: String,
+    pub status: PostStatus,
+    pub published_at: Option<DateTime<Utc>>,
+    pub is_premium: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct PostListResponse {
     pub data: Vec<PostResponse>,
-    pub total: usize,
+    pub total: Option<i64>,
+    pub limit: i64,
+    pub offset: i64,
+    pub has_more: bool,
+    pub next_cursor: Option<String>,
 }
 
-impl From<Post> for PostResponse {
-    fn from(value: Post) -> Self {
+impl PostResponse {
+    pub fn with_format(value: Post, content_format: ContentFormat) -> Self {
+        let word_count = markdown::word_count(&value.content);
+        let content = match content_format {
+            ContentFormat::Markdown => value.content.clone(),
+            ContentFormat::Html => value.html().into_owned(),
+        };
+
         Self {
             id: value.id.to_string(),
             title: value.title,
-            content: value.content,
+            content,
+            content_format,
+            word_count,
+            reading_time_minutes: markdown::reading_time_minutes(word_count),
             author_id: value.author_id.to_string(),
+            status: value.status,
+            published_at: value.published_at,
+            is_premium: value.is_premium,
+            deleted_at: value.deleted_at,
             created_at: value.created_at,
             updated_at: value.updated_at,
+            tags: value.tags,
+            snippet: value.snippet,
         }
     }
 }
+
+impl From<Post> for PostResponse {
+    fn from(value: Post) -> Self {
+        Self::with_format(value, ContentFormat::Markdown)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn draft_clears_published_at() {
+        let now = Utc::now();
+        let result = PostStatus::Draft.published_at(Some(now), Some(now), now).unwrap();
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn scheduled_requires_future_time() {
+        let now = Utc::now();
+        let later = now + Duration::hours(1);
+
+        assert_eq!(
+            PostStatus::Scheduled.published_at(Some(later), None, now).unwrap(),
+            Some(later)
+        );
+        assert!(PostStatus::Scheduled.published_at(None, None, now).is_err());
+        assert!(PostStatus::Scheduled
+            .published_at(Some(now - Duration::hours(1)), None, now)
+            .is_err());
+    }
+
+    #[test]
+    fn publishing_keeps_past_time_but_not_a_pending_schedule() {
+        let now = Utc::now();
+        let earlier = now - Duration::days(1);
+        let later = now + Duration::days(1);
+
+        assert_eq!(
+            PostStatus::Published.published_at(None, Some(earlier), now).unwrap(),
+            Some(earlier)
+        );
+        assert_eq!(
+            PostStatus::Published.published_at(None, Some(later), now).unwrap(),
+            Some(now)
+        );
+        assert!(PostStatus::Published.published_at(Some(later), None, now).is_err());
+    }
+}
@@ -1,113 +1,484 @@
-use crate::app::state::SharedState;
-use crate::author;
-use crate::error::AppError;
-use crate::post::models::Post;
+use crate::cache::get_or_load;
+use crate::db::{Db, DbPool, NOW};
+use crate::error::{AppError, ErrorCode};
+use crate::outbox;
+use crate::pagination::{self, Cursor, Page};
+use crate::post::cache::{ListKey, PostCache};
+use crate::post::events::{PostEventKind, PostEvents};
+use crate::post::models::{Post, PostCriteria, PostStatus};
+use crate::post::repository::{NewPost, PostChanges};
+use crate::revision;
+use crate::tag::{self, models::TagMatch};
+use chrono::{DateTime, Utc};
+use serde_json::{Value, json};
 use sqlx::QueryBuilder;
 use uuid::Uuid;
 
 pub async fn insert_post(
-    state: &SharedState,
-    title: &str,
-    content: &str,
-    author_id: Uuid,
+    pool: &DbPool,
+    cache: &PostCache,
+    events: &PostEvents,
+    new: NewPost<'_>,
 ) -> Result<Post, AppError> {
-    let has_author = author::author_exists(state, author_id).await?;
-    if !has_author {
-        return Err(AppError::UnProcessableEntity {
-            field: "author_id".to_string(),
-            message: "Author does not exist".to_string(),
-        });
-    }
-
     let post_id = Uuid::new_v4();
+    let mut tx = pool.begin().await.map_err(|err| {
+        AppError::InternalServerError(format!("Failed to start transaction: {err}"))
+    })?;
 
-    let post = sqlx::query_as::<_, Post>(
-        "INSERT INTO posts (id, title, content, author_id) VALUES ($1, $2, $3, $4) RETURNING id, title, content, author_id, created_at, updated_at",
+    let mut post = sqlx::query_as::<_, Post>(
+        "INSERT INTO posts (id, title, content, content_html, author_id, status, published_at, is_premium) VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING id, title, content, content_html, author_id, status, published_at, is_premium, deleted_at, version, created_at, updated_at",
     )
     .bind(post_id)
-    .bind(title)
-    .bind(content)
-    .bind(author_id)
-    .fetch_one(&state.db_pool)
+    .bind(new.title)
+    .bind(new.content)
+    .bind(new.content_html)
+    .bind(new.author_id)
+    .bind(new.status)
+    .bind(new.published_at)
+    .bind(new.is_premium)
+    .fetch_one(&mut *tx)
     .await
     .map_err(|err| AppError::InternalServerError(format!("Failed to create post: {err}")))?;
 
+    if !new.tags.is_empty() {
+        tag::set_post_tags(&mut tx, post.id, new.tags).await?;
+    }
+    post.tags = new.tags.to_vec();
+    post.tags.sort();
+
+    revision::record_revision(&mut tx, &post, None).await?;
+    outbox::enqueue(&mut tx, outbox::POST_CREATED, post.id, event_payload(&post)).await?;
+
+    tx.commit()
+        .await
+        .map_err(|err| AppError::InternalServerError(format!("Failed to create post: {err}")))?;
+    cache.invalidate_post(post.id, post.author_id);
+    events.publish(PostEventKind::Created, &post);
+
     Ok(post)
 }
 
 pub async fn list_posts(
-    state: &SharedState,
-    author_id: Option<Uuid>,
+    pool: &DbPool,
+    cache: &PostCache,
+    criteria: &PostCriteria,
+    cursor: Option<Cursor>,
     limit: i64,
     offset: i64,
-) -> Result<Vec<Post>, AppError> {
-    let mut builder = QueryBuilder::new(
-        "SELECT id, title, content, author_id, created_at, updated_at FROM posts",
-    );
+) -> Result<Page<Post>, AppError> {
+    let key = ListKey {
+        criteria: criteria.clone(),
+        cursor,
+        limit,
+        offset,
+    };
+    get_or_load(
+        &*cache.lists,
+        key,
+        load_posts(pool, criteria, cursor, limit, offset),
+    )
+    .await
+}
 
-    if let Some(author_id) = author_id {
-        builder.push(" WHERE author_id = ");
-        builder.push_bind(author_id);
+async fn load_posts(
+    pool: &DbPool,
+    criteria: &PostCriteria,
+    cursor: Option<Cursor>,
+    limit: i64,
+    offset: i64,
+) -> Result<Page<Post>, AppError> {
+    let mut builder =
+        QueryBuilder::new("SELECT id, title, content, content_html, author_id, status, published_at, is_premium, deleted_at, version, created_at, updated_at");
+
+    if let Some(search) = &criteria.search {
+        push_snippet(&mut builder, search);
     }
 
-    builder
-        .push(" ORDER BY created_at DESC LIMIT ")
-        .push_bind(limit)
-        .push(" OFFSET ")
-        .push_bind(offset);
+    builder.push(" FROM posts WHERE TRUE");
+    push_criteria(&mut builder, criteria);
+    pagination::push_after(&mut builder, cursor);
+
+    builder.push(" ORDER BY ");
+    if let Some(search) = &criteria.search {
+        push_rank(&mut builder, search);
+        builder.push(", ");
+    }
+    pagination::push_page_bounds(&mut builder, cursor, limit, offset);
 
-    let posts = builder
+    let mut posts = builder
         .build_query_as::<Post>()
-        .fetch_all(&state.db_pool)
+        .fetch_all(pool)
         .await
         .map_err(|err| AppError::InternalServerError(format!("Failed to fetch posts: {err}")))?;
+    attach_tags(pool, &mut posts).await?;
 
-    Ok(posts)
+    let mut page = Page::from_rows(posts, limit, |post| {
+        Cursor::new(post.created_at, post.id)
+    });
+
+    // Ranked results are not ordered by (created_at, id), so a cursor would skip rows.
+    if criteria.search.is_some() {
+        page.next_cursor = None;
+    }
+
+    Ok(page)
 }
 
-pub async fn find_post_by_id(state: &SharedState, id: Uuid) -> Result<Post, AppError> {
-    let post = sqlx::query_as::<_, Post>(
-        "SELECT id, title, content, author_id, created_at, updated_at FROM posts WHERE id = $1",
+pub async fn count_posts(
+    pool: &DbPool,
+    cache: &PostCache,
+    criteria: &PostCriteria,
+) -> Result<i64, AppError> {
+    get_or_load(&*cache.counts, criteria.clone(), load_count(pool, criteria)).await
+}
+
+async fn load_count(pool: &DbPool, criteria: &PostCriteria) -> Result<i64, AppError> {
+    let mut builder = QueryBuilder::new("SELECT COUNT(*) FROM posts WHERE TRUE");
+    push_criteria(&mut builder, criteria);
+
+    let total = builder
+        .build_query_scalar::<i64>()
+        .fetch_one(pool)
+        .await
+        .map_err(|err| AppError::InternalServerError(format!("Failed to count posts: {err}")))?;
+
+    Ok(total)
+}
+
+pub async fn last_modified(
+    pool: &DbPool,
+    author_id: Option<Uuid>,
+) -> Result<Option<DateTime<Utc>>, AppError> {
+    let mut builder = QueryBuilder::new("SELECT updated_at FROM posts WHERE TRUE");
+    if let Some(author_id) = author_id {
+        builder.push(" AND author_id = ").push_bind(author_id);
+    }
+    builder.push(" ORDER BY updated_at DESC LIMIT 1");
+
+    builder
+        .build_query_scalar::<DateTime<Utc>>()
+        .fetch_optional(pool)
+        .await
+        .map_err(|err| AppError::InternalServerError(format!("Failed to read post dates: {err}")))
+}
+
+pub async fn find_post_by_id(pool: &DbPool, cache: &PostCache, id: Uuid) -> Result<Post, AppError> {
+    get_or_load(&*cache.by_id, id, load_post(pool, id)).await
+}
+
+async fn load_post(pool: &DbPool, id: Uuid) -> Result<Post, AppError> {
+    let mut post = sqlx::query_as::<_, Post>(
+        "SELECT id, title, content, content_html, author_id, status, published_at, is_premium, deleted_at, version, created_at, updated_at FROM posts WHERE id = $1 AND deleted_at IS NULL",
     )
     .bind(id)
-    .fetch_optional(&state.db_pool)
+    .fetch_optional(pool)
     .await
     .map_err(|err| AppError::InternalServerError(format!("Failed to fetch post: {err}")))?
-    .ok_or_else(|| AppError::NotFound(format!("Post not found: {id}")))?;
+    .ok_or_else(|| AppError::NotFound(ErrorCode::PostNotFound, format!("Post not found: {id}")))?;
 
+    attach_tags(pool, std::slice::from_mut(&mut post)).await?;
     Ok(post)
 }
 
 pub async fn update_post(
-    state: &SharedState,
+    pool: &DbPool,
+    cache: &PostCache,
+    events: &PostEvents,
     id: Uuid,
-    title: Option<&str>,
-    content: Option<&str>,
+    expected_version: i64,
+    changes: PostChanges<'_>,
 ) -> Result<Post, AppError> {
+    let mut tx = pool.begin().await.map_err(|err| {
+        AppError::InternalServerError(format!("Failed to start transaction: {err}"))
+    })?;
+
+    // The version check on the UPDATE keeps this in step with the row it changes.
+    let previous = sqlx::query_scalar::<_, PostStatus>(
+        "SELECT status FROM posts WHERE id = $1 AND deleted_at IS NULL AND version = $2",
+    )
+    .bind(id)
+    .bind(expected_version)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|err| AppError::InternalServerError(format!("Failed to update post: {err}")))?;
+    let Some(previous) = previous else {
+        return Err(missing_or_stale(pool, id).await);
+    };
+
     let post = sqlx::query_as::<_, Post>(
-        "UPDATE posts SET title = COALESCE($2, title), content = COALESCE($3, content), updated_at = NOW() WHERE id = $1 RETURNING id, title, content, author_id, created_at, updated_at",
+        &format!("UPDATE posts SET title = COALESCE($2, title), content = COALESCE($3, content), content_html = COALESCE($8, content_html), status = $4, published_at = $5, is_premium = COALESCE($6, is_premium), version = version + 1, updated_at = {NOW} WHERE id = $1 AND deleted_at IS NULL AND version = $7 RETURNING id, title, content, content_html, author_id, status, published_at, is_premium, deleted_at, version, created_at, updated_at"),
     )
     .bind(id)
-    .bind(title)
-    .bind(content)
-    .fetch_optional(&state.db_pool)
+    .bind(changes.title)
+    .bind(changes.content)
+    .bind(changes.status)
+    .bind(changes.published_at)
+    .bind(changes.is_premium)
+    .bind(expected_version)
+    .bind(changes.content_html)
+    .fetch_optional(&mut *tx)
     .await
-    .map_err(|err| AppError::InternalServerError(format!("Failed to update post: {err}")))?
-    .ok_or_else(|| AppError::NotFound(format!("Post not found: {id}")))?;
+    .map_err(|err| AppError::InternalServerError(format!("Failed to update post: {err}")))?;
+    let Some(mut post) = post else {
+        return Err(missing_or_stale(pool, id).await);
+    };
+
+    if let Some(tags) = changes.tags {
+        tag::set_post_tags(&mut tx, post.id, tags).await?;
+    }
+
+    revision::record_revision(&mut tx, &post, changes.restored_from).await?;
+    outbox::enqueue(&mut tx, outbox::POST_UPDATED, post.id, event_payload(&post)).await?;
+
+    tx.commit()
+        .await
+        .map_err(|err| AppError::InternalServerError(format!("Failed to update post: {err}")))?;
+    cache.invalidate_post(post.id, post.author_id);
+    events.publish_update(previous, &post);
+
+    attach_tags(pool, std::slice::from_mut(&mut post)).await?;
 
     Ok(post)
 }
 
-pub async fn delete_post(state: &SharedState, id: Uuid) -> Result<(), AppError> {
-    let result = sqlx::query("DELETE FROM posts WHERE id = $1")
-        .bind(id)
-        .execute(&state.db_pool)
+pub async fn delete_post(
+    pool: &DbPool,
+    cache: &PostCache,
+    events: &PostEvents,
+    id: Uuid,
+    expected_version: i64,
+) -> Result<(), AppError> {
+    let mut tx = pool.begin().await.map_err(|err| {
+        AppError::InternalServerError(format!("Failed to start transaction: {err}"))
+    })?;
+
+    let post = sqlx::query_as::<_, Post>(
+        &format!("UPDATE posts SET deleted_at = {NOW}, version = version + 1, updated_at = {NOW} WHERE id = $1 AND deleted_at IS NULL AND version = $2 RETURNING id, title, content, content_html, author_id, status, published_at, is_premium, deleted_at, version, created_at, updated_at"),
+    )
+    .bind(id)
+    .bind(expected_version)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|err| AppError::InternalServerError(format!("Failed to delete post: {err}")))?;
+
+    let Some(post) = post else {
+        return Err(missing_or_stale(pool, id).await);
+    };
+    outbox::enqueue(&mut tx, outbox::POST_DELETED, post.id, event_payload(&post)).await?;
+
+    tx.commit()
         .await
         .map_err(|err| AppError::InternalServerError(format!("Failed to delete post: {err}")))?;
+    cache.invalidate_post(id, post.author_id);
+    events.publish(PostEventKind::Deleted, &post);
+
+    Ok(())
+}
+
+/// Flips every scheduled post whose `published_at` has passed to `published`.
+pub async fn publish_due_posts(
+    pool: &DbPool,
+    cache: &PostCache,
+    events: &PostEvents,
+) -> Result<u64, AppError> {
+    let mut tx = pool.begin().await.map_err(|err| {
+        AppError::InternalServerError(format!("Failed to start transaction: {err}"))
+    })?;
+
+    let published = sqlx::query_as::<_, Post>(
+        &format!("UPDATE posts SET status = 'published', version = version + 1, updated_at = {NOW} WHERE status = 'scheduled' AND published_at <= {NOW} AND deleted_at IS NULL RETURNING id, title, content, content_html, author_id, status, published_at, is_premium, deleted_at, version, created_at, updated_at"),
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|err| AppError::InternalServerError(format!("Failed to publish scheduled posts: {err}")))?;
+
+    for post in &published {
+        outbox::enqueue(
+            &mut tx,
+            outbox::POST_PUBLISHED,
+            post.id,
+            event_payload(post),
+        )
+        .await?;
+    }
 
-    if result.rows_affected() == 0 {
-        return Err(AppError::NotFound(format!("Post not found: {id}")));
+    tx.commit().await.map_err(|err| {
+        AppError::InternalServerError(format!("Failed to publish scheduled posts: {err}"))
+    })?;
+    for post in &published {
+        cache.invalidate_post(post.id, post.author_id);
+        events.publish_update(PostStatus::Scheduled, post);
+    }
+
+    Ok(published.len() as u64)
+}
+
+/// Snapshot handed to outbox consumers; the body is left out, consumers can re-read the post.
+pub fn event_payload(post: &Post) -> Value {
+    json!({
+        "id": post.id,
+        "title": post.title,
+        "author_id": post.author_id,
+        "status": post.status,
+        "published_at": post.published_at,
+        "is_premium": post.is_premium,
+        "updated_at": post.updated_at,
+    })
+}
+
+// A versioned write matched no row: either the post is gone or someone else wrote first.
+async fn missing_or_stale(pool: &DbPool, id: Uuid) -> AppError {
+    let exists = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (SELECT 1 FROM posts WHERE id = $1 AND deleted_at IS NULL)",
+    )
+    .bind(id)
+    .fetch_one(pool)
+    .await;
+
+    match exists {
+        Ok(true) => AppError::PreconditionFailed(format!("Post {id} was modified by another request")),
+        Ok(false) => AppError::NotFound(ErrorCode::PostNotFound, format!("Post not found: {id}")),
+        Err(err) => AppError::InternalServerError(format!("Failed to check post: {err}")),
+    }
+}
+
+async fn attach_tags(pool: &DbPool, posts: &mut [Post]) -> Result<(), AppError> {
+    if posts.is_empty() {
+        return Ok(());
+    }
+
+    let ids: Vec<Uuid> = posts.iter().map(|post| post.id).collect();
+    let mut tags = tag::tags_for_posts(pool, &ids).await?;
+    for post in posts.iter_mut() {
+        post.tags = tags.remove(&post.id).unwrap_or_default();
     }
 
     Ok(())
 }
+
+// Appends the filters shared by `list_posts` and `count_posts`, so totals always match the listing.
+fn push_criteria(builder: &mut QueryBuilder<'_, Db>, criteria: &PostCriteria) {
+    // Without a viewer nothing matches `author_id = NULL`, so only public rows remain.
+    if criteria.include_drafts {
+        builder
+            .push(" AND (status = 'published' OR author_id = ")
+            .push_bind(criteria.viewer_id)
+            .push(")");
+    } else {
+        builder.push(" AND status = 'published'");
+    }
+
+    if criteria.include_deleted {
+        builder
+            .push(" AND (deleted_at IS NULL OR author_id = ")
+            .push_bind(criteria.viewer_id)
+            .push(")");
+    } else {
+        builder.push(" AND deleted_at IS NULL");
+    }
+
+    if let Some(author_id) = criteria.author_id {
+        builder.push(" AND author_id = ");
+        builder.push_bind(author_id);
+    }
+
+    if !criteria.tags.is_empty() {
+        let matched_tags = "SELECT COUNT(DISTINCT t.name) FROM post_tags pt JOIN tags t ON t.id = pt.tag_id WHERE pt.post_id = posts.id AND t.name";
+        let required = match criteria.tag_match {
+            TagMatch::Any => 1,
+            TagMatch::All => criteria.tags.len() as i64,
+        };
+
+        builder.push(format!(" AND ({matched_tags}"));
+        push_name_in(builder, &criteria.tags);
+        builder.push(") >= ").push_bind(required);
+    }
+
+    if let Some(search) = &criteria.search {
+        push_search_filter(builder, search);
+    }
+}
+
+// Postgres searches the weighted `search_vector` column; `websearch_to_tsquery` accepts
+// anything a user types, so the raw query is bound as is.
+
+#[cfg(feature = "postgres")]
+const HEADLINE_OPTIONS: &str =
+    "StartSel=<mark>, StopSel=</mark>, MaxFragments=2, MaxWords=30, MinWords=10";
+
+#[cfg(feature = "postgres")]
+fn push_snippet(builder: &mut QueryBuilder<'_, Db>, search: &str) {
+    builder
+        .push(", ts_headline('english', content, websearch_to_tsquery('english', ")
+        .push_bind(search.to_string())
+        .push(format!("), '{HEADLINE_OPTIONS}') AS snippet"));
+}
+
+#[cfg(feature = "postgres")]
+fn push_rank(builder: &mut QueryBuilder<'_, Db>, search: &str) {
+    builder
+        .push("ts_rank(search_vector, websearch_to_tsquery('english', ")
+        .push_bind(search.to_string())
+        .push(")) DESC");
+}
+
+#[cfg(feature = "postgres")]
+fn push_search_filter(builder: &mut QueryBuilder<'_, Db>, search: &str) {
+    builder
+        .push(" AND search_vector @@ websearch_to_tsquery('english', ")
+        .push_bind(search.to_string())
+        .push(")");
+}
+
+#[cfg(feature = "postgres")]
+fn push_name_in(builder: &mut QueryBuilder<'_, Db>, names: &[String]) {
+    builder.push(" = ANY(").push_bind(names.to_vec()).push(")");
+}
+
+// SQLite searches the `posts_fts` table, matching on `post_id` because FTS5 rowids
+// are not stable for tables keyed by a UUID. Title hits weigh ten times body hits.
+
+#[cfg(feature = "sqlite")]
+fn push_snippet(builder: &mut QueryBuilder<'_, Db>, search: &str) {
+    builder
+        .push(", (SELECT snippet(posts_fts, 2, '<mark>', '</mark>', '…', 30) FROM posts_fts WHERE posts_fts MATCH ")
+        .push_bind(fts_match(search))
+        .push(" AND post_id = posts.id) AS snippet");
+}
+
+#[cfg(feature = "sqlite")]
+fn push_rank(builder: &mut QueryBuilder<'_, Db>, search: &str) {
+    // bm25 scores are negative, better matches lower.
+    builder
+        .push("(SELECT bm25(posts_fts, 0.0, 10.0, 1.0) FROM posts_fts WHERE posts_fts MATCH ")
+        .push_bind(fts_match(search))
+        .push(" AND post_id = posts.id) ASC");
+}
+
+#[cfg(feature = "sqlite")]
+fn push_search_filter(builder: &mut QueryBuilder<'_, Db>, search: &str) {
+    builder
+        .push(" AND id IN (SELECT post_id FROM posts_fts WHERE posts_fts MATCH ")
+        .push_bind(fts_match(search))
+        .push(")");
+}
+
+// SQLite has no arrays, so the list goes over as one JSON value.
+#[cfg(feature = "sqlite")]
+fn push_name_in(builder: &mut QueryBuilder<'_, Db>, names: &[String]) {
+    builder
+        .push(" IN (SELECT value FROM json_each(")
+        .push_bind(sqlx::types::Json(names.to_vec()))
+        .push("))");
+}
+
+/// Quotes every word, so FTS5 requires each one to match instead of parsing the
+/// input as query syntax, where a stray `"` or `-` is an error.
+#[cfg(feature = "sqlite")]
+fn fts_match(search: &str) -> String {
+    search
+        .split_whitespace()
+        .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
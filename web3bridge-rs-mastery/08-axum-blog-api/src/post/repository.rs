@@ -0,0 +1,171 @@
+use crate::db::DbPool;
+use crate::error::AppError;
+use crate::pagination::{Cursor, Page};
+use crate::post::cache::PostCache;
+use crate::post::events::PostEvents;
+use crate::post::models::{Post, PostCriteria, PostStatus};
+use crate::post::queries;
+use crate::revision::{self, models::PostRevision};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::fmt::Debug;
+use uuid::Uuid;
+
+pub struct NewPost<'a> {
+    pub title: &'a str,
+    pub content: &'a str,
+    /// `content` rendered with `markdown::render`.
+    pub content_html: &'a str,
+    pub author_id: Uuid,
+    pub status: PostStatus,
+    pub published_at: Option<DateTime<Utc>>,
+    pub is_premium: bool,
+    pub tags: &'a [String],
+}
+
+/// Fields left as `None` keep their stored value; `status` and `published_at` are always written.
+pub struct PostChanges<'a> {
+    pub title: Option<&'a str>,
+    pub content: Option<&'a str>,
+    /// Set exactly when `content` is, to its rendering.
+    pub content_html: Option<&'a str>,
+    pub status: PostStatus,
+    pub published_at: Option<DateTime<Utc>>,
+    pub is_premium: Option<bool>,
+    pub tags: Option<&'a [String]>,
+    /// Recorded on the new revision when this write rolls back to an older one.
+    pub restored_from: Option<i32>,
+}
+
+/// Storage for posts, their tags and revision history. Soft-deleted posts are only reachable
+/// through `list`.
+#[async_trait]
+pub trait PostRepository: Debug + Send + Sync {
+    /// The caller checks that the author exists. Records revision 1.
+    async fn insert(&self, new: NewPost<'_>) -> Result<Post, AppError>;
+
+    /// Newest first, or by relevance when searching; `offset` is ignored when a cursor is given.
+    async fn list(
+        &self,
+        criteria: &PostCriteria,
+        cursor: Option<Cursor>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Page<Post>, AppError>;
+
+    async fn count(&self, criteria: &PostCriteria) -> Result<i64, AppError>;
+
+    /// Newest `updated_at` of any post, or of `author_id`'s posts. Drafts and deleted posts
+    /// count too, so taking a post out of a listing still moves it.
+    async fn last_modified(
+        &self,
+        author_id: Option<Uuid>,
+    ) -> Result<Option<DateTime<Utc>>, AppError>;
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Post, AppError>;
+
+    /// Records a new revision. Fails with a `412` unless the stored version is still
+    /// `expected_version`.
+    async fn update(
+        &self,
+        id: Uuid,
+        expected_version: i64,
+        changes: PostChanges<'_>,
+    ) -> Result<Post, AppError>;
+
+    /// Soft delete; fails with a `412` unless the stored version is still `expected_version`.
+    async fn delete(&self, id: Uuid, expected_version: i64) -> Result<(), AppError>;
+
+    /// Flips every scheduled post whose `published_at` has passed to `published`.
+    async fn publish_due(&self) -> Result<u64, AppError>;
+
+    /// Newest first.
+    async fn revisions(&self, post_id: Uuid) -> Result<Vec<PostRevision>, AppError>;
+
+    async fn revision(&self, post_id: Uuid, revision: i32) -> Result<PostRevision, AppError>;
+}
+
+/// Database-backed posts; writes also record a revision and an outbox event in the same
+/// transaction. Reads go through `PostCache`, which writes invalidate once committed;
+/// committed writes are then published to `PostEvents`.
+#[derive(Debug, Clone)]
+pub struct SqlPostRepository {
+    pool: DbPool,
+    cache: PostCache,
+    events: PostEvents,
+}
+
+impl SqlPostRepository {
+    pub fn new(pool: DbPool, cache: PostCache, events: PostEvents) -> Self {
+        Self {
+            pool,
+            cache,
+            events,
+        }
+    }
+}
+
+#[async_trait]
+impl PostRepository for SqlPostRepository {
+    async fn insert(&self, new: NewPost<'_>) -> Result<Post, AppError> {
+        queries::insert_post(&self.pool, &self.cache, &self.events, new).await
+    }
+
+    async fn list(
+        &self,
+        criteria: &PostCriteria,
+        cursor: Option<Cursor>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Page<Post>, AppError> {
+        queries::list_posts(&self.pool, &self.cache, criteria, cursor, limit, offset).await
+    }
+
+    async fn count(&self, criteria: &PostCriteria) -> Result<i64, AppError> {
+        queries::count_posts(&self.pool, &self.cache, criteria).await
+    }
+
+    async fn last_modified(
+        &self,
+        author_id: Option<Uuid>,
+    ) -> Result<Option<DateTime<Utc>>, AppError> {
+        queries::last_modified(&self.pool, author_id).await
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Post, AppError> {
+        queries::find_post_by_id(&self.pool, &self.cache, id).await
+    }
+
+    async fn update(
+        &self,
+        id: Uuid,
+        expected_version: i64,
+        changes: PostChanges<'_>,
+    ) -> Result<Post, AppError> {
+        queries::update_post(
+            &self.pool,
+            &self.cache,
+            &self.events,
+            id,
+            expected_version,
+            changes,
+        )
+        .await
+    }
+
+    async fn delete(&self, id: Uuid, expected_version: i64) -> Result<(), AppError> {
+        queries::delete_post(&self.pool, &self.cache, &self.events, id, expected_version).await
+    }
+
+    async fn publish_due(&self) -> Result<u64, AppError> {
+        queries::publish_due_posts(&self.pool, &self.cache, &self.events).await
+    }
+
+    async fn revisions(&self, post_id: Uuid) -> Result<Vec<PostRevision>, AppError> {
+        revision::list_revisions(&self.pool, post_id).await
+    }
+
+    async fn revision(&self, post_id: Uuid, revision: i32) -> Result<PostRevision, AppError> {
+        revision::find_revision(&self.pool, post_id, revision).await
+    }
+}
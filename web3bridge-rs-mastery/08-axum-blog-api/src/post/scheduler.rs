@@ -0,0 +1,22 @@
+use crate::app::state::SharedState;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+const PUBLISH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Spawns the background task that promotes scheduled posts once their time arrives.
+pub fn spawn_publisher(state: SharedState) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PUBLISH_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            match state.posts.publish_due().await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!(count, "published scheduled posts"),
+                Err(err) => tracing::error!(error = %err, "scheduled publishing failed"),
+            }
+        }
+    })
+}
@@ -0,0 +1,261 @@
+mod store;
+
+use crate::app::state::SharedState;
+use crate::config::{Budget, RateLimitConfig};
+use crate::error::AppError;
+use axum::{
+    extract::{ConnectInfo, MatchedPath, Request, State},
+    http::{
+        HeaderMap, HeaderName, HeaderValue,
+        header::{AUTHORIZATION, RETRY_AFTER},
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub use store::{Decision, InMemoryRateLimitStore, RateLimitStore};
+
+pub const RATELIMIT_LIMIT: HeaderName = HeaderName::from_static("ratelimit-limit");
+pub const RATELIMIT_REMAINING: HeaderName = HeaderName::from_static("ratelimit-remaining");
+pub const RATELIMIT_RESET: HeaderName = HeaderName::from_static("ratelimit-reset");
+
+/// Picks the bucket for each request and asks the store for a token.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    config: Arc<RateLimitConfig>,
+    store: Arc<dyn RateLimitStore>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig, store: Arc<dyn RateLimitStore>) -> Self {
+        Self {
+            config: Arc::new(config),
+            store,
+        }
+    }
+
+    /// Buckets kept in this process only; each instance enforces its own budget.
+    pub fn in_memory(config: RateLimitConfig) -> Self {
+        Self::new(config, Arc::new(InMemoryRateLimitStore::default()))
+    }
+
+    // Routes with their own budget get their own bucket; everything else shares one.
+    fn budget_for(&self, method: &str, path: Option<&str>) -> (String, Budget) {
+        path.and_then(|path| {
+            self.config
+                .routes
+                .iter()
+                .find(|route| route.method == method && route.path == path)
+        })
+        .map(|route| (format!("{} {}", route.method, route.path), route.budget))
+        .unwrap_or_else(|| ("*".to_string(), self.config.default))
+    }
+
+    // A valid token keys by author so a shared IP (office, NAT) does not pool budgets.
+    fn client_key(&self, state: &SharedState, request: &Request) -> String {
+        let author = request
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .and_then(|token| state.jwt.verify(token.trim()).ok());
+        if let Some(author_id) = author {
+            return format!("author:{author_id}");
+        }
+
+        let forwarded = self
+            .config
+            .trust_forwarded_for
+            .then(|| request.headers().get("x-forwarded-for"))
+            .flatten()
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(',').next())
+            .map(|ip| ip.trim().to_string())
+            .filter(|ip| !ip.is_empty());
+        let peer = || {
+            request
+                .extensions()
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip().to_string())
+        };
+
+        // Without connection info (in-process tests) every anonymous caller shares a bucket.
+        format!(
+            "ip:{}",
+            forwarded
+                .or_else(peer)
+                .unwrap_or_else(|| "unknown".to_string())
+        )
+    }
+}
+
+/// Token-bucket rate limiting keyed by authenticated author, else client IP.
+///
+/// Every limited response carries `RateLimit-Limit`, `RateLimit-Remaining` and
+/// `RateLimit-Reset`; a request over budget gets `429` with `Retry-After`.
+pub async fn rate_limit(
+    State(state): State<SharedState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let limiter = &state.rate_limiter;
+    if !limiter.config.enabled {
+        return next.run(request).await;
+    }
+
+    let path = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(MatchedPath::as_str);
+    let (scope, budget) = limiter.budget_for(request.method().as_str(), path);
+    let client = limiter.client_key(&state, &request);
+    let key = format!("{scope}|{client}");
+
+    let decision = match limiter.store.acquire(&key, budget).await {
+        Ok(decision) => decision,
+        // An unreachable store should not take the API down with it.
+        Err(err) => {
+            tracing::warn!(error = %err, "rate limit store failed, letting request through");
+            return next.run(request).await;
+        }
+    };
+
+    if !decision.allowed {
+        tracing::info!(scope = %scope, client = %client, "rate limit exceeded");
+        let mut headers = rate_limit_headers(&decision);
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from(ceil_secs(decision.retry_after).max(1)),
+        );
+        let error = AppError::TooManyRequests(format!(
+            "Rate limit exceeded, retry in {} seconds",
+            ceil_secs(decision.retry_after).max(1)
+        ));
+        return (headers, error).into_response();
+    }
+
+    let mut response = next.run(request).await;
+    response.headers_mut().extend(rate_limit_headers(&decision));
+    response
+}
+
+fn rate_limit_headers(decision: &Decision) -> HeaderMap {
+    HeaderMap::from_iter([
+        (RATELIMIT_LIMIT, HeaderValue::from(decision.limit)),
+        (RATELIMIT_REMAINING, HeaderValue::from(decision.remaining)),
+        (
+            RATELIMIT_RESET,
+            HeaderValue::from(ceil_secs(decision.reset_after)),
+        ),
+    ])
+}
+
+fn ceil_secs(duration: Duration) -> u64 {
+    duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::router::create_router;
+    use crate::app::state::{AppState, SharedState};
+    use crate::config::{Budget, RateLimitConfig};
+    use crate::rate_limit::RateLimiter;
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode, header::AUTHORIZATION},
+    };
+    use std::sync::Arc;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    fn state(config: RateLimitConfig) -> SharedState {
+        let mut state = Arc::into_inner(AppState::for_tests()).expect("state is not shared yet");
+        state.rate_limiter = RateLimiter::in_memory(config);
+        SharedState::new(state)
+    }
+
+    fn config(burst: u32) -> RateLimitConfig {
+        RateLimitConfig {
+            enabled: true,
+            trust_forwarded_for: true,
+            default: Budget {
+                burst,
+                per_minute: 1,
+            },
+            routes: vec!["GET /tags=1/1".parse().unwrap()],
+        }
+    }
+
+    async fn get(
+        state: &SharedState,
+        uri: &str,
+        headers: &[(&str, &str)],
+    ) -> axum::response::Response {
+        let mut request = Request::get(uri);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        create_router(state.clone())
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn over_budget_requests_get_429_with_retry_after() {
+        let state = state(config(2));
+        let client = [("x-forwarded-for", "203.0.113.7")];
+
+        let first = get(&state, "/authors/not-a-uuid", &client).await;
+        assert_ne!(first.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(first.headers()["ratelimit-limit"], "2");
+        assert_eq!(first.headers()["ratelimit-remaining"], "1");
+
+        get(&state, "/authors/not-a-uuid", &client).await;
+        let limited = get(&state, "/authors/not-a-uuid", &client).await;
+        assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(limited.headers()["ratelimit-remaining"], "0");
+        assert_eq!(limited.headers()["retry-after"], "60");
+
+        // Another address has its own bucket.
+        let other = get(
+            &state,
+            "/authors/not-a-uuid",
+            &[("x-forwarded-for", "198.51.100.1")],
+        )
+        .await;
+        assert_ne!(other.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        // Health probes are never limited.
+        assert_eq!(
+            get(&state, "/health/live", &client).await.status(),
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn routes_and_authors_get_their_own_buckets() {
+        let state = state(config(5));
+        let token = state.jwt.issue(Uuid::new_v4()).unwrap();
+        let bearer = format!("Bearer {token}");
+
+        assert_ne!(
+            get(&state, "/tags", &[]).await.status(),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+        let limited = get(&state, "/tags", &[]).await;
+        assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(limited.headers()["ratelimit-limit"], "1");
+
+        // The same connection, signed in, is keyed by author instead.
+        let signed_in = get(&state, "/tags", &[(AUTHORIZATION.as_str(), &bearer)]).await;
+        assert_ne!(signed_in.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        // The route budget does not spend the default one.
+        let elsewhere = get(&state, "/authors/not-a-uuid", &[]).await;
+        assert_eq!(elsewhere.headers()["ratelimit-remaining"], "4");
+    }
+}
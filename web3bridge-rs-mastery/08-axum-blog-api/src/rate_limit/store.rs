@@ -0,0 +1,157 @@
+use crate::config::Budget;
+use crate::error::AppError;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How often the in-memory store drops buckets that have refilled completely.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Outcome of taking a token, with what the `RateLimit-*` headers report.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Decision {
+    pub allowed: bool,
+    pub limit: u32,
+    pub remaining: u32,
+    /// Until the next token is available; zero when the request was allowed.
+    pub retry_after: Duration,
+    /// Until the bucket is full again.
+    pub reset_after: Duration,
+}
+
+/// Where token buckets live. The in-memory store is per process; a shared backend
+/// (Redis, Postgres) can implement this to give several instances one budget.
+#[async_trait]
+pub trait RateLimitStore: Debug + Send + Sync {
+    /// Takes one token from the bucket under `key`, which starts out full.
+    async fn acquire(&self, key: &str, budget: Budget) -> Result<Decision, AppError>;
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryRateLimitStore {
+    buckets: Mutex<Buckets>,
+}
+
+#[derive(Debug, Default)]
+struct Buckets {
+    by_key: HashMap<String, Bucket>,
+    last_sweep: Option<Instant>,
+}
+
+#[async_trait]
+impl RateLimitStore for InMemoryRateLimitStore {
+    async fn acquire(&self, key: &str, budget: Budget) -> Result<Decision, AppError> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().expect("rate limit store poisoned");
+
+        // A full bucket behaves exactly like a missing one, so idle clients can be forgotten.
+        if buckets
+            .last_sweep
+            .is_none_or(|swept| now.duration_since(swept) >= SWEEP_INTERVAL)
+        {
+            buckets.by_key.retain(|_, bucket| bucket.full_at > now);
+            buckets.last_sweep = Some(now);
+        }
+
+        let bucket = buckets
+            .by_key
+            .entry(key.to_string())
+            .or_insert_with(|| Bucket::full(budget, now));
+        Ok(bucket.take(budget, now))
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+    full_at: Instant,
+}
+
+impl Bucket {
+    fn full(budget: Budget, now: Instant) -> Self {
+        Self {
+            tokens: f64::from(budget.burst),
+            updated_at: now,
+            full_at: now,
+        }
+    }
+
+    fn take(&mut self, budget: Budget, now: Instant) -> Decision {
+        let burst = f64::from(budget.burst);
+        let per_second = f64::from(budget.per_minute) / 60.0;
+
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * per_second).min(burst);
+        self.updated_at = now;
+
+        let allowed = self.tokens >= 1.0;
+        let retry_after = if allowed {
+            self.tokens -= 1.0;
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((1.0 - self.tokens) / per_second)
+        };
+        let reset_after = Duration::from_secs_f64((burst - self.tokens) / per_second);
+        self.full_at = now + reset_after;
+
+        Decision {
+            allowed,
+            limit: budget.burst,
+            remaining: self.tokens.floor() as u32,
+            retry_after,
+            reset_after,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUDGET: Budget = Budget {
+        burst: 2,
+        per_minute: 30,
+    };
+
+    #[test]
+    fn bucket_empties_then_refills_over_time() {
+        let start = Instant::now();
+        let mut bucket = Bucket::full(BUDGET, start);
+
+        let first = bucket.take(BUDGET, start);
+        assert!(first.allowed);
+        assert_eq!(first.remaining, 1);
+        assert_eq!(first.reset_after, Duration::from_secs(2));
+
+        assert!(bucket.take(BUDGET, start).allowed);
+        let denied = bucket.take(BUDGET, start);
+        assert!(!denied.allowed);
+        assert_eq!(denied.remaining, 0);
+        // 30 per minute is one token every two seconds.
+        assert_eq!(denied.retry_after, Duration::from_secs(2));
+
+        let later = start + Duration::from_secs(2);
+        assert!(bucket.take(BUDGET, later).allowed);
+        assert!(!bucket.take(BUDGET, later).allowed);
+
+        // Refilling stops at the burst size.
+        let much_later = later + Duration::from_secs(600);
+        assert_eq!(bucket.take(BUDGET, much_later).remaining, 1);
+    }
+
+    #[tokio::test]
+    async fn keys_have_separate_buckets() {
+        let store = InMemoryRateLimitStore::default();
+        let single = Budget {
+            burst: 1,
+            per_minute: 1,
+        };
+
+        assert!(store.acquire("a", single).await.unwrap().allowed);
+        assert!(!store.acquire("a", single).await.unwrap().allowed);
+        assert!(store.acquire("b", single).await.unwrap().allowed);
+    }
+}
@@ -0,0 +1,20 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// JSON body of the plain acknowledgements returned by deletes, webhooks and the health check.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MessageResponse {
+    /// Always `"ok"`.
+    #[schema(example = "ok")]
+    pub status: &'static str,
+    pub message: String,
+}
+
+impl MessageResponse {
+    pub fn ok(message: impl Into<String>) -> Self {
+        Self {
+            status: "ok",
+            message: message.into(),
+        }
+    }
+}
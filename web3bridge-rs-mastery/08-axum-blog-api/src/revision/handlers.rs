@@ -0,0 +1,285 @@
+use crate::app::state::SharedState;
+use crate::auth::AuthAuthor;
+use crate::error::{AppError, ErrorCode, ErrorResponse};
+use crate::extract::Path;
+use crate::markdown;
+use crate::post::{
+    self, PostChanges,
+    models::{Post, PostResponse},
+};
+use crate::revision::models::{RevisionDiffResponse, RevisionListResponse, RevisionResponse};
+use crate::validation::parse_uuid;
+use axum::{Json, extract::State, http::HeaderMap, response::IntoResponse};
+
+#[utoipa::path(
+    get,
+    path = "/posts/{post_id}/revisions",
+    tag = "revisions",
+    params(("post_id" = String, Path, description = "Post UUID")),
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Revision history, newest first", body = RevisionListResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not the owner", body = ErrorResponse),
+        (status = 404, description = "Post not found", body = ErrorResponse),
+        (status = 422, description = "Invalid input", body = ErrorResponse),
+    )
+)]
+pub async fn get_revisions(
+    State(state): State<SharedState>,
+    auth: AuthAuthor,
+    Path(post_id): Path<String>,
+) -> Result<Json<RevisionListResponse>, AppError> {
+    let post = find_owned_post(&state, &auth, &post_id).await?;
+
+    let data = state
+        .posts
+        .revisions(post.id)
+        .await?
+        .into_iter()
+        .map(RevisionResponse::from)
+        .collect();
+
+    Ok(Json(RevisionListResponse { data }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/posts/{post_id}/revisions/{revision}/diff",
+    tag = "revisions",
+    params(
+        ("post_id" = String, Path, description = "Post UUID"),
+        ("revision" = i32, Path, description = "Revision number, starting at 1"),
+    ),
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Changes made by this revision", body = RevisionDiffResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not the owner", body = ErrorResponse),
+        (status = 404, description = "Post or revision not found", body = ErrorResponse),
+        (status = 422, description = "Invalid input", body = ErrorResponse),
+    )
+)]
+pub async fn get_revision_diff(
+    State(state): State<SharedState>,
+    auth: AuthAuthor,
+    Path((post_id, revision)): Path<(String, String)>,
+) -> Result<Json<RevisionDiffResponse>, AppError> {
+    let post = find_owned_post(&state, &auth, &post_id).await?;
+    let revision = parse_revision(&revision)?;
+
+    let to = state.posts.revision(post.id, revision).await?;
+    let from = match revision {
+        1 => None,
+        _ => Some(state.posts.revision(post.id, revision - 1).await?),
+    };
+
+    Ok(Json(RevisionDiffResponse::between(from.as_ref(), &to)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/posts/{post_id}/revisions/{revision}/restore",
+    tag = "revisions",
+    params(
+        ("post_id" = String, Path, description = "Post UUID"),
+        ("revision" = i32, Path, description = "Revision number to roll back to"),
+        ("If-Match" = String, Header, description = "ETag from the last read of the post"),
+    ),
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Post with the revision's title and content, saved as a new revision", body = PostResponse, headers(("ETag" = String))),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not the owner", body = ErrorResponse),
+        (status = 404, description = "Post or revision not found", body = ErrorResponse),
+        (status = 412, description = "Changed since the given ETag", body = ErrorResponse),
+        (status = 422, description = "Invalid input", body = ErrorResponse),
+        (status = 428, description = "If-Match is missing", body = ErrorResponse),
+    )
+)]
+pub async fn restore_revision(
+    State(state): State<SharedState>,
+    auth: AuthAuthor,
+    headers: HeaderMap,
+    Path((post_id, revision)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    let current = find_owned_post(&state, &auth, &post_id).await?;
+    let revision = parse_revision(&revision)?;
+    current.etag().require_if_match(&headers)?;
+
+    let restored = state.posts.revision(current.id, revision).await?;
+    let content_html = markdown::render(&restored.content);
+
+    // Only the text is rolled back; status, schedule, tags and premium flag stay as they are.
+    let post = state
+        .posts
+        .update(
+            current.id,
+            current.version,
+            PostChanges {
+                title: Some(&restored.title),
+                content: Some(&restored.content),
+                content_html: Some(&content_html),
+                status: current.status,
+                published_at: current.published_at,
+                is_premium: None,
+                tags: None,
+                restored_from: Some(restored.revision),
+            },
+        )
+        .await?;
+
+    Ok((post.etag(), Json(PostResponse::from(post))))
+}
+
+// History can hold text the author never published, so only the author may read it.
+async fn find_owned_post(
+    state: &SharedState,
+    auth: &AuthAuthor,
+    post_id: &str,
+) -> Result<Post, AppError> {
+    let post_id = parse_uuid("post_id", post_id)?;
+    let post = post::find_post_by_id(state, post_id).await?;
+
+    if !post.is_visible_to(Some(auth.author_id)) {
+        return Err(AppError::NotFound(
+            ErrorCode::PostNotFound,
+            format!("Post not found: {post_id}"),
+        ));
+    }
+
+    auth.ensure_owner(post.author_id)?;
+    Ok(post)
+}
+
+fn parse_revision(value: &str) -> Result<i32, AppError> {
+    value
+        .parse::<i32>()
+        .ok()
+        .filter(|revision| *revision >= 1)
+        .ok_or_else(|| {
+            AppError::invalid(
+                "revision",
+                ErrorCode::ValidationInvalidNumber,
+                "Revision must be a positive integer",
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::state::AppState;
+    use crate::app::test_support::{current_etag, send, send_with_headers, signed_in_author};
+    use axum::http::{Method, StatusCode, header::IF_MATCH};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn edits_are_recorded_diffed_and_restorable() {
+        let state = AppState::for_tests_in_memory();
+        let (_, token) = signed_in_author(&state, "writer@example.com").await;
+        let body = json!({ "title": "First", "content": "one\ntwo" });
+        let (_, created) = send(&state, Method::POST, "/posts", Some(&token), Some(body)).await;
+        let post_uri = format!("/posts/{}", created["id"].as_str().unwrap());
+
+        let etag = current_etag(&state, &post_uri, None).await;
+        let edit = json!({ "title": "Second", "content": "one\n2" });
+        let (status, _, _) = send_with_headers(
+            &state,
+            Method::PATCH,
+            &post_uri,
+            Some(&token),
+            &[(IF_MATCH, &etag)],
+            Some(edit),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, history) = send(
+            &state,
+            Method::GET,
+            &format!("{post_uri}/revisions"),
+            Some(&token),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(history["data"][0]["revision"], 2);
+        assert_eq!(history["data"][1]["title"], "First");
+
+        let (status, diff) = send(
+            &state,
+            Method::GET,
+            &format!("{post_uri}/revisions/2/diff"),
+            Some(&token),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(diff["from_revision"], 1);
+        assert_eq!(diff["title_before"], "First");
+        assert_eq!(
+            diff["diff"],
+            "--- revision 1\n+++ revision 2\n@@ -1,2 +1,2 @@\n one\n-two\n+2\n"
+        );
+
+        let restore_uri = format!("{post_uri}/revisions/1/restore");
+        let (status, _) = send(&state, Method::POST, &restore_uri, Some(&token), None).await;
+        assert_eq!(status, StatusCode::PRECONDITION_REQUIRED);
+
+        let etag = current_etag(&state, &post_uri, None).await;
+        let (status, _, restored) = send_with_headers(
+            &state,
+            Method::POST,
+            &restore_uri,
+            Some(&token),
+            &[(IF_MATCH, &etag)],
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(restored["title"], "First");
+        assert_eq!(restored["content"], "one\ntwo");
+
+        let (_, history) = send(
+            &state,
+            Method::GET,
+            &format!("{post_uri}/revisions"),
+            Some(&token),
+            None,
+        )
+        .await;
+        assert_eq!(history["data"][0]["revision"], 3);
+        assert_eq!(history["data"][0]["restored_from"], 1);
+    }
+
+    #[tokio::test]
+    async fn history_is_private_to_the_author() {
+        let state = AppState::for_tests_in_memory();
+        let (_, token) = signed_in_author(&state, "writer@example.com").await;
+        let (_, other_token) = signed_in_author(&state, "reader@example.com").await;
+        let body = json!({ "title": "Post", "content": "Body" });
+        let (_, created) = send(&state, Method::POST, "/posts", Some(&token), Some(body)).await;
+        let revisions_uri = format!("/posts/{}/revisions", created["id"].as_str().unwrap());
+
+        let (status, _) = send(&state, Method::GET, &revisions_uri, None, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = send(
+            &state,
+            Method::GET,
+            &revisions_uri,
+            Some(&other_token),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let uri = format!("{revisions_uri}/0/diff");
+        let (status, error) = send(&state, Method::GET, &uri, Some(&token), None).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error["errors"][0]["field"], "revision");
+
+        let uri = format!("{revisions_uri}/7/diff");
+        let (status, _) = send(&state, Method::GET, &uri, Some(&token), None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,29 @@
+mod handlers;
+pub mod models;
+mod queries;
+
+use crate::app::state::SharedState;
+use axum::{
+    Router,
+    routing::{get, post},
+};
+use utoipa::OpenApi;
+
+pub use queries::{find_revision, list_revisions, record_revision};
+
+/// OpenAPI paths for the routes under `/posts/{post_id}/revisions`.
+#[derive(OpenApi)]
+#[openapi(paths(
+    handlers::get_revisions,
+    handlers::get_revision_diff,
+    handlers::restore_revision,
+))]
+pub struct RevisionApi;
+
+/// Routes nested under `/posts/{post_id}/revisions`.
+pub fn revision_routes() -> Router<SharedState> {
+    Router::new()
+        .route("/", get(handlers::get_revisions))
+        .route("/{revision}/diff", get(handlers::get_revision_diff))
+        .route("/{revision}/restore", post(handlers::restore_revision))
+}
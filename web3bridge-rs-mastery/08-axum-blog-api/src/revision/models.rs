@@ -0,0 +1,129 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use similar::TextDiff;
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Title and content of a post as they stood after one write.
+#[derive(Debug, Clone, FromRow)]
+pub struct PostRevision {
+    pub post_id: Uuid,
+    pub revision: i32,
+    pub title: String,
+    pub content: String,
+    pub restored_from: Option<i32>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A revision without its content; use the diff endpoint to see what changed.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RevisionResponse {
+    pub revision: i32,
+    pub title: String,
+    /// The older revision this one was restored from, if any.
+    pub restored_from: Option<i32>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RevisionListResponse {
+    /// Newest first.
+    pub data: Vec<RevisionResponse>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RevisionDiffResponse {
+    pub post_id: String,
+    /// `null` for the first revision, which is compared against an empty post.
+    pub from_revision: Option<i32>,
+    pub to_revision: i32,
+    pub title_before: Option<String>,
+    pub title_after: String,
+    /// Line-level unified diff of the content.
+    #[schema(example = "--- revision 1\n+++ revision 2\n@@ -1 +1 @@\n-Hello\n+Hello, world\n")]
+    pub diff: String,
+}
+
+impl From<PostRevision> for RevisionResponse {
+    fn from(value: PostRevision) -> Self {
+        Self {
+            revision: value.revision,
+            title: value.title,
+            restored_from: value.restored_from,
+            created_at: value.created_at,
+        }
+    }
+}
+
+impl RevisionDiffResponse {
+    /// Compares `to` with the revision before it, or with nothing when `from` is `None`.
+    pub fn between(from: Option<&PostRevision>, to: &PostRevision) -> Self {
+        let old = from.map_or("", |revision| revision.content.as_str());
+        let old_header = from.map_or_else(
+            || "/dev/null".to_string(),
+            |revision| format!("revision {}", revision.revision),
+        );
+        let diff = TextDiff::from_lines(old, &to.content)
+            .unified_diff()
+            .context_radius(3)
+            .header(&old_header, &format!("revision {}", to.revision))
+            .missing_newline_hint(false)
+            .to_string();
+
+        Self {
+            post_id: to.post_id.to_string(),
+            from_revision: from.map(|revision| revision.revision),
+            to_revision: to.revision,
+            title_before: from.map(|revision| revision.title.clone()),
+            title_after: to.title.clone(),
+            diff,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn revision(revision: i32, content: &str) -> PostRevision {
+        PostRevision {
+            post_id: Uuid::nil(),
+            revision,
+            title: format!("Title {revision}"),
+            content: content.to_string(),
+            restored_from: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn diff_is_line_level_and_unified() {
+        let before = revision(1, "one\ntwo\nthree");
+        let after = revision(2, "one\n2\nthree");
+
+        let diff = RevisionDiffResponse::between(Some(&before), &after);
+        assert_eq!(
+            diff.diff,
+            "--- revision 1\n+++ revision 2\n@@ -1,3 +1,3 @@\n one\n-two\n+2\n three\n"
+        );
+        assert_eq!(diff.title_before.as_deref(), Some("Title 1"));
+    }
+
+    #[test]
+    fn first_revision_is_diffed_against_nothing() {
+        let diff = RevisionDiffResponse::between(None, &revision(1, "hello"));
+
+        assert_eq!(diff.from_revision, None);
+        assert_eq!(
+            diff.diff,
+            "--- /dev/null\n+++ revision 1\n@@ -0,0 +1 @@\n+hello\n"
+        );
+    }
+
+    #[test]
+    fn identical_content_has_an_empty_diff() {
+        let diff = RevisionDiffResponse::between(Some(&revision(1, "same")), &revision(2, "same"));
+        assert_eq!(diff.diff, "");
+    }
+}
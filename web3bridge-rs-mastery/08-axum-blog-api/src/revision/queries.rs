@@ -0,0 +1,58 @@
+use crate::db::{DbConnection, DbPool};
+use crate::error::{AppError, ErrorCode};
+use crate::post::models::Post;
+use crate::revision::models::PostRevision;
+use uuid::Uuid;
+
+/// Appends the post's current title and content as its next revision.
+///
+/// Runs on the caller's connection so the snapshot commits with the write that produced it.
+pub async fn record_revision(
+    conn: &mut DbConnection,
+    post: &Post,
+    restored_from: Option<i32>,
+) -> Result<(), AppError> {
+    sqlx::query(
+        "INSERT INTO post_revisions (id, post_id, revision, title, content, restored_from) SELECT $1, $2, COALESCE(MAX(revision), 0) + 1, $3, $4, $5 FROM post_revisions WHERE post_id = $2",
+    )
+    .bind(Uuid::new_v4())
+    .bind(post.id)
+    .bind(&post.title)
+    .bind(&post.content)
+    .bind(restored_from)
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| AppError::InternalServerError(format!("Failed to record revision: {err}")))?;
+
+    Ok(())
+}
+
+pub async fn list_revisions(pool: &DbPool, post_id: Uuid) -> Result<Vec<PostRevision>, AppError> {
+    let revisions = sqlx::query_as::<_, PostRevision>(
+        "SELECT post_id, revision, title, content, restored_from, created_at FROM post_revisions WHERE post_id = $1 ORDER BY revision DESC",
+    )
+    .bind(post_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|err| AppError::InternalServerError(format!("Failed to fetch revisions: {err}")))?;
+
+    Ok(revisions)
+}
+
+pub async fn find_revision(
+    pool: &DbPool,
+    post_id: Uuid,
+    revision: i32,
+) -> Result<PostRevision, AppError> {
+    let revision = sqlx::query_as::<_, PostRevision>(
+        "SELECT post_id, revision, title, content, restored_from, created_at FROM post_revisions WHERE post_id = $1 AND revision = $2",
+    )
+    .bind(post_id)
+    .bind(revision)
+    .fetch_optional(pool)
+    .await
+    .map_err(|err| AppError::InternalServerError(format!("Failed to fetch revision: {err}")))?
+    .ok_or_else(|| AppError::NotFound(ErrorCode::RevisionNotFound, format!("Revision not found: {revision}")))?;
+
+    Ok(revision)
+}
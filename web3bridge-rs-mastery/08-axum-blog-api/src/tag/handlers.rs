@@ -0,0 +1,40 @@
+use crate::app::state::SharedState;
+use crate::error::{AppError, ErrorResponse};
+use crate::extract::Path;
+use crate::tag::models::{TagListResponse, TagResponse};
+use crate::tag::{normalize_tag, queries};
+use axum::{Json, extract::State};
+
+#[utoipa::path(
+    get,
+    path = "/tags",
+    tag = "tags",
+    responses((status = 200, description = "All tags with published post counts", body = TagListResponse))
+)]
+pub async fn get_tags(State(state): State<SharedState>) -> Result<Json<TagListResponse>, AppError> {
+    let tags = queries::list_tags(&state).await?;
+    let total = tags.len();
+    let data = tags.into_iter().map(TagResponse::from).collect();
+
+    Ok(Json(TagListResponse { data, total }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/tags/{name}",
+    tag = "tags",
+    params(("name" = String, Path, description = "Tag name")),
+    responses(
+        (status = 200, description = "The tag", body = TagResponse),
+        (status = 404, description = "Tag not found", body = ErrorResponse),
+        (status = 422, description = "Invalid input", body = ErrorResponse),
+    )
+)]
+pub async fn get_tag_by_name(
+    State(state): State<SharedState>,
+    Path(name): Path<String>,
+) -> Result<Json<TagResponse>, AppError> {
+    let name = normalize_tag(&name)?;
+    let tag = queries::find_tag_by_name(&state, &name).await?;
+    Ok(Json(TagResponse::from(tag)))
+}
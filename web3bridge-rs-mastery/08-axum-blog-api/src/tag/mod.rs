@@ -0,0 +1,77 @@
+mod handlers;
+pub mod models;
+mod queries;
+
+use crate::app::state::SharedState;
+use crate::error::{AppError, ErrorCode};
+use axum::{Router, routing::get};
+use utoipa::OpenApi;
+
+pub use queries::{set_post_tags, tags_for_posts};
+
+pub const MAX_TAG_LENGTH: usize = 64;
+pub const MAX_TAGS_PER_POST: usize = 20;
+
+/// OpenAPI paths for the routes under `/tags`.
+#[derive(OpenApi)]
+#[openapi(paths(handlers::get_tags, handlers::get_tag_by_name))]
+pub struct TagApi;
+
+pub fn tag_routes() -> Router<SharedState> {
+    Router::new()
+        .route("/", get(handlers::get_tags))
+        .route("/{name}", get(handlers::get_tag_by_name))
+}
+
+/// Tags are stored trimmed and lowercased so `Rust` and ` rust ` are the same tag.
+pub fn normalize_tag(raw: &str) -> Result<String, AppError> {
+    let name = raw.trim().to_lowercase();
+
+    if name.is_empty() || name.chars().count() > MAX_TAG_LENGTH {
+        return Err(AppError::invalid(
+            "tags",
+            ErrorCode::ValidationInvalidLength,
+            format!("Tags must be between 1 and {MAX_TAG_LENGTH} characters"),
+        ));
+    }
+
+    Ok(name)
+}
+
+/// Normalizes and de-duplicates a list of tags, keeping first-seen order.
+pub fn normalize_tags(raw: &[String]) -> Result<Vec<String>, AppError> {
+    let mut tags: Vec<String> = Vec::with_capacity(raw.len());
+    for value in raw {
+        let name = normalize_tag(value)?;
+        if !tags.contains(&name) {
+            tags.push(name);
+        }
+    }
+
+    if tags.len() > MAX_TAGS_PER_POST {
+        return Err(AppError::invalid(
+            "tags",
+            ErrorCode::TagTooMany,
+            format!("A post can have at most {MAX_TAGS_PER_POST} tags"),
+        ));
+    }
+
+    Ok(tags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_are_trimmed_lowercased_and_deduplicated() {
+        let raw = vec![" Rust ".to_string(), "axum".to_string(), "RUST".to_string()];
+        assert_eq!(normalize_tags(&raw).unwrap(), vec!["rust", "axum"]);
+    }
+
+    #[test]
+    fn blank_or_oversized_tags_are_rejected() {
+        assert!(normalize_tag("   ").is_err());
+        assert!(normalize_tag(&"x".repeat(MAX_TAG_LENGTH + 1)).is_err());
+    }
+}
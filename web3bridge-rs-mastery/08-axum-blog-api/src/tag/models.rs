@@ -0,0 +1,46 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Tag {
+    pub id: Uuid,
+    pub name: String,
+    pub post_count: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TagMatch {
+    #[default]
+    Any,
+    All,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TagResponse {
+    pub id: String,
+    pub name: String,
+    pub post_count: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TagListResponse {
+    pub data: Vec<TagResponse>,
+    pub total: usize,
+}
+
+impl From<Tag> for TagResponse {
+    fn from(value: Tag) -> Self {
+        Self {
+            id: value.id.to_string(),
+            name: value.name,
+            post_count: value.post_count,
+            created_at: value.created_at,
+        }
+    }
+}
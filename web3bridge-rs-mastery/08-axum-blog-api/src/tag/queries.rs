@@ -0,0 +1,142 @@
+use crate::app::state::SharedState;
+use crate::db::{DbConnection, DbPool};
+use crate::error::{AppError, ErrorCode};
+use crate::tag::models::Tag;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// Only public posts count towards a tag, so drafts never leak through the tag list.
+const TAG_SELECT: &str = "SELECT t.id, t.name, t.created_at, COUNT(p.id) AS post_count FROM tags t LEFT JOIN post_tags pt ON pt.tag_id = t.id LEFT JOIN posts p ON p.id = pt.post_id AND p.status = 'published' AND p.deleted_at IS NULL";
+
+pub async fn list_tags(state: &SharedState) -> Result<Vec<Tag>, AppError> {
+    let tags = sqlx::query_as::<_, Tag>(&format!(
+        "{TAG_SELECT} GROUP BY t.id ORDER BY post_count DESC, t.name ASC"
+    ))
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(|err| AppError::InternalServerError(format!("Failed to fetch tags: {err}")))?;
+
+    Ok(tags)
+}
+
+pub async fn find_tag_by_name(state: &SharedState, name: &str) -> Result<Tag, AppError> {
+    let tag = sqlx::query_as::<_, Tag>(&format!("{TAG_SELECT} WHERE t.name = $1 GROUP BY t.id"))
+        .bind(name)
+        .fetch_optional(&state.db_pool)
+        .await
+        .map_err(|err| AppError::InternalServerError(format!("Failed to fetch tag: {err}")))?
+        .ok_or_else(|| {
+            AppError::NotFound(ErrorCode::TagNotFound, format!("Tag not found: {name}"))
+        })?;
+
+    Ok(tag)
+}
+
+/// Replaces the tags on `post_id` with `names`, creating any tag that does not exist yet.
+///
+/// Runs on the caller's connection so it can share the post write's transaction.
+pub async fn set_post_tags(
+    conn: &mut DbConnection,
+    post_id: Uuid,
+    names: &[String],
+) -> Result<(), AppError> {
+    create_tags(conn, names)
+        .await
+        .map_err(|err| AppError::InternalServerError(format!("Failed to create tags: {err}")))?;
+
+    sqlx::query("DELETE FROM post_tags WHERE post_id = $1")
+        .bind(post_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|err| {
+            AppError::InternalServerError(format!("Failed to clear post tags: {err}"))
+        })?;
+
+    #[cfg(feature = "postgres")]
+    let link = sqlx::query(
+        "INSERT INTO post_tags (post_id, tag_id) SELECT $1, id FROM tags WHERE name = ANY($2)",
+    )
+    .bind(post_id)
+    .bind(names);
+    #[cfg(feature = "sqlite")]
+    let link = sqlx::query(
+        "INSERT INTO post_tags (post_id, tag_id) SELECT $1, id FROM tags WHERE name IN (SELECT value FROM json_each($2))",
+    )
+    .bind(post_id)
+    .bind(sqlx::types::Json(names));
+
+    link.execute(&mut *conn)
+        .await
+        .map_err(|err| AppError::InternalServerError(format!("Failed to tag post: {err}")))?;
+
+    Ok(())
+}
+
+#[cfg(feature = "postgres")]
+async fn create_tags(conn: &mut DbConnection, names: &[String]) -> Result<(), sqlx::Error> {
+    let ids: Vec<Uuid> = names.iter().map(|_| Uuid::new_v4()).collect();
+
+    sqlx::query(
+        "INSERT INTO tags (id, name) SELECT * FROM UNNEST($1::uuid[], $2::text[]) ON CONFLICT (name) DO NOTHING",
+    )
+    .bind(&ids)
+    .bind(names)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+// No UNNEST in SQLite; posts carry a handful of tags, so one insert each is cheap.
+#[cfg(feature = "sqlite")]
+async fn create_tags(conn: &mut DbConnection, names: &[String]) -> Result<(), sqlx::Error> {
+    for name in names {
+        sqlx::query("INSERT INTO tags (id, name) VALUES ($1, $2) ON CONFLICT (name) DO NOTHING")
+            .bind(Uuid::new_v4())
+            .bind(name)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Loads tag names for many posts in one round trip, keyed by post id.
+pub async fn tags_for_posts(
+    pool: &DbPool,
+    post_ids: &[Uuid],
+) -> Result<HashMap<Uuid, Vec<String>>, AppError> {
+    #[cfg(feature = "postgres")]
+    let rows = sqlx::query_as::<_, (Uuid, String)>(
+        "SELECT pt.post_id, t.name FROM post_tags pt JOIN tags t ON t.id = pt.tag_id WHERE pt.post_id = ANY($1) ORDER BY t.name",
+    )
+    .bind(post_ids)
+    .fetch_all(pool)
+    .await;
+    // UUIDs are blobs in SQLite, which a JSON list cannot carry, so each id gets its own bind.
+    #[cfg(feature = "sqlite")]
+    let rows = {
+        let mut builder = sqlx::QueryBuilder::new(
+            "SELECT pt.post_id, t.name FROM post_tags pt JOIN tags t ON t.id = pt.tag_id WHERE pt.post_id IN (",
+        );
+        let mut ids = builder.separated(", ");
+        for id in post_ids {
+            ids.push_bind(*id);
+        }
+        builder.push(") ORDER BY t.name");
+        builder
+            .build_query_as::<(Uuid, String)>()
+            .fetch_all(pool)
+            .await
+    };
+    let rows = rows.map_err(|err| {
+        AppError::InternalServerError(format!("Failed to fetch post tags: {err}"))
+    })?;
+
+    let mut tags: HashMap<Uuid, Vec<String>> = HashMap::new();
+    for (post_id, name) in rows {
+        tags.entry(post_id).or_default().push(name);
+    }
+
+    Ok(tags)
+}
@@ -0,0 +1,222 @@
+use crate::app::state::SharedState;
+use crate::cache::CacheCounters;
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use prometheus_client::encoding::{EncodeLabelSet, text::encode};
+use prometheus_client::metrics::counter::Counter;
+use prometheus_client::metrics::family::Family;
+use prometheus_client::metrics::gauge::Gauge;
+use prometheus_client::metrics::histogram::{Histogram, exponential_buckets};
+use prometheus_client::registry::{Registry, Unit};
+use std::sync::Arc;
+use std::time::Instant;
+
+const CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct RequestLabels {
+    method: String,
+    path: String,
+    status: u16,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct RouteLabels {
+    method: String,
+    path: String,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct CacheLabels {
+    cache: String,
+}
+
+type LatencyFamily = Family<RouteLabels, Histogram, fn() -> Histogram>;
+
+/// Prometheus metrics for HTTP traffic, the database pool and the read caches.
+#[derive(Debug, Clone)]
+pub struct Metrics {
+    registry: Arc<Registry>,
+    requests: Family<RequestLabels, Counter>,
+    latency: LatencyFamily,
+    cache_hits: Family<CacheLabels, Counter>,
+    cache_misses: Family<CacheLabels, Counter>,
+    pool_connections: Gauge,
+    pool_idle_connections: Gauge,
+    pool_max_connections: Gauge,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let requests = Family::<RequestLabels, Counter>::default();
+        // 5ms up to ~10s.
+        let latency: LatencyFamily =
+            Family::new_with_constructor(|| Histogram::new(exponential_buckets(0.005, 2.0, 12)));
+        let pool_connections = Gauge::default();
+        let pool_idle_connections = Gauge::default();
+        let pool_max_connections = Gauge::default();
+        let cache_hits = Family::<CacheLabels, Counter>::default();
+        let cache_misses = Family::<CacheLabels, Counter>::default();
+
+        let mut registry = Registry::default();
+        registry.register(
+            "http_requests",
+            "HTTP requests by method, route and status code",
+            requests.clone(),
+        );
+        registry.register_with_unit(
+            "http_request_duration",
+            "HTTP request latency by method and route",
+            Unit::Seconds,
+            latency.clone(),
+        );
+        registry.register(
+            "db_pool_connections",
+            "Open database connections",
+            pool_connections.clone(),
+        );
+        registry.register(
+            "db_pool_idle_connections",
+            "Idle database connections",
+            pool_idle_connections.clone(),
+        );
+        registry.register(
+            "db_pool_max_connections",
+            "Configured maximum database connections",
+            pool_max_connections.clone(),
+        );
+        registry.register(
+            "cache_hits",
+            "Reads answered from the in-process cache, by cache",
+            cache_hits.clone(),
+        );
+        registry.register(
+            "cache_misses",
+            "Reads that went to the database, by cache",
+            cache_misses.clone(),
+        );
+
+        Self {
+            registry: Arc::new(registry),
+            requests,
+            latency,
+            cache_hits,
+            cache_misses,
+            pool_connections,
+            pool_idle_connections,
+            pool_max_connections,
+        }
+    }
+
+    fn observe(&self, method: &str, path: &str, status: u16, seconds: f64) {
+        let route = RouteLabels {
+            method: method.to_string(),
+            path: path.to_string(),
+        };
+        self.latency.get_or_create(&route).observe(seconds);
+        self.requests
+            .get_or_create(&RequestLabels {
+                method: route.method,
+                path: route.path,
+                status,
+            })
+            .inc();
+    }
+
+    /// Counters for the cache `name`; asking twice for a name shares the counters.
+    pub fn cache_counters(&self, name: &str) -> CacheCounters {
+        let labels = CacheLabels {
+            cache: name.to_string(),
+        };
+        CacheCounters {
+            hits: self.cache_hits.get_or_create(&labels).clone(),
+            misses: self.cache_misses.get_or_create(&labels).clone(),
+        }
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Records a count and latency sample for every request.
+///
+/// Routes are labelled by their pattern (`/posts/{id}`) so ids never become labels;
+/// requests that match no route share the `unmatched` label.
+pub async fn track_metrics(
+    State(state): State<SharedState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().to_string();
+    let path = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+
+    let started = Instant::now();
+    let response = next.run(request).await;
+    state.metrics.observe(
+        &method,
+        &path,
+        response.status().as_u16(),
+        started.elapsed().as_secs_f64(),
+    );
+
+    response
+}
+
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "meta",
+    responses((status = 200, description = "Prometheus metrics", body = String, content_type = "application/openmetrics-text"))
+)]
+pub async fn get_metrics(State(state): State<SharedState>) -> impl IntoResponse {
+    let metrics = &state.metrics;
+    let pool = &state.db_pool;
+    metrics.pool_connections.set(i64::from(pool.size()));
+    metrics.pool_idle_connections.set(pool.num_idle() as i64);
+    metrics
+        .pool_max_connections
+        .set(i64::from(pool.options().get_max_connections()));
+
+    let mut body = String::new();
+    encode(&mut body, &metrics.registry).expect("writing to a String cannot fail");
+    ([(header::CONTENT_TYPE, CONTENT_TYPE)], body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_are_counted_per_route_and_status() {
+        let metrics = Metrics::new();
+        metrics.observe("GET", "/posts/{id}", 200, 0.01);
+        metrics.observe("GET", "/posts/{id}", 200, 0.02);
+        metrics.observe("GET", "/posts/{id}", 404, 0.01);
+
+        let mut body = String::new();
+        encode(&mut body, &metrics.registry).unwrap();
+
+        assert!(
+            body.contains(r#"http_requests_total{method="GET",path="/posts/{id}",status="200"} 2"#)
+        );
+        assert!(
+            body.contains(r#"http_requests_total{method="GET",path="/posts/{id}",status="404"} 1"#)
+        );
+        assert!(
+            body.contains(
+                r#"http_request_duration_seconds_count{method="GET",path="/posts/{id}"} 3"#
+            )
+        );
+    }
+}
@@ -0,0 +1,23 @@
+pub mod metrics;
+mod request_id;
+
+use crate::config::{LogConfig, LogFormat};
+use tracing_subscriber::EnvFilter;
+
+pub use metrics::{Metrics, get_metrics, track_metrics};
+pub use request_id::{current_request_id, request_context};
+
+/// Installs the global tracing subscriber.
+///
+/// Logs this crate at the configured level; `RUST_LOG` replaces that filter entirely
+/// when set. The JSON format writes one object per line for log shippers.
+pub fn init_tracing(config: &LogConfig) {
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(format!("axum_blog_api={}", config.level)));
+    let subscriber = tracing_subscriber::fmt().with_env_filter(filter);
+
+    match config.format {
+        LogFormat::Json => subscriber.json().init(),
+        LogFormat::Text => subscriber.init(),
+    }
+}
@@ -0,0 +1,131 @@
+use axum::{
+    extract::{MatchedPath, Request},
+    http::HeaderValue,
+    middleware::Next,
+    response::Response,
+};
+use std::time::Instant;
+use tracing::Instrument;
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// The id of the request being handled on this task, if any.
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// Assigns every request an id, runs it inside a tracing span and logs its outcome.
+///
+/// A well-formed incoming `x-request-id` is kept so ids line up across services;
+/// anything else is replaced with a fresh UUID. The id is echoed on the response.
+pub async fn request_context(mut request: Request, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| is_valid_request_id(value))
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let header = HeaderValue::from_str(&request_id).expect("request ids are visible ASCII");
+    request
+        .headers_mut()
+        .insert(REQUEST_ID_HEADER, header.clone());
+
+    let path = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+    let span = tracing::info_span!(
+        "http_request",
+        method = %request.method(),
+        path = %path,
+        request_id = %request_id,
+    );
+
+    let started = Instant::now();
+    let mut response = REQUEST_ID
+        .scope(request_id, next.run(request))
+        .instrument(span.clone())
+        .await;
+
+    span.in_scope(|| {
+        tracing::info!(
+            status = response.status().as_u16(),
+            latency_ms = started.elapsed().as_millis() as u64,
+            "request completed"
+        )
+    });
+    response.headers_mut().insert(REQUEST_ID_HEADER, header);
+    response
+}
+
+fn is_valid_request_id(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= MAX_REQUEST_ID_LENGTH
+        && value
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::router::create_router;
+    use crate::app::state::AppState;
+    use axum::{body::Body, http::StatusCode};
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    async fn get(uri: &str, request_id: Option<&str>) -> (StatusCode, String, Value) {
+        let router = create_router(AppState::for_tests());
+        let mut request = Request::get(uri);
+        if let Some(id) = request_id {
+            request = request.header(REQUEST_ID_HEADER, id);
+        }
+
+        let response = router
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let echoed = response.headers()[REQUEST_ID_HEADER]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (
+            status,
+            echoed,
+            serde_json::from_slice(&body).unwrap_or(Value::Null),
+        )
+    }
+
+    #[tokio::test]
+    async fn incoming_request_id_is_propagated_into_errors() {
+        let (status, echoed, body) = get("/posts/not-a-uuid", Some("edge-42")).await;
+
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(echoed, "edge-42");
+        assert_eq!(body["request_id"], "edge-42");
+    }
+
+    #[tokio::test]
+    async fn missing_or_malformed_ids_are_replaced() {
+        let (_, generated, body) = get("/posts/not-a-uuid", None).await;
+        assert!(Uuid::parse_str(&generated).is_ok());
+        assert_eq!(body["request_id"], generated.as_str());
+
+        let (_, replaced, _) = get("/health", Some("bad id with spaces")).await;
+        assert!(Uuid::parse_str(&replaced).is_ok());
+    }
+}
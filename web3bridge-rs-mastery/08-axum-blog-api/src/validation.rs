@@ -0,0 +1,307 @@
+//! Request body validation.
+//!
+//! Request structs declare their rules with `#[derive(Validate)]` and trim their strings with
+//! the `deserialize_with` helpers below; `ValidJson` runs both and answers with one `422`
+//! listing every rejected field. Ids are typed as `Uuid` and read with `uuid_opt`, so a
+//! malformed one is reported on its field like any other rule.
+
+use crate::error::{AppError, ErrorCode, FieldErrors};
+use crate::extract::rejected_field;
+use axum::{
+    Json,
+    extract::{FromRequest, Request, rejection::JsonRejection},
+};
+use email_address::{EmailAddress, Options};
+use serde::{Deserialize, Deserializer, de::DeserializeOwned, de::Error as _};
+use std::borrow::Cow;
+use uuid::Uuid;
+use validator::{Validate, ValidationError, ValidationErrors};
+
+/// Length of the `VARCHAR(255)` columns: author names and emails, post titles.
+pub const MAX_VARCHAR_LENGTH: u64 = 255;
+
+const INVALID_UUID: &str = "Invalid UUID";
+
+/// `Json<T>` that also checks `T`'s validation rules.
+///
+/// Unreadable JSON is a `422` on `body` rather than axum's plain-text rejection; a value of the
+/// wrong type is a `422` on its own field.
+#[derive(Debug)]
+pub struct ValidJson<T>(pub T);
+
+impl<S, T> FromRequest<S> for ValidJson<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned + Validate,
+{
+    type Rejection = AppError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(request, state)
+            .await
+            .map_err(rejected)?;
+        check(&value)?;
+        Ok(Self(value))
+    }
+}
+
+fn rejected(rejection: JsonRejection) -> AppError {
+    let Some((field, error)) = rejected_field::<serde_json::Error>(&rejection) else {
+        return AppError::invalid("body", ErrorCode::RequestInvalidBody, rejection.body_text());
+    };
+    let position = format!(" at line {} column {}", error.line(), error.column());
+    let message = error.to_string();
+    let message = message.strip_suffix(&position).unwrap_or(&message);
+    let code = if message == INVALID_UUID {
+        ErrorCode::ValidationInvalidUuid
+    } else {
+        ErrorCode::RequestInvalidBody
+    };
+    AppError::invalid(field, code, message)
+}
+
+/// Runs `value`'s rules, for input that does not arrive through `ValidJson`.
+pub fn check(value: &impl Validate) -> Result<(), AppError> {
+    let Err(failures) = value.validate() else {
+        return Ok(());
+    };
+
+    let mut errors = FieldErrors::default();
+    for (field, failure) in sorted(&failures) {
+        let (code, message) = describe(&field, failure);
+        errors.add(field, code, message);
+    }
+    errors.finish()
+}
+
+// `ValidationErrors` is a map; sorting keeps responses stable between requests.
+fn sorted(failures: &ValidationErrors) -> Vec<(Cow<'static, str>, &ValidationError)> {
+    let mut sorted: Vec<_> = failures
+        .field_errors()
+        .into_iter()
+        .flat_map(|(field, errors)| errors.iter().map(move |error| (field.clone(), error)))
+        .collect();
+    sorted.sort_by(|(left, _), (right, _)| left.cmp(right));
+    sorted
+}
+
+fn describe(field: &str, failure: &ValidationError) -> (ErrorCode, String) {
+    let label = label(field);
+    match failure.code.as_ref() {
+        "length" if failure.params.get("value").is_some_and(|value| value == "") => (
+            ErrorCode::ValidationRequired,
+            format!("{label} cannot be empty"),
+        ),
+        "length" => {
+            let bound = |name: &str| failure.params.get(name).and_then(|value| value.as_u64());
+            let message = match (bound("min"), bound("max")) {
+                (Some(min), Some(max)) => {
+                    format!("{label} must be between {min} and {max} characters")
+                }
+                (Some(min), None) => format!("{label} must be at least {min} characters"),
+                (None, Some(max)) => format!("{label} must be at most {max} characters"),
+                (None, None) => format!("{label} has an invalid length"),
+            };
+            (ErrorCode::ValidationInvalidLength, message)
+        }
+        "email" => (
+            ErrorCode::ValidationInvalidEmail,
+            "Invalid email format".to_string(),
+        ),
+        "blank" => (
+            ErrorCode::ValidationRequired,
+            format!("{label} cannot be empty"),
+        ),
+        other => (
+            ErrorCode::ValidationFailed,
+            format!("{label} failed the {other} check"),
+        ),
+    }
+}
+
+// `author_id` -> `Author id`, for messages.
+fn label(field: &str) -> String {
+    let words = field.replace('_', " ");
+    let mut chars = words.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => words,
+    }
+}
+
+/// Parses an id from a path or body field, as a `422` on `field` when it is not a UUID.
+pub fn parse_uuid(field: &str, value: &str) -> Result<Uuid, AppError> {
+    Uuid::parse_str(value)
+        .map_err(|_| AppError::invalid(field, ErrorCode::ValidationInvalidUuid, INVALID_UUID))
+}
+
+/// An RFC 5322 `addr-spec` with a dotted domain: no display names or IP literals.
+fn is_valid_email(email: &str) -> bool {
+    let options = Options::default()
+        .with_required_tld()
+        .without_display_text()
+        .without_domain_literal();
+    email.len() as u64 <= MAX_VARCHAR_LENGTH
+        && EmailAddress::parse_with_options(email, options).is_ok()
+}
+
+/// Rule for `#[validate(custom(function = "validation::email"))]`.
+pub fn email(value: &str) -> Result<(), ValidationError> {
+    if is_valid_email(value) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("email"))
+    }
+}
+
+/// Rule for `#[validate(custom(function = "validation::not_blank"))]`, for text that is kept
+/// as written, such as Markdown, where trimming would change its meaning.
+pub fn not_blank(value: &str) -> Result<(), ValidationError> {
+    if value.trim().is_empty() {
+        Err(ValidationError::new("blank"))
+    } else {
+        Ok(())
+    }
+}
+
+/// `deserialize_with` helper that trims surrounding whitespace.
+pub fn trim<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    String::deserialize(deserializer).map(|value| value.trim().to_string())
+}
+
+/// `trim` for optional fields; pair it with `#[serde(default)]`.
+pub fn trim_opt<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    Option::<String>::deserialize(deserializer)
+        .map(|value| value.map(|value| value.trim().to_string()))
+}
+
+/// `deserialize_with` helper for optional ids; pair it with `#[serde(default)]`.
+pub fn uuid_opt<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Uuid>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|value| Uuid::parse_str(&value).map_err(|_| D::Error::custom(INVALID_UUID)))
+        .transpose()
+}
+
+/// `deserialize_with` helper for emails, which are matched case-insensitively: trims and lowercases.
+pub fn email_address<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    trim(deserializer).map(|value| value.to_lowercase())
+}
+
+/// `email_address` for optional fields; pair it with `#[serde(default)]`.
+pub fn email_address_opt<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    trim_opt(deserializer).map(|value| value.map(|value| value.to_lowercase()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emails_follow_rfc_5322_with_a_dotted_domain() {
+        for valid in [
+            "ada@example.com",
+            "first.last+tag@sub.example.co",
+            "\"a b\"@example.com",
+        ] {
+            assert!(is_valid_email(valid), "{valid}");
+        }
+        for invalid in [
+            "nope",
+            "a@b",
+            "a@@example.com",
+            "a..b@example.com",
+            ".a@example.com",
+            "Ada <ada@example.com>",
+            "a@[127.0.0.1]",
+            "a@-example.com",
+        ] {
+            assert!(!is_valid_email(invalid), "{invalid}");
+        }
+        let long = format!("{}@example.com", "a".repeat(250));
+        assert!(!is_valid_email(&long));
+    }
+
+    #[derive(Debug, Deserialize, Validate)]
+    struct Sample {
+        #[serde(deserialize_with = "trim")]
+        #[validate(length(min = 1, max = 5))]
+        display_name: String,
+        #[serde(default, deserialize_with = "email_address_opt")]
+        #[validate(custom(function = "super::email"))]
+        email: Option<String>,
+        #[serde(default, deserialize_with = "uuid_opt")]
+        author_id: Option<Uuid>,
+        #[validate(custom(function = "super::not_blank"))]
+        content: String,
+    }
+
+    fn sample(json: serde_json::Value) -> Sample {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn input_is_normalized_before_the_rules_run() {
+        let input = sample(serde_json::json!({
+            "display_name": "  Ada ",
+            "email": " Ada@Example.COM ",
+            "author_id": Uuid::nil().to_string(),
+            "content": "    indented code\n",
+        }));
+
+        assert_eq!(input.display_name, "Ada");
+        assert_eq!(input.email.as_deref(), Some("ada@example.com"));
+        assert_eq!(input.author_id, Some(Uuid::nil()));
+        assert_eq!(input.content, "    indented code\n");
+        assert!(check(&input).is_ok());
+    }
+
+    #[test]
+    fn every_failed_rule_is_reported_in_field_order() {
+        let input = sample(serde_json::json!({
+            "display_name": "   ",
+            "email": "nope",
+            "content": " \n ",
+        }));
+
+        let Err(AppError::UnProcessableEntity(errors)) = check(&input) else {
+            panic!("expected field errors");
+        };
+        let reported: Vec<_> = errors
+            .iter()
+            .map(|error| (error.field.as_str(), error.code, error.message.as_str()))
+            .collect();
+        assert_eq!(
+            reported,
+            [
+                (
+                    "content",
+                    ErrorCode::ValidationRequired,
+                    "Content cannot be empty"
+                ),
+                (
+                    "display_name",
+                    ErrorCode::ValidationRequired,
+                    "Display name cannot be empty"
+                ),
+                (
+                    "email",
+                    ErrorCode::ValidationInvalidEmail,
+                    "Invalid email format"
+                ),
+            ]
+        );
+
+        let input = sample(serde_json::json!({ "display_name": "Ada Lovelace", "content": "" }));
+        let Err(AppError::UnProcessableEntity(errors)) = check(&input) else {
+            panic!("expected field errors");
+        };
+        assert_eq!(errors[1].field, "display_name");
+        assert_eq!(errors[1].code, ErrorCode::ValidationInvalidLength);
+        assert_eq!(
+            errors[1].message,
+            "Display name must be between 1 and 5 characters"
+        );
+    }
+}